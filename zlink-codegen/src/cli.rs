@@ -19,6 +19,11 @@ pub struct Args {
     /// Generate separate files for each interface (ignored if --output is specified).
     #[arg(short = 'm', long)]
     pub multiple_files: bool,
+
+    /// Split the code of each interface into types, proxy and server files, in a directory
+    /// named after the interface.
+    #[arg(short, long, requires = "multiple_files")]
+    pub split: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -36,5 +41,10 @@ pub enum Command {
         /// Generate separate files for each interface.
         #[arg(short = 'm', long)]
         multiple_files: bool,
+
+        /// Split the code of each interface into types, proxy and server files, in a directory
+        /// named after the interface.
+        #[arg(short, long, requires = "multiple_files")]
+        split: bool,
     },
 }
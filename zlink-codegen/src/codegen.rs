@@ -1,14 +1,25 @@
 //! Code generation implementation.
 
 use anyhow::Result;
-use heck::{ToPascalCase, ToSnakeCase};
+use heck::{ToPascalCase, ToShoutySnakeCase, ToSnakeCase};
 use std::fmt::Write;
-use zlink::idl::{CustomEnum, CustomObject, CustomType, Field, Interface, Method, Type};
+use zlink::idl::{
+    comments_to_doc, Comment, CustomEnum, CustomObject, CustomType, Field, Interface, Method,
+    Parameter, Type,
+};
 
 /// Code generator for Varlink interfaces.
 pub struct CodeGenerator {
     output: String,
     indent_level: usize,
+    preserve_extra_fields: bool,
+    example_docs: bool,
+    newtype_wrappers: bool,
+    introspection: bool,
+    unwrap_custom_outputs: bool,
+    stream_methods: Vec<String>,
+    unknown_enum_variants: bool,
+    visibility: String,
 }
 
 impl CodeGenerator {
@@ -17,9 +28,112 @@ impl CodeGenerator {
         Self {
             output: String::new(),
             indent_level: 0,
+            preserve_extra_fields: false,
+            example_docs: false,
+            newtype_wrappers: false,
+            introspection: false,
+            unwrap_custom_outputs: false,
+            stream_methods: Vec::new(),
+            unknown_enum_variants: false,
+            visibility: "pub".to_string(),
         }
     }
 
+    /// Set whether to preserve unknown fields of method replies.
+    ///
+    /// If enabled, the generated output structs get an `extra` field that catches all the fields
+    /// of a reply that are not part of the method's signature, instead of them being silently
+    /// dropped. This is useful for debugging forward-compatibility issues. The generated code then
+    /// requires a `serde_json` dependency. Disabled by default.
+    pub fn set_preserve_extra_fields(mut self, preserve_extra_fields: bool) -> Self {
+        self.preserve_extra_fields = preserve_extra_fields;
+        self
+    }
+
+    /// Set whether to add an example to the module documentation.
+    ///
+    /// If enabled, the module-level documentation of each interface gets an `# Example` section,
+    /// showing how to connect to a service and call the first method of the interface through the
+    /// generated proxy trait. Disabled by default.
+    pub fn set_example_docs(mut self, example_docs: bool) -> Self {
+        self.example_docs = example_docs;
+        self
+    }
+
+    /// Set whether to generate newtypes for custom types with a single field.
+    ///
+    /// If enabled, a type like `type UserId (value: string)` results in a newtype,
+    /// `pub struct UserId(pub String)`, instead of a struct with a `value` field. The newtype
+    /// still (de)serializes as an object with the single field, e.g `{"value": "..."}`, as the
+    /// IDL defines. Disabled by default.
+    pub fn set_newtype_wrappers(mut self, newtype_wrappers: bool) -> Self {
+        self.newtype_wrappers = newtype_wrappers;
+        self
+    }
+
+    /// Set whether to implement the introspection traits for the generated types.
+    ///
+    /// If enabled, the custom types derive `zlink::introspect::CustomType`, the method output
+    /// structs derive `zlink::introspect::Type` and the error types derive
+    /// `zlink::introspect::ReplyError`, so a service can describe its interface from the generated
+    /// types. The generated code then requires the `introspection` feature of `zlink`. Inline
+    /// enums and objects are represented as `String` and `serde_json::Value` respectively, so
+    /// their introspected types don't match the IDL. Disabled by default.
+    pub fn set_introspection(mut self, introspection: bool) -> Self {
+        self.introspection = introspection;
+        self
+    }
+
+    /// Set whether proxy methods with a single custom-typed output return that type directly.
+    ///
+    /// If enabled, the proxy method of a method like `method GetUser(id: int) -> (user: User)`
+    /// returns `User` directly, reading it from the `user` reply parameter, instead of a
+    /// `GetUserOutput`. This only affects the proxy: the `GetUserOutput` struct is still generated
+    /// and the handler method still returns it, so that the reply has the `user` parameter.
+    /// Disabled by default.
+    pub fn set_unwrap_custom_outputs(mut self, unwrap_custom_outputs: bool) -> Self {
+        self.unwrap_custom_outputs = unwrap_custom_outputs;
+        self
+    }
+
+    /// Set the methods to generate streaming variants of the proxy methods for.
+    ///
+    /// Each listed method like `Monitor` gets two more proxy methods: `monitor_more`, which calls
+    /// it with the `more` flag and yields each reply as a nested result, and `monitor_stream`,
+    /// which flattens each of these into a `Result<MonitorOutput, {Interface}StreamError>`.
+    /// Since the IDL doesn't tell which methods support `more`, the methods are listed by their
+    /// IDL name, e.g. `Monitor`. If any are listed, the generated code requires a
+    /// `futures-util` dependency. Empty by default.
+    pub fn set_stream_methods<I, S>(mut self, stream_methods: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.stream_methods = stream_methods.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set whether to add a catch-all `Unknown` variant to the generated enums.
+    ///
+    /// If enabled, each enum generated for an IDL enum type gets an `Unknown` variant, marked with
+    /// `#[serde(other)]`, that any value not known to the generated code deserializes to. This
+    /// way, a service adding a new value to an enum doesn't break older clients. Note that
+    /// `Unknown` itself serializes as `"unknown"`, and that it conflicts with an IDL variant of
+    /// the same name. Disabled by default.
+    pub fn set_unknown_enum_variants(mut self, unknown_enum_variants: bool) -> Self {
+        self.unknown_enum_variants = unknown_enum_variants;
+        self
+    }
+
+    /// Set the visibility of the generated structs, enums and traits.
+    ///
+    /// This is useful to keep the generated API internal to a library, e.g with `pub(crate)`, and
+    /// only expose a curated API on top of it. Defaults to `pub`.
+    pub fn set_visibility(mut self, visibility: impl Into<String>) -> Self {
+        self.visibility = visibility.into();
+        self
+    }
+
     /// Get the generated output.
     pub fn output(self) -> String {
         self.output
@@ -55,9 +169,58 @@ impl CodeGenerator {
         }
 
         // Generate proxy trait using the proxy macro.
-        self.generate_proxy_trait(interface)?;
+        self.generate_proxy_only(interface)?;
+        self.writeln("")?;
+
+        // Generate the lists of method and error names.
+        self.generate_member_name_consts(interface, skip_module_header)?;
+        self.writeln("")?;
+
+        self.generate_types_only(interface)?;
+
+        Ok(())
+    }
+
+    /// Generate only the `METHODS` and `ERRORS` consts of an interface.
+    ///
+    /// These list the fully-qualified names of the methods and errors of the interface. They're
+    /// part of the code generated by [`CodeGenerator::generate_interface`] as well.
+    pub fn generate_member_names_only(&mut self, interface: &Interface<'_>) -> Result<()> {
+        self.generate_member_name_consts(interface, false)
+    }
+
+    /// Generate the `mod.rs` of an interface split into multiple files.
+    ///
+    /// This includes the module documentation and declares the `types`, `proxy` and `server`
+    /// modules, re-exporting all their items. See [`crate::generate_split`].
+    pub fn generate_split_mod(&mut self, interface: &Interface<'_>) -> Result<()> {
+        self.write_header(interface)?;
+        for module in ["types", "proxy", "server"] {
+            self.writeln(&format!("mod {module};"))?;
+        }
         self.writeln("")?;
+        for module in ["types", "proxy", "server"] {
+            self.writeln(&format!("pub use {module}::*;"))?;
+        }
 
+        Ok(())
+    }
+
+    /// Generate a `VERSION` constant, for clients to check compatibility with.
+    pub fn generate_version_const(&mut self, version: &str) -> Result<()> {
+        self.writeln("/// The version of the interface this code was generated from.")?;
+        self.writeln(&format!("pub const VERSION: &str = {version:?};"))?;
+        self.writeln("")?;
+
+        Ok(())
+    }
+
+    /// Generate only the types of an interface.
+    ///
+    /// This includes the output structs of the methods, the custom types and the error type. No
+    /// imports are generated, so the caller is responsible for importing `serde::{Deserialize,
+    /// Serialize}` and `zlink::ReplyError`.
+    pub fn generate_types_only(&mut self, interface: &Interface<'_>) -> Result<()> {
         // Generate output structs for methods.
         self.generate_output_structs(interface)?;
 
@@ -68,11 +231,185 @@ impl CodeGenerator {
         }
 
         // Generate errors.
-        if interface.errors().count() > 0 {
+        if interface.error_count() > 0 {
             self.generate_errors(interface)?;
             self.writeln("")?;
+            self.generate_error_impls(interface)?;
+            self.writeln("")?;
+        }
+
+        Ok(())
+    }
+
+    /// Generate only the client-side proxy trait of an interface.
+    ///
+    /// The proxy refers to the types generated by [`CodeGenerator::generate_types_only`]. If the
+    /// interface has no errors, a stub error type is generated along with the trait. No imports are
+    /// generated, so the caller is responsible for importing `zlink::{proxy, ReplyError}`.
+    pub fn generate_proxy_only(&mut self, interface: &Interface<'_>) -> Result<()> {
+        self.generate_proxy_trait(interface)
+    }
+
+    /// Generate only the server-side types of an interface.
+    ///
+    /// This generates a `{Interface}Method` enum that deserializes itself from method calls of the
+    /// interface, suitable for use as the `MethodCall` of a `zlink::Service` implementation, and a
+    /// `{Interface}Handler` trait with a method for each method of the interface. All the trait
+    /// methods reply with the standard `MethodNotImplemented` error by default, so implementors
+    /// only need to override the methods they support. These refer to the types generated by
+    /// [`CodeGenerator::generate_types_only`]. No imports are generated, so the caller is
+    /// responsible for importing `serde::{Deserialize, Serialize}`.
+    pub fn generate_handler_only(&mut self, interface: &Interface<'_>) -> Result<()> {
+        self.writeln("/// Method calls of the interface, for the service side.")?;
+        self.writeln("#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]")?;
+        self.writeln("#[serde(tag = \"method\", content = \"parameters\")]")?;
+        self.writeln(&format!(
+            "{} enum {}Method {{",
+            self.visibility,
+            interface_name_to_rust(interface.name())
+        ))?;
+        self.indent();
+
+        for method in interface.methods() {
+            // Add method comments.
+            self.write_doc(method.comments().chain(method.trailing_comments()))?;
+
+            self.writeln(&format!(
+                "#[serde(rename = \"{}\")]",
+                method.fully_qualified_name(interface.name())
+            ))?;
+            let variant_name = method.name().to_pascal_case();
+            if method.has_no_inputs() {
+                self.writeln(&format!("{},", variant_name))?;
+            } else {
+                self.writeln(&format!("{} {{", variant_name))?;
+                self.indent();
+                for input in method.inputs() {
+                    self.generate_handler_field(input)?;
+                }
+                self.dedent();
+                self.writeln("},")?;
+            }
+        }
+
+        self.dedent();
+        self.writeln("}")?;
+        self.writeln("")?;
+
+        self.generate_handler_trait(interface)?;
+
+        Ok(())
+    }
+
+    fn generate_handler_trait(&mut self, interface: &Interface<'_>) -> Result<()> {
+        let name = interface_name_to_rust(interface.name());
+        let error_type = format!("{name}HandlerError");
+
+        self.writeln(&format!(
+            "/// Errors returned by the methods of [`{name}Handler`]."
+        ))?;
+        self.writeln("#[derive(Debug, Clone, PartialEq, Serialize)]")?;
+        self.writeln("#[serde(untagged)]")?;
+        self.writeln(&format!("{} enum {error_type} {{", self.visibility))?;
+        self.indent();
+        if interface.error_count() > 0 {
+            self.writeln("/// An error of the interface.")?;
+            self.writeln(&format!("Interface({name}Error),"))?;
+        }
+        self.writeln("/// A standard `org.varlink.service` error.")?;
+        self.writeln("VarlinkService(zlink::varlink_service::Error),")?;
+        self.dedent();
+        self.writeln("}")?;
+        self.writeln("")?;
+
+        self.writeln("/// Handler of the method calls of the interface, for the service side.")?;
+        self.writeln("///")?;
+        self.writeln(
+            "/// All methods reply with the standard `MethodNotImplemented` error by default, so",
+        )?;
+        self.writeln("/// only the supported methods need to be implemented.")?;
+        self.writeln(&format!("{} trait {name}Handler {{", self.visibility))?;
+        self.indent();
+
+        let mut first = true;
+        for method in interface.methods() {
+            if !first {
+                self.writeln("")?;
+            }
+            first = false;
+            self.generate_handler_method(interface, method, &error_type)?;
+        }
+
+        self.dedent();
+        self.writeln("}")?;
+
+        Ok(())
+    }
+
+    fn generate_handler_method(
+        &mut self,
+        interface: &Interface<'_>,
+        method: &Method<'_>,
+        error_type: &str,
+    ) -> Result<()> {
+        self.write_doc(method.comments().chain(method.trailing_comments()))?;
+
+        let method_name = method.name().to_snake_case();
+        let safe_method_name = if is_rust_keyword(&method_name) {
+            format!("r#{}", method_name)
+        } else {
+            method_name
+        };
+
+        let mut signature = format!("fn {}(&mut self", safe_method_name);
+        for param in method.inputs() {
+            let param_name = param.name().to_snake_case();
+            let safe_param_name = if is_rust_keyword(&param_name) {
+                format!("r#{}", param_name)
+            } else {
+                param_name
+            };
+            let rust_type = self.type_to_rust(param.ty())?;
+            write!(&mut signature, ", {}: {}", safe_param_name, rust_type)?;
         }
 
+        let output = if method.has_no_outputs() {
+            "()".to_string()
+        } else {
+            let struct_name = format!("{}Output", method.name().to_pascal_case());
+            if method.outputs().any(|o| type_needs_lifetime(o.ty())) {
+                format!("{}<'_>", struct_name)
+            } else {
+                struct_name
+            }
+        };
+        write!(
+            &mut signature,
+            ") -> impl core::future::Future<Output = Result<{output}, {error_type}>> {{"
+        )?;
+
+        if !method.has_no_inputs() {
+            self.writeln("#[allow(unused_variables)]")?;
+        }
+        self.writeln(&signature)?;
+        self.indent();
+        self.writeln(&format!(
+            "core::future::ready(Err({error_type}::VarlinkService("
+        ))?;
+        self.indent();
+        self.writeln("zlink::varlink_service::Error::MethodNotImplemented {")?;
+        self.indent();
+        self.writeln(&format!(
+            "method: \"{}\".parse().unwrap(),",
+            method.fully_qualified_name(interface.name())
+        ))?;
+        self.dedent();
+        self.writeln("},")?;
+        self.dedent();
+        self.writeln(")))")?;
+        self.dedent();
+        self.writeln("}")?;
+
         Ok(())
     }
 
@@ -104,14 +441,57 @@ impl CodeGenerator {
         writeln!(&mut self.output)?;
 
         // Add interface comments if any.
-        for comment in interface.comments() {
-            writeln!(&mut self.output, "//! {}", comment.text())?;
+        for line in comments_to_doc(interface.comments()).lines() {
+            writeln!(&mut self.output, "//! {line}")?;
+        }
+        if self.example_docs {
+            self.write_example_doc(interface)?;
         }
         writeln!(&mut self.output)?;
 
         Ok(())
     }
 
+    fn write_example_doc(&mut self, interface: &Interface<'_>) -> Result<()> {
+        let Some(method) = interface.methods().next() else {
+            return Ok(());
+        };
+        let trait_name = interface_name_to_rust(interface.name());
+        let safe_name = |name: String| {
+            if is_rust_keyword(&name) {
+                format!("r#{}", name)
+            } else {
+                name
+            }
+        };
+        let args = method
+            .inputs()
+            .map(|param| safe_name(param.name().to_snake_case()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(&mut self.output, "//!")?;
+        writeln!(&mut self.output, "//! # Example")?;
+        writeln!(&mut self.output, "//!")?;
+        writeln!(&mut self.output, "//! ```rust,ignore")?;
+        writeln!(
+            &mut self.output,
+            "//! // With the `{trait_name}` trait in scope:"
+        )?;
+        writeln!(
+            &mut self.output,
+            "//! let mut conn = zlink::unix::connect(\"/path/to/socket\").await?;"
+        )?;
+        writeln!(
+            &mut self.output,
+            "//! let reply = conn.{}({args}).await??;",
+            safe_name(method.name().to_snake_case())
+        )?;
+        writeln!(&mut self.output, "//! ```")?;
+
+        Ok(())
+    }
+
     fn generate_custom_type(&mut self, custom_type: &CustomType<'_>) -> Result<()> {
         match custom_type {
             CustomType::Object(obj) => self.generate_custom_object(obj),
@@ -121,12 +501,18 @@ impl CodeGenerator {
 
     fn generate_custom_object(&mut self, obj: &CustomObject<'_>) -> Result<()> {
         // Add comments.
-        for comment in obj.comments() {
-            self.writeln(&format!("/// {}", comment.text()))?;
-        }
+        self.write_doc(obj.comments())?;
 
-        self.writeln("#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]")?;
-        self.writeln(&format!("pub struct {} {{", obj.name().to_pascal_case()))?;
+        if self.newtype_wrappers && obj.fields().count() == 1 {
+            self.writeln("#[derive(Debug, Clone, PartialEq)]")?;
+            return self.generate_newtype(obj);
+        }
+        self.writeln(&self.type_derive("zlink::introspect::CustomType"))?;
+        self.writeln(&format!(
+            "{} struct {} {{",
+            self.visibility,
+            obj.name().to_pascal_case()
+        ))?;
         self.indent();
 
         for field in obj.fields() {
@@ -139,28 +525,146 @@ impl CodeGenerator {
         Ok(())
     }
 
+    fn generate_newtype(&mut self, obj: &CustomObject<'_>) -> Result<()> {
+        let field = obj.fields().next().unwrap();
+        let name = obj.name().to_pascal_case();
+        let rust_type = self.type_to_rust(field.ty())?;
+
+        if field.comments().next().is_none() {
+            self.writeln(&format!(
+                "{} struct {name}(pub {rust_type});",
+                self.visibility
+            ))?;
+        } else {
+            self.writeln(&format!("{} struct {name}(", self.visibility))?;
+            self.indent();
+            self.write_doc(field.comments())?;
+            self.writeln(&format!("pub {rust_type},"))?;
+            self.dedent();
+            self.writeln(");")?;
+        }
+
+        // The newtype is (de)serialized as an object with its single field, like the IDL type.
+        self.writeln("")?;
+        self.writeln(&format!("impl serde::Serialize for {name} {{"))?;
+        self.indent();
+        self.writeln(
+            "fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>",
+        )?;
+        self.writeln("where")?;
+        self.indent();
+        self.writeln("S: serde::Serializer,")?;
+        self.dedent();
+        self.writeln("{")?;
+        self.indent();
+        self.writeln("use serde::ser::SerializeStruct;")?;
+        self.writeln("")?;
+        self.writeln(&format!(
+            "let mut object = serializer.serialize_struct(\"{name}\", 1)?;"
+        ))?;
+        self.writeln(&format!(
+            "object.serialize_field(\"{}\", &self.0)?;",
+            field.name()
+        ))?;
+        self.writeln("object.end()")?;
+        self.dedent();
+        self.writeln("}")?;
+        self.dedent();
+        self.writeln("}")?;
+        self.writeln("")?;
+        self.writeln(&format!("impl<'de> serde::Deserialize<'de> for {name} {{"))?;
+        self.indent();
+        self.writeln("fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>")?;
+        self.writeln("where")?;
+        self.indent();
+        self.writeln("D: serde::Deserializer<'de>,")?;
+        self.dedent();
+        self.writeln("{")?;
+        self.indent();
+        self.writeln("#[derive(serde::Deserialize)]")?;
+        self.writeln("struct Object {")?;
+        self.indent();
+        self.writeln(&format!("#[serde(rename = \"{}\")]", field.name()))?;
+        self.writeln(&format!("value: {rust_type},"))?;
+        self.dedent();
+        self.writeln("}")?;
+        self.writeln("")?;
+        self.writeln("Object::deserialize(deserializer).map(|object| Self(object.value))")?;
+        self.dedent();
+        self.writeln("}")?;
+        self.dedent();
+        self.writeln("}")?;
+
+        if self.introspection {
+            // The derive macro doesn't support tuple structs.
+            self.writeln("")?;
+            self.writeln(&format!("impl zlink::introspect::CustomType for {name} {{"))?;
+            self.indent();
+            self.writeln("const CUSTOM_TYPE: &'static zlink::idl::CustomType<'static> = &{")?;
+            self.indent();
+            self.writeln("static FIELD: zlink::idl::Field<'static> = zlink::idl::Field::new(")?;
+            self.indent();
+            self.writeln(&format!("\"{}\",", field.name()))?;
+            self.writeln(&format!("<{rust_type} as zlink::introspect::Type>::TYPE,"))?;
+            self.writeln("&[],")?;
+            self.dedent();
+            self.writeln(");")?;
+            self.writeln("static FIELDS: &[&zlink::idl::Field<'static>] = &[&FIELD];")?;
+            self.writeln("")?;
+            self.writeln("zlink::idl::CustomType::Object(zlink::idl::CustomObject::new(")?;
+            self.indent();
+            self.writeln(&format!("\"{name}\","))?;
+            self.writeln("FIELDS,")?;
+            self.writeln("&[],")?;
+            self.dedent();
+            self.writeln("))")?;
+            self.dedent();
+            self.writeln("};")?;
+            self.dedent();
+            self.writeln("}")?;
+            self.writeln("")?;
+            self.writeln(&format!("impl zlink::introspect::Type for {name} {{"))?;
+            self.indent();
+            self.writeln("const TYPE: &'static zlink::idl::Type<'static> =")?;
+            self.indent();
+            self.writeln(&format!("&zlink::idl::Type::Custom(\"{name}\");"))?;
+            self.dedent();
+            self.dedent();
+            self.writeln("}")?;
+        }
+
+        Ok(())
+    }
+
     fn generate_custom_enum(&mut self, enum_type: &CustomEnum<'_>) -> Result<()> {
         // Add comments.
-        for comment in enum_type.comments() {
-            self.writeln(&format!("/// {}", comment.text()))?;
-        }
+        self.write_doc(enum_type.comments())?;
 
-        self.writeln("#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]")?;
+        self.writeln(&self.type_derive("zlink::introspect::CustomType"))?;
         self.writeln("#[serde(rename_all = \"snake_case\")]")?;
         self.writeln(&format!(
-            "pub enum {} {{",
+            "{} enum {} {{",
+            self.visibility,
             enum_type.name().to_pascal_case()
         ))?;
         self.indent();
 
         for variant in enum_type.variants() {
             // Add variant comments.
-            for comment in variant.comments() {
-                self.writeln(&format!("/// {}", comment.text()))?;
-            }
+            self.write_doc(variant.comments())?;
 
             // Varlink enum variants don't have explicit values, just names.
-            self.writeln(&format!("{},", variant.name().to_pascal_case()))?;
+            let variant_name = variant.name().to_pascal_case();
+            if self.introspection && variant_name != variant.name() {
+                self.writeln(&format!("#[zlink(rename = \"{}\")]", variant.name()))?;
+            }
+            self.writeln(&format!("{},", variant_name))?;
+        }
+
+        if self.unknown_enum_variants {
+            self.writeln("/// A value unknown to this version of the interface.")?;
+            self.writeln("#[serde(other)]")?;
+            self.writeln("Unknown,")?;
         }
 
         self.dedent();
@@ -171,9 +675,7 @@ impl CodeGenerator {
 
     fn generate_field(&mut self, field: &Field<'_>) -> Result<()> {
         // Add field comments.
-        for comment in field.comments() {
-            self.writeln(&format!("/// {}", comment.text()))?;
-        }
+        self.write_doc(field.comments())?;
 
         let field_name = field.name().to_snake_case();
         let rust_type = self.type_to_rust(field.ty())?;
@@ -195,6 +697,9 @@ impl CodeGenerator {
 
         if !field_name_attr.is_empty() {
             self.writeln(&field_name_attr)?;
+            if self.introspection {
+                self.writeln(&format!("#[zlink(rename = \"{}\")]", field.name()))?;
+            }
         }
 
         let safe_field_name = if is_rust_keyword(&field_name) {
@@ -210,19 +715,18 @@ impl CodeGenerator {
 
     fn generate_errors(&mut self, interface: &Interface<'_>) -> Result<()> {
         self.writeln("/// Errors that can occur in this interface.")?;
-        self.writeln("#[derive(Debug, Clone, PartialEq, ReplyError)]")?;
+        self.writeln(&self.error_derive())?;
         self.writeln(&format!("#[zlink(interface = \"{}\")]", interface.name()))?;
         self.writeln(&format!(
-            "pub enum {}Error {{",
+            "{} enum {}Error {{",
+            self.visibility,
             interface_name_to_rust(interface.name())
         ))?;
         self.indent();
 
         for error in interface.errors() {
             // Add error comments.
-            for comment in error.comments() {
-                self.writeln(&format!("/// {}", comment.text()))?;
-            }
+            self.write_doc(error.comments())?;
 
             let variant_name = error.name().to_pascal_case();
             if error.fields().count() == 0 {
@@ -244,6 +748,75 @@ impl CodeGenerator {
         Ok(())
     }
 
+    /// Generate `Display` and `std::error::Error` impls for the errors of the `interface`.
+    ///
+    /// The errors are displayed with all their fields, e.g `invalid input: field="name"`, so
+    /// that they're informative when wrapped by error handling crates, such as `anyhow`.
+    fn generate_error_impls(&mut self, interface: &Interface<'_>) -> Result<()> {
+        let enum_name = format!("{}Error", interface_name_to_rust(interface.name()));
+
+        self.writeln(&format!("impl core::fmt::Display for {} {{", enum_name))?;
+        self.indent();
+        self.writeln("fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {")?;
+        self.indent();
+        self.writeln("match self {")?;
+        self.indent();
+
+        for error in interface.errors() {
+            let variant_name = error.name().to_pascal_case();
+            let description = error.name().to_snake_case().replace('_', " ");
+            if error.fields().count() == 0 {
+                self.writeln(&format!(
+                    "Self::{} => write!(f, \"{}\"),",
+                    variant_name, description
+                ))?;
+                continue;
+            }
+
+            let bindings: Vec<String> = error
+                .fields()
+                .map(|field| {
+                    let field_name = field.name().to_snake_case();
+                    if is_rust_keyword(&field_name) {
+                        format!("r#{}", field_name)
+                    } else {
+                        field_name
+                    }
+                })
+                .collect();
+            let format = error
+                .fields()
+                .map(|field| format!("{}={{:?}}", field.name()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.writeln(&format!(
+                "Self::{} {{ {} }} => {{",
+                variant_name,
+                bindings.join(", ")
+            ))?;
+            self.indent();
+            self.writeln(&format!(
+                "write!(f, \"{}: {}\", {})",
+                description,
+                format,
+                bindings.join(", ")
+            ))?;
+            self.dedent();
+            self.writeln("}")?;
+        }
+
+        self.dedent();
+        self.writeln("}")?;
+        self.dedent();
+        self.writeln("}")?;
+        self.dedent();
+        self.writeln("}")?;
+        self.writeln("")?;
+        self.writeln(&format!("impl std::error::Error for {} {{}}", enum_name))?;
+
+        Ok(())
+    }
+
     /// Generate output structs for all methods in the `interface`.
     fn generate_output_structs(&mut self, interface: &Interface<'_>) -> Result<()> {
         for method in interface.methods() {
@@ -262,11 +835,19 @@ impl CodeGenerator {
                 // Add lifetime parameter for output structs that need it
                 let needs_lifetime = method.outputs().any(|o| type_needs_lifetime(o.ty()));
 
-                self.writeln("#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]")?;
+                // The extra fields can't be introspected.
+                if self.preserve_extra_fields {
+                    self.writeln("#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]")?;
+                } else {
+                    self.writeln(&self.type_derive("zlink::introspect::Type"))?;
+                }
                 if needs_lifetime {
-                    self.writeln(&format!("pub struct {}<'a> {{", struct_name))?;
+                    self.writeln(&format!(
+                        "{} struct {}<'a> {{",
+                        self.visibility, struct_name
+                    ))?;
                 } else {
-                    self.writeln(&format!("pub struct {} {{", struct_name))?;
+                    self.writeln(&format!("{} struct {} {{", self.visibility, struct_name))?;
                 }
                 self.indent();
 
@@ -286,6 +867,9 @@ impl CodeGenerator {
 
                     if field_name != output.name() {
                         self.writeln(&format!("#[serde(rename = \"{}\")]", output.name()))?;
+                        if self.introspection && !self.preserve_extra_fields {
+                            self.writeln(&format!("#[zlink(rename = \"{}\")]", output.name()))?;
+                        }
                     }
 
                     let safe_field_name = if is_rust_keyword(&field_name) {
@@ -297,6 +881,14 @@ impl CodeGenerator {
                     self.writeln(&format!("pub {}: {},", safe_field_name, rust_type))?;
                 }
 
+                if self.preserve_extra_fields {
+                    self.writeln("/// Fields of the reply not part of the method signature.")?;
+                    self.writeln("#[serde(flatten)]")?;
+                    self.writeln(
+                        "pub extra: serde_json::Map<std::string::String, serde_json::Value>,",
+                    )?;
+                }
+
                 self.dedent();
                 self.writeln("}")?;
                 self.writeln("")?;
@@ -306,11 +898,57 @@ impl CodeGenerator {
         Ok(())
     }
 
+    /// Generate `METHODS` and `ERRORS` consts, listing the fully-qualified member names.
+    ///
+    /// If `prefixed` is `true`, the const names are prefixed with the interface name to avoid
+    /// conflicts when multiple interfaces are generated in the same module.
+    fn generate_member_name_consts(
+        &mut self,
+        interface: &Interface<'_>,
+        prefixed: bool,
+    ) -> Result<()> {
+        let prefix = if prefixed {
+            format!(
+                "{}_",
+                interface_name_to_rust(interface.name()).to_shouty_snake_case()
+            )
+        } else {
+            String::new()
+        };
+
+        self.writeln("/// The fully-qualified names of all methods of the interface.")?;
+        self.writeln(&format!("pub const {prefix}METHODS: &[&str] = &["))?;
+        self.indent();
+        for method in interface.methods() {
+            self.writeln(&format!(
+                "\"{}\",",
+                method.fully_qualified_name(interface.name())
+            ))?;
+        }
+        self.dedent();
+        self.writeln("];")?;
+        self.writeln("")?;
+
+        self.writeln("/// The fully-qualified names of all errors of the interface.")?;
+        self.writeln(&format!("pub const {prefix}ERRORS: &[&str] = &["))?;
+        self.indent();
+        for error in interface.errors() {
+            self.writeln(&format!(
+                "\"{}\",",
+                error.fully_qualified_name(interface.name())
+            ))?;
+        }
+        self.dedent();
+        self.writeln("];")?;
+
+        Ok(())
+    }
+
     fn generate_proxy_trait(&mut self, interface: &Interface<'_>) -> Result<()> {
         let trait_name = interface_name_to_rust(interface.name());
 
         // Generate a stub error type if there are no errors in the interface
-        let error_type = if interface.errors().count() > 0 {
+        let error_type = if interface.error_count() > 0 {
             format!("{}Error", interface_name_to_rust(interface.name()))
         } else {
             // Generate a stub error type for interfaces without errors
@@ -321,21 +959,47 @@ impl CodeGenerator {
             self.writeln("///")?;
             self.writeln("/// This is an empty enum that can never be instantiated.")?;
             self.writeln("/// It exists only to satisfy the proxy trait requirements.")?;
-            self.writeln("#[derive(Debug, Clone, PartialEq, ReplyError)]")?;
+            self.writeln(&self.error_derive())?;
             self.writeln(&format!("#[zlink(interface = \"{}\")]", interface.name()))?;
-            self.writeln(&format!("pub enum {} {{}}", stub_error_name))?;
+            self.writeln(&format!(
+                "{} enum {} {{}}",
+                self.visibility, stub_error_name
+            ))?;
             self.writeln("")?;
 
             stub_error_name
         };
 
+        let stream_error_type = format!("{}StreamError", trait_name);
+        let has_stream_methods = interface.methods().any(|m| self.is_stream_method(m));
+        if has_stream_methods {
+            self.writeln("/// The error of a reply in the streams returned by the proxy.")?;
+            self.writeln("#[derive(Debug)]")?;
+            self.writeln(&format!(
+                "{} enum {} {{",
+                self.visibility, stream_error_type
+            ))?;
+            self.indent();
+            self.writeln("/// The connection failed.")?;
+            self.writeln("Connection(zlink::Error),")?;
+            self.writeln("/// The service replied with an error.")?;
+            self.writeln(&format!("Reply({}),", error_type))?;
+            self.dedent();
+            self.writeln("}")?;
+            self.writeln("")?;
+            self.generate_stream_error_impls(interface, &stream_error_type)?;
+        }
+
         self.writeln("/// Proxy trait for calling methods on the interface.")?;
         self.writeln(&format!("#[proxy(\"{}\")]", interface.name()))?;
-        self.writeln(&format!("pub trait {} {{", trait_name))?;
+        self.writeln(&format!("{} trait {} {{", self.visibility, trait_name))?;
         self.indent();
 
         for method in interface.methods() {
             self.generate_proxy_method_signature(method, &error_type)?;
+            if self.is_stream_method(method) {
+                self.generate_proxy_stream_methods(method, &error_type, &stream_error_type)?;
+            }
         }
 
         self.dedent();
@@ -344,14 +1008,70 @@ impl CodeGenerator {
         Ok(())
     }
 
+    fn generate_stream_error_impls(
+        &mut self,
+        interface: &Interface<'_>,
+        stream_error_type: &str,
+    ) -> Result<()> {
+        // The stub error type of an interface without errors can't be instantiated.
+        let (reply_display, reply_source) = if interface.error_count() > 0 {
+            ("write!(f, \"{e}\")", "Some(e)")
+        } else {
+            ("match *e {}", "match *e {}")
+        };
+
+        self.writeln(&format!(
+            "impl core::fmt::Display for {stream_error_type} {{"
+        ))?;
+        self.indent();
+        self.writeln("fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {")?;
+        self.indent();
+        self.writeln("match self {")?;
+        self.indent();
+        self.writeln("Self::Connection(e) => write!(f, \"{e}\"),")?;
+        self.writeln(&format!("Self::Reply(e) => {reply_display},"))?;
+        self.dedent();
+        self.writeln("}")?;
+        self.dedent();
+        self.writeln("}")?;
+        self.dedent();
+        self.writeln("}")?;
+        self.writeln("")?;
+        self.writeln(&format!(
+            "impl std::error::Error for {stream_error_type} {{"
+        ))?;
+        self.indent();
+        self.writeln("fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {")?;
+        self.indent();
+        self.writeln("match self {")?;
+        self.indent();
+        self.writeln("Self::Connection(e) => Some(e),")?;
+        self.writeln(&format!("Self::Reply(e) => {reply_source},"))?;
+        self.dedent();
+        self.writeln("}")?;
+        self.dedent();
+        self.writeln("}")?;
+        self.dedent();
+        self.writeln("}")?;
+        self.writeln("")?;
+
+        Ok(())
+    }
+
     fn generate_proxy_method_signature(
         &mut self,
         method: &Method<'_>,
         error_type: &str,
     ) -> Result<()> {
         // Add method comments.
-        for comment in method.comments() {
-            self.writeln(&format!("/// {}", comment.text()))?;
+        self.write_doc(method.comments().chain(method.trailing_comments()))?;
+        match method.deprecation_note() {
+            Some("") => self.writeln("#[deprecated]")?,
+            Some(note) => self.writeln(&format!("#[deprecated(note = {:?})]", note))?,
+            None => (),
+        }
+        if let Some(output) = self.direct_output(method) {
+            self.writeln(&format!("#[zlink(output = \"{}\")]", output.name()))?;
         }
 
         let method_name = method.name().to_snake_case();
@@ -360,63 +1080,155 @@ impl CodeGenerator {
         } else {
             method_name
         };
+        let params = self.proxy_method_params(method, true)?;
+        let output = self.proxy_output_type(method)?;
+
+        self.writeln(&format!(
+            "async fn {safe_method_name}(&mut self{params}) -> zlink::Result<Result<{output}, \
+             {error_type}>>;"
+        ))?;
+
+        Ok(())
+    }
+
+    fn generate_proxy_stream_methods(
+        &mut self,
+        method: &Method<'_>,
+        error_type: &str,
+        stream_error_type: &str,
+    ) -> Result<()> {
+        let method_name = method.name().to_snake_case();
+        let output = self.proxy_output_type(method)?;
+        let args = method
+            .inputs()
+            .map(proxy_param_name)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // The streaming method itself.
+        let mut attrs = format!("rename = \"{}\", more", method.name());
+        if let Some(output) = self.direct_output(method) {
+            write!(&mut attrs, ", output = \"{}\"", output.name())?;
+        }
+        let params = self.proxy_method_params(method, true)?;
+        self.writeln(&format!(
+            "/// Call `{}`, receiving all its replies.",
+            method.name()
+        ))?;
+        self.writeln(&format!("#[zlink({attrs})]"))?;
+        self.writeln(&format!(
+            "async fn {method_name}_more(&mut self{params}) -> zlink::Result<impl \
+             futures_util::Stream<Item = zlink::Result<Result<{output}, {error_type}>>>>;"
+        ))?;
+
+        // A helper flattening the result of each reply. Being a helper, its parameters can't have
+        // any `zlink` attributes.
+        let params = self.proxy_method_params(method, false)?;
+        self.writeln(&format!(
+            "/// Call `{}`, receiving all its replies as a flat stream.",
+            method.name()
+        ))?;
+        self.writeln("///")?;
+        self.writeln(&format!(
+            "/// Unlike [`Self::{method_name}_more`], each reply is either the output or a \
+             [`{stream_error_type}`]."
+        ))?;
+        self.writeln("#[zlink(skip)]")?;
+        self.writeln(&format!(
+            "async fn {method_name}_stream(&mut self{params}) -> zlink::Result<impl \
+             futures_util::Stream<Item = Result<{output}, {stream_error_type}>>> {{"
+        ))?;
+        self.indent();
+        self.writeln(&format!(
+            "let replies = self.{method_name}_more({args}).await?;"
+        ))?;
+        self.writeln("Ok(futures_util::StreamExt::map(replies, |reply| match reply {")?;
+        self.indent();
+        self.writeln("Ok(Ok(output)) => Ok(output),")?;
+        self.writeln(&format!(
+            "Ok(Err(e)) => Err({stream_error_type}::Reply(e)),"
+        ))?;
+        self.writeln(&format!(
+            "Err(e) => Err({stream_error_type}::Connection(e)),"
+        ))?;
+        self.dedent();
+        self.writeln("}))")?;
+        self.dedent();
+        self.writeln("}")?;
 
-        // Generate method signature.
-        let mut signature = format!("async fn {}(&mut self", safe_method_name);
+        Ok(())
+    }
 
-        // Add input parameters.
+    /// The input parameters of the proxy method for `method`, each preceded by `, `.
+    ///
+    /// Parameters whose name differs from the Rust one get a `rename` attribute if `renames` is
+    /// set.
+    fn proxy_method_params(&self, method: &Method<'_>, renames: bool) -> Result<String> {
+        let mut params = String::new();
         for param in method.inputs() {
-            let param_name = param.name().to_snake_case();
-            let safe_param_name = if is_rust_keyword(&param_name) {
-                format!("r#{}", param_name)
-            } else {
-                param_name
-            };
+            let safe_param_name = proxy_param_name(param);
             // Use references for parameters that can be borrowed
             let rust_type = self.type_to_rust_param(param.ty())?;
 
-            write!(&mut signature, ",")?;
+            write!(&mut params, ",")?;
             // Add parameter with potential rename attribute.
-            if safe_param_name != param.name() {
-                write!(&mut signature, " #[zlink(rename = \"{}\")]", param.name(),)?;
+            if renames && safe_param_name != param.name() {
+                write!(&mut params, " #[zlink(rename = \"{}\")]", param.name(),)?;
             }
 
-            write!(&mut signature, " {}: {}", safe_param_name, rust_type)?;
+            write!(&mut params, " {}: {}", safe_param_name, rust_type)?;
         }
 
-        signature.push_str(") -> zlink::Result<Result<");
+        Ok(params)
+    }
+
+    /// The type of the successful reply of the proxy method for `method`.
+    fn proxy_output_type(&self, method: &Method<'_>) -> Result<String> {
+        if method.outputs().count() == 0 {
+            return Ok("()".to_string());
+        }
+        if let Some(output) = self.direct_output(method) {
+            return self.type_to_rust(output.ty());
+        }
 
-        // Handle output parameters.
-        let output_count = method.outputs().count();
-        if output_count == 0 {
-            signature.push_str("()");
+        // Always use the generated output struct for any outputs.
+        // Varlink output parameters are always named, so we need a struct even for single
+        // outputs.
+        let struct_name = format!("{}Output", method.name().to_pascal_case());
+        // Add lifetime parameter if the struct needs one
+        let needs_lifetime = method.outputs().any(|o| type_needs_lifetime(o.ty()));
+        if needs_lifetime {
+            Ok(format!("{}<'_>", struct_name))
         } else {
-            // Always use the generated output struct for any outputs.
-            // Varlink output parameters are always named, so we need a struct even for single
-            // outputs.
-            let struct_name = format!("{}Output", method.name().to_pascal_case());
-            // Add lifetime parameter if the struct needs one
-            let needs_lifetime = method.outputs().any(|o| type_needs_lifetime(o.ty()));
-            if needs_lifetime {
-                signature.push_str(&format!("{}<'_>", struct_name));
-            } else {
-                signature.push_str(&struct_name);
-            }
+            Ok(struct_name)
         }
+    }
 
-        write!(&mut signature, ", {}>>", error_type)?;
-        signature.push(';');
+    fn generate_handler_field(&mut self, field: &Field<'_>) -> Result<()> {
+        // Add field comments.
+        self.write_doc(field.comments())?;
 
-        self.writeln(&signature)?;
+        let field_name = field.name().to_snake_case();
+        let rust_type = self.type_to_rust(field.ty())?;
+
+        if field_name != field.name() {
+            self.writeln(&format!("#[serde(rename = \"{}\")]", field.name()))?;
+        }
+
+        let safe_field_name = if is_rust_keyword(&field_name) {
+            format!("r#{}", field_name)
+        } else {
+            field_name
+        };
+
+        self.writeln(&format!("{}: {},", safe_field_name, rust_type))?;
 
         Ok(())
     }
 
     fn generate_error_field(&mut self, field: &Field<'_>) -> Result<()> {
         // Add field comments.
-        for comment in field.comments() {
-            self.writeln(&format!("/// {}", comment.text()))?;
-        }
+        self.write_doc(field.comments())?;
 
         let field_name = field.name().to_snake_case();
         let rust_type = self.type_to_rust(field.ty())?;
@@ -443,8 +1255,46 @@ impl CodeGenerator {
         Ok(())
     }
 
+    /// Whether streaming variants of the proxy method for `method` are generated.
+    fn is_stream_method(&self, method: &Method<'_>) -> bool {
+        self.stream_methods.iter().any(|name| name == method.name())
+    }
+
+    /// The sole output of `method`, if the proxy returns it directly instead of the output struct.
+    fn direct_output<'m, 'a>(&self, method: &'m Method<'a>) -> Option<&'m Parameter<'a>> {
+        if !self.unwrap_custom_outputs {
+            return None;
+        }
+
+        let mut outputs = method.outputs();
+        match (outputs.next(), outputs.next()) {
+            (Some(output), None) if matches!(output.ty(), Type::Custom(_)) => Some(output),
+            _ => None,
+        }
+    }
+
+    /// The derive attribute of a generated type, including `introspection_derive` if enabled.
+    fn type_derive(&self, introspection_derive: &str) -> String {
+        let derives = "Debug, Clone, Serialize, Deserialize, PartialEq";
+        if self.introspection {
+            format!("#[derive({derives}, {introspection_derive})]")
+        } else {
+            format!("#[derive({derives})]")
+        }
+    }
+
+    /// The derive attribute of a generated error type.
+    fn error_derive(&self) -> String {
+        if self.introspection {
+            "#[derive(Debug, Clone, PartialEq, ReplyError, zlink::introspect::ReplyError)]"
+                .to_string()
+        } else {
+            "#[derive(Debug, Clone, PartialEq, ReplyError)]".to_string()
+        }
+    }
+
     fn type_to_rust(&self, ty: &Type) -> Result<String> {
-        type_to_rust(ty)
+        Ok(type_to_rust(ty))
     }
 
     fn type_to_rust_param(&self, ty: &Type) -> Result<String> {
@@ -455,6 +1305,21 @@ impl CodeGenerator {
         type_to_rust_output(ty)
     }
 
+    fn write_doc<'c, 'a: 'c>(
+        &mut self,
+        comments: impl IntoIterator<Item = &'c Comment<'a>>,
+    ) -> Result<()> {
+        for line in comments_to_doc(comments).lines() {
+            if line.is_empty() {
+                self.writeln("///")?;
+            } else {
+                self.writeln(&format!("/// {line}"))?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn writeln(&mut self, s: &str) -> Result<()> {
         self.write(s)?;
         writeln!(&mut self.output)?;
@@ -486,8 +1351,8 @@ impl Default for CodeGenerator {
     }
 }
 
-fn type_to_rust(ty: &Type) -> Result<String> {
-    Ok(match ty {
+pub(crate) fn type_to_rust(ty: &Type) -> String {
+    match ty {
         Type::Bool => "bool".to_string(),
         Type::Int => "i64".to_string(),
         Type::Float => "f64".to_string(),
@@ -503,20 +1368,20 @@ fn type_to_rust(ty: &Type) -> Result<String> {
             "String".to_string()
         }
         Type::Array(elem_type) => {
-            let elem_rust = type_to_rust(elem_type.inner())?;
+            let elem_rust = type_to_rust(elem_type.inner());
             format!("Vec<{}>", elem_rust)
         }
         Type::Map(value_type) => {
-            let value_rust = type_to_rust(value_type.inner())?;
+            let value_rust = type_to_rust(value_type.inner());
             format!("std::collections::HashMap<String, {}>", value_rust)
         }
         Type::ForeignObject => "serde_json::Value".to_string(),
         Type::Optional(inner_type) => {
-            let inner_rust = type_to_rust(inner_type.inner())?;
+            let inner_rust = type_to_rust(inner_type.inner());
             format!("Option<{}>", inner_rust)
         }
         Type::Custom(name) => name.to_pascal_case(),
-    })
+    }
 }
 
 fn type_to_rust_param(ty: &Type) -> Result<String> {
@@ -599,7 +1464,7 @@ fn type_to_rust_output(ty: &Type) -> Result<String> {
             let elem_rust = match elem_type.inner() {
                 Type::String => "&'a str".to_string(),
                 Type::Enum(_) => "&'a str".to_string(),
-                _ => type_to_rust(elem_type.inner())?,
+                _ => type_to_rust(elem_type.inner()),
             };
             format!("Vec<{}>", elem_rust)
         }
@@ -608,7 +1473,7 @@ fn type_to_rust_output(ty: &Type) -> Result<String> {
             let value_rust = match value_type.inner() {
                 Type::String => "&'a str".to_string(),
                 Type::Enum(_) => "&'a str".to_string(),
-                _ => type_to_rust(value_type.inner())?,
+                _ => type_to_rust(value_type.inner()),
             };
             format!("std::collections::HashMap<&'a str, {}>", value_rust)
         }
@@ -623,6 +1488,16 @@ fn type_to_rust_output(ty: &Type) -> Result<String> {
     })
 }
 
+/// The name of the Rust parameter for `param`, in a proxy method.
+fn proxy_param_name(param: &Parameter<'_>) -> String {
+    let param_name = param.name().to_snake_case();
+    if is_rust_keyword(&param_name) {
+        format!("r#{}", param_name)
+    } else {
+        param_name
+    }
+}
+
 fn interface_name_to_rust(name: &str) -> String {
     // Convert interface name like "org.example.Interface" to "Interface".
     name.split('.').next_back().unwrap_or(name).to_pascal_case()
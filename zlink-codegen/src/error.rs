@@ -0,0 +1,26 @@
+//! Errors of code generation.
+
+use std::fmt;
+
+/// Errors specific to code generation.
+///
+/// These are returned wrapped in an [`anyhow::Error`], so use [`anyhow::Error::downcast_ref`] to
+/// tell them apart from other errors.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// `rustfmt` rejected the code, usually because it's not valid Rust.
+    ///
+    /// Contains the diagnostics emitted by `rustfmt`.
+    FormatFailed(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::FormatFailed(diagnostics) => write!(f, "rustfmt failed: {diagnostics}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+
+/// The formatter to use for the generated code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Formatter {
+    /// Format using `rustfmt`.
+    ///
+    /// If `rustfmt` is not available, [`Formatter::Builtin`] is used instead.
+    #[default]
+    Rustfmt,
+    /// A lightweight built-in formatter that doesn't need any external tool.
+    ///
+    /// It only normalizes the indentation and the blank lines, so the output is consistent but not
+    /// as polished as that of `rustfmt`.
+    Builtin,
+    /// Leave the generated code as is.
+    None,
+}
+
+impl Formatter {
+    /// Format `code` using this formatter.
+    pub fn format(self, code: &str) -> Result<String> {
+        match self {
+            Formatter::Rustfmt => crate::format_code(code),
+            Formatter::Builtin => Ok(format_builtin(code)),
+            Formatter::None => Ok(code.to_string()),
+        }
+    }
+}
+
+/// Re-indent `code` based on the nesting of brackets, using 4 spaces per level.
+///
+/// Trailing whitespace is removed and consecutive blank lines are collapsed into one.
+pub(crate) fn format_builtin(code: &str) -> String {
+    let mut output = String::with_capacity(code.len());
+    let mut depth = 0usize;
+    let mut previous_blank = true;
+
+    for line in code.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            if !previous_blank {
+                output.push('\n');
+            }
+            previous_blank = true;
+            continue;
+        }
+        previous_blank = false;
+
+        let (opened, closed, leading_closers) = count_brackets(line);
+        let indent = depth.saturating_sub(leading_closers);
+        for _ in 0..indent {
+            output.push_str("    ");
+        }
+        output.push_str(line);
+        output.push('\n');
+
+        depth = (depth + opened).saturating_sub(closed);
+    }
+
+    output
+}
+
+/// Count the opening and closing brackets in `line`, as well as the closing brackets it starts
+/// with.
+///
+/// Brackets in string literals and comments are ignored.
+fn count_brackets(line: &str) -> (usize, usize, usize) {
+    let mut opened = 0;
+    let mut closed = 0;
+    let mut leading_closers = 0;
+    let mut leading = true;
+    let mut in_string = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '/' if chars.peek() == Some(&'/') => break,
+            '{' | '(' | '[' => opened += 1,
+            '}' | ')' | ']' => {
+                closed += 1;
+                if leading {
+                    leading_closers += 1;
+                }
+                continue;
+            }
+            _ => (),
+        }
+        leading = false;
+    }
+
+    (opened, closed, leading_closers)
+}
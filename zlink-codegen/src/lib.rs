@@ -4,10 +4,19 @@
 //! Code generation for Varlink interfaces.
 
 use anyhow::{Context, Result};
-use zlink::idl::Interface;
+use heck::ToSnakeCase;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use zlink::idl::{Interface, Type};
 
 mod codegen;
 pub use codegen::CodeGenerator;
+mod error;
+pub use error::Error;
+mod formatter;
+pub use formatter::Formatter;
 
 /// Generate Rust code from a Varlink interface.
 pub fn generate_interface(interface: &Interface<'_>) -> Result<String> {
@@ -33,20 +42,360 @@ pub fn generate_interfaces(interfaces: &[Interface<'_>]) -> Result<String> {
     Ok(generator.output())
 }
 
+/// Returns the Rust type used in the generated code for a Varlink type.
+///
+/// This is the owned form of the type, as used in custom types and errors. Custom types are
+/// referred to by their name converted to Pascal case.
+pub fn rust_type_for(ty: &Type<'_>) -> String {
+    codegen::type_to_rust(ty)
+}
+
+/// Options for [`generate_dir`], [`generate_from_str`] and [`generate_to_out_dir`].
+#[derive(Debug, Clone)]
+pub struct CodegenOptions {
+    /// The directory to write the generated files to.
+    pub output_dir: PathBuf,
+    /// Whether to also generate a `mod.rs` file, declaring all the generated modules.
+    pub generate_mod_rs: bool,
+    /// The formatter to use for the generated code.
+    pub formatter: Formatter,
+    /// The version to stamp into each generated module as a `VERSION` constant, if any.
+    pub version: Option<String>,
+    /// Whether to preserve unknown fields of method replies.
+    ///
+    /// See [`CodeGenerator::set_preserve_extra_fields`] for details.
+    pub preserve_extra_fields: bool,
+    /// Whether to add an example to the module documentation of each generated file.
+    ///
+    /// See [`CodeGenerator::set_example_docs`] for details.
+    pub example_docs: bool,
+    /// Whether to generate newtypes for custom types with a single field.
+    ///
+    /// See [`CodeGenerator::set_newtype_wrappers`] for details.
+    pub newtype_wrappers: bool,
+    /// Whether to implement the introspection traits for the generated types.
+    ///
+    /// See [`CodeGenerator::set_introspection`] for details.
+    pub introspection: bool,
+    /// Whether proxy methods with a single custom-typed output return that type directly.
+    ///
+    /// See [`CodeGenerator::set_unwrap_custom_outputs`] for details.
+    pub unwrap_custom_outputs: bool,
+    /// The methods to generate streaming variants of the proxy methods for, by their IDL name.
+    ///
+    /// See [`CodeGenerator::set_stream_methods`] for details.
+    pub stream_methods: Vec<String>,
+    /// Whether to split the code of each interface into multiple files.
+    ///
+    /// If enabled, [`generate_dir`] generates a directory for each interface instead of a single
+    /// file. See [`generate_split`] for details.
+    pub split_files: bool,
+    /// Whether to add a catch-all `Unknown` variant to the generated enums.
+    ///
+    /// See [`CodeGenerator::set_unknown_enum_variants`] for details.
+    pub unknown_enum_variants: bool,
+    /// The visibility of the generated structs, enums and traits, e.g `pub(crate)`.
+    ///
+    /// See [`CodeGenerator::set_visibility`] for details.
+    pub visibility: String,
+}
+
+impl CodegenOptions {
+    /// Create options for writing generated files to `output_dir`.
+    ///
+    /// By default, the generated code is formatted using rustfmt, no `mod.rs` is generated, no
+    /// version is stamped, unknown reply fields are not preserved, no example docs are added, no
+    /// newtypes are generated, the introspection traits are not implemented, all method outputs
+    /// get an output struct, no streaming proxy methods are generated, each interface is
+    /// generated in a single file, the generated enums get no `Unknown` variant and the generated
+    /// items are `pub`.
+    pub fn new(output_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            output_dir: output_dir.into(),
+            generate_mod_rs: false,
+            formatter: Formatter::default(),
+            version: None,
+            preserve_extra_fields: false,
+            example_docs: false,
+            newtype_wrappers: false,
+            introspection: false,
+            unwrap_custom_outputs: false,
+            stream_methods: Vec::new(),
+            split_files: false,
+            unknown_enum_variants: false,
+            visibility: "pub".to_string(),
+        }
+    }
+}
+
+/// Generate Rust code for all Varlink IDL (`*.varlink`) files in a directory.
+///
+/// One Rust file is generated for each IDL file, named after the last segment of the interface
+/// name (e.g. `org.example.Ping` results in `ping.rs`). The files are processed in alphabetical
+/// order of their names. If [`CodegenOptions::split_files`] is enabled, a directory of the same
+/// name is generated instead, containing the files returned by [`generate_split`].
+///
+/// Returns the paths of all the generated files, including `mod.rs` if requested.
+pub fn generate_dir(dir: &Path, options: &CodegenOptions) -> Result<Vec<PathBuf>> {
+    let mut idl_files = Vec::new();
+    for entry in
+        fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "varlink") {
+            idl_files.push(path);
+        }
+    }
+    idl_files.sort();
+
+    fs::create_dir_all(&options.output_dir).with_context(|| {
+        format!(
+            "Failed to create output directory: {}",
+            options.output_dir.display()
+        )
+    })?;
+
+    let mut output_paths = Vec::new();
+    let mut module_names = Vec::new();
+    for idl_file in &idl_files {
+        let content = fs::read_to_string(idl_file)
+            .with_context(|| format!("Failed to read file: {}", idl_file.display()))?;
+        let interface = Interface::try_from(content.as_str())
+            .with_context(|| format!("Failed to parse interface from: {}", idl_file.display()))?;
+        let module_name = interface_to_module_name(interface.name());
+
+        if options.split_files {
+            let module_dir = options.output_dir.join(&module_name);
+            fs::create_dir_all(&module_dir).with_context(|| {
+                format!(
+                    "Failed to create output directory: {}",
+                    module_dir.display()
+                )
+            })?;
+            for (file_name, code) in generate_split(&interface, options)? {
+                let output_path = module_dir.join(file_name);
+                fs::write(&output_path, code).with_context(|| {
+                    format!("Failed to write output file: {}", output_path.display())
+                })?;
+                output_paths.push(output_path);
+            }
+        } else {
+            let code = generate_with_options(&interface, options)?;
+            let output_path = options.output_dir.join(format!("{module_name}.rs"));
+            fs::write(&output_path, code).with_context(|| {
+                format!("Failed to write output file: {}", output_path.display())
+            })?;
+            output_paths.push(output_path);
+        }
+
+        module_names.push(module_name);
+    }
+
+    if options.generate_mod_rs {
+        let mut code = String::from("// Generated code from Varlink IDL files.\n\n");
+        for module_name in &module_names {
+            code.push_str(&format!("pub mod {module_name};\n"));
+        }
+
+        let mod_path = options.output_dir.join("mod.rs");
+        fs::write(&mod_path, code)
+            .with_context(|| format!("Failed to write output file: {}", mod_path.display()))?;
+        output_paths.push(mod_path);
+    }
+
+    Ok(output_paths)
+}
+
+/// Generate Rust code from Varlink IDL in a string and write it to `OUT_DIR`.
+///
+/// This is meant to be used from build scripts, when the IDL is not available as a file (e.g it's
+/// fetched or generated at build time). Same as [`generate_from_str`], except that
+/// `options.output_dir` is ignored.
+///
+/// Returns the path of the generated file, so it can be included using `include!`.
+pub fn generate_to_out_dir(
+    interface_name: &str,
+    idl: &str,
+    options: &CodegenOptions,
+) -> Result<PathBuf> {
+    let out_dir = std::env::var_os("OUT_DIR").context("`OUT_DIR` is not set")?;
+    let options = CodegenOptions {
+        output_dir: out_dir.into(),
+        ..options.clone()
+    };
+
+    generate_from_str(interface_name, idl, &options)
+}
+
+/// Generate Rust code from Varlink IDL in a string and write it to `options.output_dir`.
+///
+/// The generated file is named after the last segment of `interface_name`, the same way as
+/// [`generate_dir`] does. It is an error if `idl` defines an interface other than
+/// `interface_name`.
+///
+/// Returns the path of the generated file.
+pub fn generate_from_str(
+    interface_name: &str,
+    idl: &str,
+    options: &CodegenOptions,
+) -> Result<PathBuf> {
+    let interface = Interface::try_from(idl)
+        .with_context(|| format!("Failed to parse interface: {interface_name}"))?;
+    if interface.name() != interface_name {
+        anyhow::bail!(
+            "Expected interface `{interface_name}` but found `{}`",
+            interface.name()
+        );
+    }
+    let code = generate_with_options(&interface, options)?;
+
+    let output_path = options
+        .output_dir
+        .join(format!("{}.rs", interface_to_module_name(interface_name)));
+    fs::write(&output_path, code)
+        .with_context(|| format!("Failed to write output file: {}", output_path.display()))?;
+
+    Ok(output_path)
+}
+
+/// Generate Rust code from a Varlink interface, split into multiple files.
+///
+/// The types, the client-side proxy and the service-side handler of the interface are generated in
+/// `types.rs`, `proxy.rs` and `server.rs` respectively, along with a `mod.rs` declaring these
+/// modules and re-exporting all their items. This makes the code of large interfaces easier to
+/// navigate. `options.output_dir` and `options.generate_mod_rs` are ignored.
+///
+/// Returns the names of the files along with their code, in the order above.
+pub fn generate_split(
+    interface: &Interface<'_>,
+    options: &CodegenOptions,
+) -> Result<Vec<(&'static str, String)>> {
+    let context = || {
+        format!(
+            "Failed to generate code for interface: {}",
+            interface.name()
+        )
+    };
+    let has_errors = interface.error_count() > 0;
+
+    let mut generator = generator_with_options(options);
+    generator
+        .generate_member_names_only(interface)
+        .with_context(context)?;
+    generator
+        .generate_types_only(interface)
+        .with_context(context)?;
+    if let Some(version) = &options.version {
+        generator.generate_version_const(version)?;
+    }
+    let imports = if has_errors {
+        "use serde::{Deserialize, Serialize};\nuse zlink::ReplyError;\n"
+    } else {
+        "use serde::{Deserialize, Serialize};\n"
+    };
+    let types = format!("{imports}\n{}", generator.output());
+
+    let mut generator = generator_with_options(options);
+    generator
+        .generate_proxy_only(interface)
+        .with_context(context)?;
+    // A stub error type is generated along with the proxy if the interface has no errors.
+    let imports = if has_errors {
+        "use zlink::proxy;\n"
+    } else {
+        "use zlink::{proxy, ReplyError};\n"
+    };
+    let proxy = format!(
+        "{imports}\n#[allow(unused_imports)]\nuse super::types::*;\n\n{}",
+        generator.output()
+    );
+
+    let mut generator = generator_with_options(options);
+    generator
+        .generate_handler_only(interface)
+        .with_context(context)?;
+    let server = format!(
+        "use serde::{{Deserialize, Serialize}};\n\n\
+         #[allow(unused_imports)]\nuse super::types::*;\n\n{}",
+        generator.output()
+    );
+
+    let mut generator = generator_with_options(options);
+    generator
+        .generate_split_mod(interface)
+        .with_context(context)?;
+    let mod_rs = generator.output();
+
+    Ok(vec![
+        ("types.rs", options.formatter.format(&types)?),
+        ("proxy.rs", options.formatter.format(&proxy)?),
+        ("server.rs", options.formatter.format(&server)?),
+        ("mod.rs", options.formatter.format(&mod_rs)?),
+    ])
+}
+
+fn generator_with_options(options: &CodegenOptions) -> CodeGenerator {
+    CodeGenerator::new()
+        .set_preserve_extra_fields(options.preserve_extra_fields)
+        .set_example_docs(options.example_docs)
+        .set_newtype_wrappers(options.newtype_wrappers)
+        .set_introspection(options.introspection)
+        .set_unwrap_custom_outputs(options.unwrap_custom_outputs)
+        .set_stream_methods(&options.stream_methods)
+        .set_unknown_enum_variants(options.unknown_enum_variants)
+        .set_visibility(options.visibility.as_str())
+}
+
+fn generate_with_options(interface: &Interface<'_>, options: &CodegenOptions) -> Result<String> {
+    let mut generator = generator_with_options(options);
+    generator
+        .generate_interface(interface, false)
+        .with_context(|| {
+            format!(
+                "Failed to generate code for interface: {}",
+                interface.name()
+            )
+        })?;
+    if let Some(version) = &options.version {
+        generator.generate_version_const(version)?;
+    }
+
+    options.formatter.format(&generator.output())
+}
+
+fn interface_to_module_name(interface_name: &str) -> String {
+    // Convert interface name like "org.example.Interface" to "interface".
+    interface_name
+        .split('.')
+        .next_back()
+        .unwrap_or(interface_name)
+        .to_snake_case()
+}
+
 /// Format generated Rust code using rustfmt.
+///
+/// If rustfmt is not available, the code is formatted using the [built-in
+/// formatter](Formatter::Builtin) instead. If rustfmt rejects the code, e.g because it's not valid
+/// Rust, [`Error::FormatFailed`] is returned with the diagnostics of rustfmt.
 pub fn format_code(code: &str) -> Result<String> {
     use std::{
-        io::Write,
+        io::{ErrorKind, Write},
         process::{Command, Stdio},
     };
 
-    let mut child = Command::new("rustfmt")
+    let child = Command::new("rustfmt")
         .arg("--edition=2021")
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
-        .spawn()
-        .context("Failed to spawn rustfmt")?;
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            return Ok(formatter::format_builtin(code));
+        }
+        Err(e) => return Err(e).context("Failed to spawn rustfmt"),
+    };
 
     if let Some(mut stdin) = child.stdin.take() {
         stdin
@@ -59,12 +408,8 @@ pub fn format_code(code: &str) -> Result<String> {
         .context("Failed to wait for rustfmt")?;
 
     if !output.status.success() {
-        // If rustfmt fails, return the original code.
-        eprintln!(
-            "Warning: rustfmt failed: {}",
-            String::from_utf8_lossy(&output.stderr)
-        );
-        return Ok(code.to_string());
+        let diagnostics = String::from_utf8_lossy(&output.stderr).into_owned();
+        return Err(Error::FormatFailed(diagnostics).into());
     }
 
     String::from_utf8(output.stdout).context("Failed to parse rustfmt output")
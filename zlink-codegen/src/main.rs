@@ -6,7 +6,9 @@ use std::{
     io::{self, Write},
 };
 use zlink::idl::Interface;
-use zlink_codegen::{format_code, generate_interface, generate_interfaces};
+use zlink_codegen::{
+    format_code, generate_interface, generate_interfaces, generate_split, CodegenOptions,
+};
 
 mod cli;
 use cli::Args;
@@ -15,13 +17,14 @@ fn main() -> Result<()> {
     let args = Args::parse();
 
     // Handle the case where no command is provided (use files directly).
-    let (files, output, multiple_files) = match args.command {
+    let (files, output, multiple_files, split) = match args.command {
         Some(cli::Command::Generate {
             files,
             output,
             multiple_files,
-        }) => (files, output, multiple_files),
-        None => (args.files, args.output, args.multiple_files),
+            split,
+        }) => (files, output, multiple_files, split),
+        None => (args.files, args.output, args.multiple_files, args.split),
     };
 
     if files.is_empty() {
@@ -71,6 +74,26 @@ fn main() -> Result<()> {
             .with_context(|| format!("Failed to write output file: {}", output_path.display()))?;
 
         println!("Generated code written to {}", output_path.display());
+    } else if split {
+        // A directory of multiple output files for each interface.
+        let options = CodegenOptions::new(".");
+        for interface in &interfaces {
+            let dir = interface_to_filename(interface.name());
+            fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create output directory: {}", dir))?;
+
+            for (file_name, code) in generate_split(interface, &options)? {
+                let output_path = format!("{}/{}", dir, file_name);
+                fs::write(&output_path, code)
+                    .with_context(|| format!("Failed to write output file: {}", output_path))?;
+            }
+
+            println!(
+                "Generated code for `{}` written to {}/",
+                interface.name(),
+                dir
+            );
+        }
     } else if multiple_files {
         // Multiple output files.
         for interface in &interfaces {
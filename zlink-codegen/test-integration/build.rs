@@ -1,5 +1,7 @@
 use std::{env, fs, path::PathBuf};
 
+use zlink_codegen::CodeGenerator;
+
 fn main() {
     // Get the manifest directory.
     let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
@@ -43,4 +45,92 @@ fn main() {
     let out_dir = env::var("OUT_DIR").unwrap();
     let out_path = PathBuf::from(&out_dir).join("generated.rs");
     fs::write(&out_path, formatted_code).expect("Failed to write generated code");
+
+    // Generate code preserving unknown reply fields, in a separate module.
+    generate_module(
+        "extra.idl",
+        CodeGenerator::new().set_preserve_extra_fields(true),
+        "extra_fields.rs",
+    );
+
+    // Generate code with newtypes for single-field types, in a separate module.
+    generate_module(
+        "newtypes.idl",
+        CodeGenerator::new().set_newtype_wrappers(true),
+        "newtypes.rs",
+    );
+
+    // Generate code with a restricted visibility, in a separate module.
+    generate_module(
+        "visibility.idl",
+        CodeGenerator::new().set_visibility("pub(crate)"),
+        "visibility.rs",
+    );
+
+    // Generate code implementing the introspection traits, in a separate module.
+    generate_module(
+        "introspection.idl",
+        CodeGenerator::new().set_introspection(true),
+        "introspection.rs",
+    );
+
+    // Generate code with streaming proxy methods, in a separate module.
+    generate_module(
+        "streams.idl",
+        CodeGenerator::new().set_stream_methods(["Monitor"]),
+        "streams.rs",
+    );
+
+    // Generate enums with a catch-all `Unknown` variant, in a separate module.
+    generate_module(
+        "enums.idl",
+        CodeGenerator::new().set_unknown_enum_variants(true),
+        "enums.rs",
+    );
+
+    // Generate code for the random values of `arbitrary_json`, in a separate module.
+    generate_module("arbitrary.idl", CodeGenerator::new(), "arbitrary.rs");
+
+    // Generate the service-side code, in a separate module.
+    let idl_path = PathBuf::from(&manifest_dir).join("storage.idl");
+    let content = fs::read_to_string(&idl_path)
+        .unwrap_or_else(|_| panic!("Failed to read IDL file: {}", idl_path.display()));
+    let interface: zlink::idl::Interface = content
+        .as_str()
+        .try_into()
+        .expect("Failed to parse IDL file");
+    let mut generator = zlink_codegen::CodeGenerator::new();
+    generator
+        .generate_types_only(&interface)
+        .expect("Failed to generate code");
+    generator
+        .generate_handler_only(&interface)
+        .expect("Failed to generate code");
+    let generated_code = format!(
+        "use serde::{{Deserialize, Serialize}};\n\n{}",
+        generator.output()
+    );
+    let formatted_code = zlink_codegen::format_code(&generated_code).unwrap_or(generated_code);
+    let out_path = PathBuf::from(&out_dir).join("handler.rs");
+    fs::write(&out_path, formatted_code).expect("Failed to write generated code");
+}
+
+/// Generate the code for the interface in `idl_file` with `generator`, into `out_file`.
+fn generate_module(idl_file: &str, mut generator: CodeGenerator, out_file: &str) {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let idl_path = PathBuf::from(&manifest_dir).join(idl_file);
+    println!("cargo:rerun-if-changed={}", idl_path.display());
+    let content = fs::read_to_string(&idl_path)
+        .unwrap_or_else(|_| panic!("Failed to read IDL file: {}", idl_path.display()));
+    let interface: zlink::idl::Interface = content
+        .as_str()
+        .try_into()
+        .expect("Failed to parse IDL file");
+    generator
+        .generate_interface(&interface, false)
+        .expect("Failed to generate code");
+    let generated_code = generator.output();
+    let formatted_code = zlink_codegen::format_code(&generated_code).unwrap_or(generated_code);
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join(out_file);
+    fs::write(&out_path, formatted_code).expect("Failed to write generated code");
 }
@@ -1,6 +1,51 @@
 // Include the generated code from the build script.
 include!(concat!(env!("OUT_DIR"), "/generated.rs"));
 
+/// Code generated with unknown reply fields preserved.
+pub mod extra_fields {
+    include!(concat!(env!("OUT_DIR"), "/extra_fields.rs"));
+}
+
+/// Code generated with newtypes for single-field types.
+pub mod newtypes {
+    include!(concat!(env!("OUT_DIR"), "/newtypes.rs"));
+}
+
+/// Code generated with `pub(crate)` visibility.
+///
+/// The module is public, so any generated item left `pub` that exposes the `pub(crate)` types
+/// fails to compile.
+#[allow(dead_code)]
+#[deny(private_interfaces)]
+pub mod visibility {
+    include!(concat!(env!("OUT_DIR"), "/visibility.rs"));
+}
+
+/// Code generated with the introspection traits implemented.
+pub mod introspection {
+    include!(concat!(env!("OUT_DIR"), "/introspection.rs"));
+}
+
+/// Code generated with streaming proxy methods.
+pub mod streams {
+    include!(concat!(env!("OUT_DIR"), "/streams.rs"));
+}
+
+/// Code generated with a catch-all `Unknown` variant in the enums.
+pub mod enums {
+    include!(concat!(env!("OUT_DIR"), "/enums.rs"));
+}
+
+/// Code for the random values of `arbitrary_json`.
+pub mod arbitrary {
+    include!(concat!(env!("OUT_DIR"), "/arbitrary.rs"));
+}
+
+/// Service-side code.
+pub mod handler {
+    include!(concat!(env!("OUT_DIR"), "/handler.rs"));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -92,6 +137,19 @@ mod tests {
         assert!(matches!(result, Err(ExampleError::Unknown)));
     }
 
+    #[test]
+    fn test_error_display() {
+        let error = ExampleError::InvalidInput {
+            code: 400,
+            details: "Invalid input provided".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            r#"invalid input: code=400, details="Invalid input provided""#
+        );
+        assert_eq!(ExampleError::Unknown.to_string(), "unknown");
+    }
+
     #[tokio::test]
     async fn test_calc_proxy() {
         // Prepare all responses for CalcProxy tests.
@@ -254,6 +312,202 @@ mod tests {
         assert_eq!(result.docs.len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_extra_reply_fields_preserved() {
+        use extra_fields::Status;
+
+        let response = json!({
+            "parameters": {
+                "state": "running",
+                "load": 3,
+                "uptime": 42
+            }
+        })
+        .to_string();
+        let socket = MockSocket::new(&[&response]);
+        let mut conn: Connection<MockSocket> = Connection::new(socket);
+
+        let status = conn.get_status().await.unwrap().unwrap();
+        assert_eq!(status.state, "running");
+        assert_eq!(status.load, 3);
+        // The field unknown to the client ends up in `extra`.
+        assert_eq!(status.extra.len(), 1);
+        assert_eq!(status.extra["uptime"], 42);
+    }
+
+    #[tokio::test]
+    async fn test_newtype_round_trip() {
+        use newtypes::{User, UserId, Users};
+
+        let user = User {
+            id: UserId("u1".to_string()),
+            name: "Alice".to_string(),
+        };
+        // The newtype keeps the object shape of the IDL type on the wire.
+        let value = serde_json::to_value(&user).unwrap();
+        assert_eq!(value, json!({"id": {"value": "u1"}, "name": "Alice"}));
+        assert_eq!(serde_json::from_value::<User>(value).unwrap(), user);
+
+        let response = json!({"parameters": {"user": {"id": {"value": "u2"}, "name": "Bob"}}});
+        let response = response.to_string();
+        let socket = MockSocket::new(&[&response]);
+        let mut conn: Connection<MockSocket> = Connection::new(socket);
+        // The `GetUser` method of the `camelcase.idl` interface is in scope as well.
+        let output = Users::get_user(&mut conn, UserId("u2".to_string()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(output.user.id, UserId("u2".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_restricted_visibility() {
+        use visibility::{People, Person};
+
+        let response = json!({"parameters": {"person": {"name": "Alice"}}}).to_string();
+        let socket = MockSocket::new(&[&response]);
+        let mut conn: Connection<MockSocket> = Connection::new(socket);
+        let output = conn.get("Alice").await.unwrap().unwrap();
+        assert_eq!(
+            output.person,
+            Person {
+                name: "Alice".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_introspection_matches_idl() {
+        use introspection::{Color, GetOutput, InventoryError, Item};
+        use zlink::{
+            idl::{self, Interface},
+            introspect::{CustomType, ReplyError, Type},
+        };
+
+        let interface = Interface::try_from(include_str!("../introspection.idl")).unwrap();
+        let custom_type = |name| interface.custom_types().find(|t| t.name() == name).unwrap();
+
+        assert_eq!(Item::CUSTOM_TYPE, custom_type("Item"));
+        assert_eq!(*Item::TYPE, idl::Type::Custom("Item"));
+        assert_eq!(Color::CUSTOM_TYPE, custom_type("Color"));
+
+        let method = interface.methods().next().unwrap();
+        let idl::Type::Object(fields) = GetOutput::TYPE else {
+            panic!("Expected an object type");
+        };
+        assert!(fields.iter().eq(method.outputs()));
+
+        assert!(InventoryError::VARIANTS
+            .iter()
+            .copied()
+            .eq(interface.errors()));
+    }
+
+    #[tokio::test]
+    async fn test_flat_stream() {
+        use futures_util::{pin_mut, StreamExt};
+        use streams::{Events, EventsError, EventsStreamError};
+
+        let responses = [
+            json!({"continues": true, "parameters": {"event": {"kind": "added"}}}).to_string(),
+            json!({"continues": true, "parameters": {"event": {"kind": "removed"}}}).to_string(),
+            json!({"error": "org.example.events.Overflow"}).to_string(),
+        ];
+        let socket = MockSocket::new(&responses.iter().map(|s| s.as_str()).collect::<Vec<_>>());
+        let mut conn: Connection<MockSocket> = Connection::new(socket);
+
+        let stream = conn.monitor_stream(0).await.unwrap();
+        pin_mut!(stream);
+
+        let output = stream.next().await.unwrap().unwrap();
+        assert_eq!(output.event.kind, "added");
+        let output = stream.next().await.unwrap().unwrap();
+        assert_eq!(output.event.kind, "removed");
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(matches!(
+            err,
+            EventsStreamError::Reply(EventsError::Overflow)
+        ));
+        assert_eq!(err.to_string(), "overflow");
+        assert!(std::error::Error::source(&err).is_some());
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_enum_value() {
+        use enums::{Color, Paint};
+
+        let responses = [
+            json!({"parameters": {"color": "green"}}).to_string(),
+            json!({"parameters": {"color": "blue"}}).to_string(),
+        ];
+        let socket = MockSocket::new(&responses.iter().map(|s| s.as_str()).collect::<Vec<_>>());
+        let mut conn: Connection<MockSocket> = Connection::new(socket);
+
+        let output = conn.get().await.unwrap().unwrap();
+        assert_eq!(output.color, Color::Green);
+        let output = conn.get().await.unwrap().unwrap();
+        assert_eq!(output.color, Color::Unknown);
+    }
+
+    #[test]
+    fn test_arbitrary_json() {
+        use rand::{rngs::SmallRng, SeedableRng};
+        use zlink::idl::{arbitrary_json, Interface, Type};
+
+        let interface = Interface::try_from(include_str!("../arbitrary.idl")).unwrap();
+        let profile = Type::Custom("Profile");
+
+        let mut rng = SmallRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let value = arbitrary_json(&profile, &interface, &mut rng);
+            if let Err(e) = serde_json::from_value::<arbitrary::Profile>(value.clone()) {
+                panic!("Failed to deserialize {value}: {e}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handler_default_methods() {
+        use handler::{Document, StorageHandler, StorageHandlerError, StoreOutput};
+        use zlink::varlink_service;
+
+        // Only supports storing documents.
+        struct Store {
+            last_id: String,
+        }
+
+        impl StorageHandler for Store {
+            async fn store(
+                &mut self,
+                doc: Document,
+            ) -> Result<StoreOutput<'_>, StorageHandlerError> {
+                self.last_id = doc.id;
+
+                Ok(StoreOutput { id: &self.last_id })
+            }
+        }
+
+        let doc = Document {
+            id: "doc1".to_string(),
+            title: "Title".to_string(),
+            content: "Content".to_string(),
+            tags: vec![],
+        };
+        let mut handler = Store {
+            last_id: String::new(),
+        };
+        assert_eq!(handler.store(doc).await.unwrap().id, "doc1");
+
+        let err = handler.retrieve("doc1".to_string()).await.unwrap_err();
+        match err {
+            StorageHandlerError::VarlinkService(varlink_service::Error::MethodNotImplemented {
+                method,
+            }) => assert_eq!(method.as_str(), "org.example.storage.Retrieve"),
+            _ => panic!("Expected `MethodNotImplemented` error, got: {err:?}"),
+        }
+    }
+
     #[tokio::test]
     async fn test_error_handling() {
         // Test connection-level errors separately since they each need their own connection.
@@ -264,7 +518,13 @@ mod tests {
 
         // This should result in a connection error, not a service error.
         let result = conn.get_person("test").await;
-        assert!(result.is_err()); // Connection-level error
+        match result {
+            Err(e @ zlink::Error::Json(_)) => {
+                // The location of the syntax error is preserved.
+                assert_eq!((e.line(), e.column()), (Some(1), Some(1)));
+            }
+            _ => panic!("Expected a JSON error, got: {result:?}"),
+        }
 
         // Test empty response.
         let empty_response = "";
@@ -75,6 +75,27 @@ method Get(id: int) -> (value: string)
     assert!(code.contains("InvalidInput"));
 }
 
+#[test]
+fn test_error_display() {
+    let idl = r#"
+interface org.example.errors
+
+error NotFound()
+error InvalidInput(field: string, reason: string)
+
+method Get(id: int) -> (value: string)
+"#;
+
+    let interface = Interface::try_from(idl).unwrap();
+    let code = generate_interface(&interface).unwrap();
+
+    assert!(code.contains("impl core::fmt::Display for ErrorsError {"));
+    assert!(code.contains("Self::NotFound => write!(f, \"not found\"),"));
+    assert!(code.contains("Self::InvalidInput { field, reason } => {"));
+    assert!(code.contains("write!(f, \"invalid input: field={:?}, reason={:?}\", field, reason)"));
+    assert!(code.contains("impl std::error::Error for ErrorsError {}"));
+}
+
 #[test]
 fn test_interface_with_arrays_and_dicts() {
     let idl = r#"
@@ -211,6 +232,299 @@ error InvalidParameter (parameter: string)
     assert!(code.contains("pub interfaces: Vec<&'a str>"));
 }
 
+#[test]
+fn test_method_trailing_comment() {
+    let idl = r#"
+interface org.example.trailing
+
+# Pings the service.
+method Ping() -> () # Never fails.
+"#;
+
+    let interface = Interface::try_from(idl).unwrap();
+    let code = generate_interface(&interface).unwrap();
+
+    assert!(code.contains("/// Pings the service.\n    /// Never fails.\n"));
+}
+
+#[test]
+fn test_preserve_extra_fields() {
+    use zlink_codegen::CodeGenerator;
+
+    let interface =
+        Interface::try_from("interface org.example.extra\nmethod Get() -> (value: int)").unwrap();
+
+    let mut generator = CodeGenerator::new();
+    generator.generate_interface(&interface, false).unwrap();
+    assert!(!generator.output().contains("pub extra:"));
+
+    let mut generator = CodeGenerator::new().set_preserve_extra_fields(true);
+    generator.generate_interface(&interface, false).unwrap();
+    let code = generator.output();
+    assert!(code.contains(
+        "#[serde(flatten)]\n    pub extra: serde_json::Map<std::string::String, serde_json::Value>,"
+    ));
+}
+
+#[test]
+fn test_newtype_wrappers() {
+    use zlink_codegen::CodeGenerator;
+
+    let idl = r#"
+interface org.example.users
+
+type UserId (value: string)
+
+type Score (
+    # The score in percent.
+    percent: int
+)
+
+type User (id: UserId, name: string)
+
+method GetUser(id: UserId) -> (user: User)
+"#;
+    let interface = Interface::try_from(idl).unwrap();
+
+    let mut generator = CodeGenerator::new();
+    generator.generate_interface(&interface, false).unwrap();
+    assert!(generator.output().contains("pub struct UserId {"));
+
+    let mut generator = CodeGenerator::new().set_newtype_wrappers(true);
+    generator.generate_interface(&interface, false).unwrap();
+    let code = generator.output();
+    assert!(code.contains("#[derive(Debug, Clone, PartialEq)]\npub struct UserId(pub String);\n"));
+    // The newtype keeps the object shape of the IDL type on the wire.
+    assert!(code.contains("impl serde::Serialize for UserId {"));
+    assert!(code.contains("object.serialize_field(\"value\", &self.0)?;"));
+    assert!(code.contains("impl<'de> serde::Deserialize<'de> for UserId {"));
+    assert!(code.contains(
+        "#[derive(Debug, Clone, PartialEq)]\npub struct Score(\n    /// The score in percent.\n    pub i64,\n);\n"
+    ));
+    // Types with more fields are still structs and can refer to the newtypes.
+    assert!(code.contains("pub struct User {"));
+    assert!(code.contains("pub id: UserId,"));
+}
+
+#[test]
+fn test_introspection() {
+    use zlink_codegen::CodeGenerator;
+
+    let idl = r#"
+interface org.example.inventory
+
+type Item (itemId: int, name: string)
+
+type Color (red, green)
+
+type Sku (value: string)
+
+method Get(id: int) -> (item: Item, inStock: bool)
+
+error NotFound (itemId: int)
+"#;
+    let interface = Interface::try_from(idl).unwrap();
+
+    let code = generate_interface(&interface).unwrap();
+    assert!(!code.contains("zlink::introspect"));
+    assert!(!code.contains("#[zlink(rename = \"red\")]"));
+
+    let mut generator = CodeGenerator::new()
+        .set_introspection(true)
+        .set_newtype_wrappers(true);
+    generator.generate_interface(&interface, false).unwrap();
+    let code = generator.output();
+
+    // Custom types.
+    assert!(code.contains("PartialEq, zlink::introspect::CustomType)]\npub struct Item {\n"));
+    assert!(code.contains(
+        "    #[serde(rename = \"itemId\")]\n\
+         \x20   #[zlink(rename = \"itemId\")]\n\
+         \x20   pub item_id: i64,\n"
+    ));
+    assert!(code.contains(
+        "PartialEq, zlink::introspect::CustomType)]\n\
+         #[serde(rename_all = \"snake_case\")]\n\
+         pub enum Color {\n"
+    ));
+    assert!(code.contains("    #[zlink(rename = \"red\")]\n    Red,\n"));
+    assert!(code.contains(
+        "impl zlink::introspect::Type for Sku {\n\
+         \x20   const TYPE: &'static zlink::idl::Type<'static> =\n\
+         \x20       &zlink::idl::Type::Custom(\"Sku\");\n\
+         }\n"
+    ));
+    assert!(code.contains("impl zlink::introspect::CustomType for Sku {"));
+
+    // Method outputs.
+    assert!(code.contains(
+        "#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, zlink::introspect::Type)]\n\
+         pub struct GetOutput {\n"
+    ));
+    assert!(code.contains("    #[zlink(rename = \"inStock\")]\n    pub in_stock: bool,\n"));
+
+    // Errors.
+    assert!(code.contains(
+        "#[derive(Debug, Clone, PartialEq, ReplyError, zlink::introspect::ReplyError)]\n\
+         #[zlink(interface = \"org.example.inventory\")]\npub enum InventoryError {\n"
+    ));
+}
+
+#[test]
+fn test_unwrap_custom_outputs() {
+    use zlink_codegen::CodeGenerator;
+
+    let idl = r#"
+interface org.example.users
+
+type User (id: int, name: string)
+
+method GetUser(id: int) -> (user: User)
+
+method GetUsers() -> (users: []User)
+
+method GetNames(id: int) -> (user: User, name: string)
+"#;
+    let interface = Interface::try_from(idl).unwrap();
+
+    let code = generate_interface(&interface).unwrap();
+    assert!(code.contains("pub struct GetUserOutput {"));
+    assert!(!code.contains("#[zlink(output"));
+
+    let mut generator = CodeGenerator::new().set_unwrap_custom_outputs(true);
+    generator.generate_interface(&interface, false).unwrap();
+    generator.generate_handler_only(&interface).unwrap();
+    let code = generator.output();
+    assert!(code.contains(
+        "    #[zlink(output = \"user\")]\n    \
+         async fn get_user(&mut self, id: i64) -> zlink::Result<Result<User, UsersError>>;\n"
+    ));
+    // The handler still replies with the output struct, so that the reply has the `user` parameter.
+    assert!(code.contains("pub struct GetUserOutput {"));
+    assert!(code.contains(
+        "fn get_user(&mut self, id: i64) -> impl core::future::Future<Output = \
+         Result<GetUserOutput, UsersHandlerError>> {"
+    ));
+    // Only single outputs of custom types are unwrapped.
+    assert!(code.contains("pub struct GetUsersOutput {"));
+    assert!(code.contains("pub struct GetNamesOutput<'a> {"));
+}
+
+#[test]
+fn test_visibility() {
+    use zlink_codegen::CodeGenerator;
+
+    let idl = r#"
+interface org.example.people
+
+type Person (name: string)
+
+type Mood (happy, sad)
+
+method Get(name: string) -> (person: Person)
+
+error NotFound (name: string)
+"#;
+    let interface = Interface::try_from(idl).unwrap();
+
+    let mut generator = CodeGenerator::new().set_visibility("pub(crate)");
+    generator.generate_interface(&interface, false).unwrap();
+    let code = generator.output();
+    assert!(code.contains("pub(crate) trait People {"));
+    assert!(code.contains("pub(crate) struct GetOutput {"));
+    assert!(code.contains("pub(crate) struct Person {"));
+    assert!(code.contains("pub(crate) enum Mood {"));
+    assert!(code.contains("pub(crate) enum PeopleError {"));
+    assert!(!code.contains("pub struct"));
+    assert!(!code.contains("pub enum"));
+    assert!(!code.contains("pub trait"));
+    // The generated code itself is compiled in the `test-integration` crate.
+}
+
+#[test]
+fn test_unknown_enum_variants() {
+    use zlink_codegen::CodeGenerator;
+
+    let idl = r#"
+interface org.example.paint
+
+type Color (red, green)
+
+method Get() -> (color: Color)
+"#;
+    let interface = Interface::try_from(idl).unwrap();
+
+    let mut generator = CodeGenerator::new();
+    generator.generate_interface(&interface, false).unwrap();
+    assert!(!generator.output().contains("Unknown,"));
+
+    let mut generator = CodeGenerator::new().set_unknown_enum_variants(true);
+    generator.generate_interface(&interface, false).unwrap();
+    let code = generator.output();
+    assert!(code.contains(
+        "    Green,\n    \
+         /// A value unknown to this version of the interface.\n    \
+         #[serde(other)]\n    \
+         Unknown,\n}"
+    ));
+}
+
+#[test]
+fn test_stream_methods() {
+    use zlink_codegen::CodeGenerator;
+
+    let idl = r#"
+interface org.example.events
+
+type Event (kind: string)
+
+method Monitor(since: int) -> (event: Event)
+
+method Latest() -> (event: Event)
+"#;
+    let interface = Interface::try_from(idl).unwrap();
+
+    let code = generate_interface(&interface).unwrap();
+    assert!(!code.contains("EventsStreamError"));
+    assert!(!code.contains("monitor_stream"));
+
+    let mut generator = CodeGenerator::new().set_stream_methods(["Monitor"]);
+    generator.generate_interface(&interface, false).unwrap();
+    let code = generator.output();
+    assert!(code.contains(
+        "pub enum EventsStreamError {\n\
+         \x20   /// The connection failed.\n\
+         \x20   Connection(zlink::Error),\n\
+         \x20   /// The service replied with an error.\n\
+         \x20   Reply(EventsError),\n\
+         }\n"
+    ));
+    assert!(code.contains(
+        "    #[zlink(rename = \"Monitor\", more)]\n\
+         \x20   async fn monitor_more(&mut self, since: i64) -> zlink::Result<impl \
+         futures_util::Stream<Item = zlink::Result<Result<MonitorOutput, EventsError>>>>;\n"
+    ));
+    assert!(code.contains(
+        "    #[zlink(skip)]\n\
+         \x20   async fn monitor_stream(&mut self, since: i64) -> zlink::Result<impl \
+         futures_util::Stream<Item = Result<MonitorOutput, EventsStreamError>>> {\n\
+         \x20       let replies = self.monitor_more(since).await?;\n\
+         \x20       Ok(futures_util::StreamExt::map(replies, |reply| match reply {\n\
+         \x20           Ok(Ok(output)) => Ok(output),\n\
+         \x20           Ok(Err(e)) => Err(EventsStreamError::Reply(e)),\n\
+         \x20           Err(e) => Err(EventsStreamError::Connection(e)),\n\
+         \x20       }))\n\
+         \x20   }\n"
+    ));
+    assert!(code.contains("impl core::fmt::Display for EventsStreamError {"));
+    assert!(code.contains("impl std::error::Error for EventsStreamError {"));
+    // The stub error type of the interface can't be instantiated.
+    assert!(code.contains("            Self::Reply(e) => match *e {},\n"));
+    // Only the listed methods get streaming variants.
+    assert!(!code.contains("latest_more"));
+    assert!(!code.contains("latest_stream"));
+}
+
 #[test]
 fn test_reference_types_in_proxy() {
     let idl = r#"
@@ -272,3 +586,395 @@ fn test_camelcase_rename_attributes() {
     assert!(code.contains(r#"#[zlink(rename = "userId")]"#));
     assert!(code.contains("user_id: i64"));
 }
+
+#[test]
+fn test_member_name_consts() {
+    let idl = r#"
+interface org.example.names
+
+method Get(id: int) -> (value: string)
+method Set(id: int, value: string) -> ()
+
+error NotFound(id: int)
+error PermissionDenied()
+"#;
+
+    let interface = Interface::try_from(idl).unwrap();
+    let code = generate_interface(&interface).unwrap();
+
+    // Check that the method and error names are listed with their fully-qualified names.
+    assert!(code.contains("pub const METHODS: &[&str] = &["));
+    assert!(code.contains(r#""org.example.names.Get","#));
+    assert!(code.contains(r#""org.example.names.Set","#));
+    assert!(code.contains("pub const ERRORS: &[&str] = &["));
+    assert!(code.contains(r#""org.example.names.NotFound","#));
+    assert!(code.contains(r#""org.example.names.PermissionDenied","#));
+
+    // With multiple interfaces in the same module, the consts are prefixed.
+    let other = Interface::try_from("interface org.example.other\nmethod Ping() -> ()").unwrap();
+    let code = zlink_codegen::generate_interfaces(&[interface, other]).unwrap();
+    assert!(code.contains("pub const NAMES_METHODS: &[&str] = &["));
+    assert!(code.contains("pub const OTHER_ERRORS: &[&str] = &["));
+    assert!(code.contains(r#""org.example.other.Ping","#));
+}
+
+#[test]
+fn test_incremental_generation() {
+    use zlink_codegen::CodeGenerator;
+
+    let idl = r#"
+interface org.example.parts
+
+type Item (name: string)
+
+method Get(itemId: int) -> (item: Item)
+method Reset() -> ()
+
+error NotFound()
+"#;
+
+    let interface = Interface::try_from(idl).unwrap();
+
+    // The proxy alone contains no type definitions.
+    let mut generator = CodeGenerator::new();
+    generator.generate_proxy_only(&interface).unwrap();
+    let code = generator.output();
+    assert!(code.contains("pub trait Parts {"));
+    assert!(!code.contains("pub struct"));
+    assert!(!code.contains("pub enum"));
+
+    // The types alone contain no proxy.
+    let mut generator = CodeGenerator::new();
+    generator.generate_types_only(&interface).unwrap();
+    let code = generator.output();
+    assert!(code.contains("pub struct Item {"));
+    assert!(code.contains("pub struct GetOutput"));
+    assert!(code.contains("pub enum PartsError {"));
+    assert!(!code.contains("#[proxy("));
+
+    // The handler is a method call enum.
+    let mut generator = CodeGenerator::new();
+    generator.generate_handler_only(&interface).unwrap();
+    let code = generator.output();
+    assert!(code.contains("pub enum PartsMethod {"));
+    assert!(code.contains(r#"#[serde(rename = "org.example.parts.Get")]"#));
+    assert!(code.contains(r#"#[serde(rename = "itemId")]"#));
+    assert!(code.contains("item_id: i64,"));
+    assert!(code.contains("Reset,"));
+    assert!(!code.contains("#[proxy("));
+}
+
+#[test]
+fn test_handler_default_methods() {
+    use zlink_codegen::CodeGenerator;
+
+    let idl = r#"
+interface org.example.parts
+
+method Get(itemId: int) -> (name: string)
+method Reset() -> ()
+
+error NotFound()
+"#;
+
+    let interface = Interface::try_from(idl).unwrap();
+    let mut generator = CodeGenerator::new();
+    generator.generate_handler_only(&interface).unwrap();
+    let code = generator.output();
+
+    assert!(code.contains("pub enum PartsHandlerError {"));
+    assert!(code.contains("Interface(PartsError),"));
+    assert!(code.contains("VarlinkService(zlink::varlink_service::Error),"));
+    assert!(code.contains("pub trait PartsHandler {"));
+    assert!(code.contains(
+        "fn get(&mut self, item_id: i64) -> impl core::future::Future<Output = Result<GetOutput<'_>, \
+         PartsHandlerError>> {"
+    ));
+    assert!(code.contains(
+        "fn reset(&mut self) -> impl core::future::Future<Output = Result<(), PartsHandlerError>> {"
+    ));
+    assert!(code.contains(r#"method: "org.example.parts.Get".parse().unwrap(),"#));
+    assert!(code.contains(r#"method: "org.example.parts.Reset".parse().unwrap(),"#));
+}
+
+#[test]
+fn test_deprecated_method() {
+    let idl = r#"
+interface org.example.parts
+
+# Looks up a part by name.
+# @deprecated: use Get instead
+method Find(name: string) -> (itemId: int)
+# @deprecated
+method Legacy() -> ()
+method Get(itemId: int) -> (name: string)
+"#;
+
+    let interface = Interface::try_from(idl).unwrap();
+    let methods: Vec<_> = interface.methods().collect();
+    assert_eq!(methods[0].deprecation_note(), Some("use Get instead"));
+    assert!(methods[1].is_deprecated());
+    assert!(!methods[2].is_deprecated());
+
+    let code = generate_interface(&interface).unwrap();
+    assert!(code.contains(
+        "#[deprecated(note = \"use Get instead\")]\n    async fn find(&mut self, name: &str)"
+    ));
+    assert!(code.contains("#[deprecated]\n    async fn legacy(&mut self)"));
+    assert_eq!(code.matches("#[deprecated").count(), 2);
+}
+
+#[test]
+fn test_generate_dir() {
+    use zlink_codegen::{generate_dir, CodegenOptions, Formatter};
+
+    let input_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        input_dir.path().join("org.example.ping.varlink"),
+        "interface org.example.ping\n\nmethod Ping(message: string) -> (reply: string)\n",
+    )
+    .unwrap();
+    std::fs::write(
+        input_dir.path().join("org.example.calc.varlink"),
+        "interface org.example.Calc\n\nmethod Add(a: int, b: int) -> (result: int)\n",
+    )
+    .unwrap();
+    // Files without the `.varlink` extension are ignored.
+    std::fs::write(input_dir.path().join("README.md"), "Not an IDL file.").unwrap();
+
+    let output_dir = tempfile::tempdir().unwrap();
+    let mut options = CodegenOptions::new(output_dir.path());
+    options.generate_mod_rs = true;
+    options.formatter = Formatter::None;
+    let paths = generate_dir(input_dir.path(), &options).unwrap();
+
+    assert_eq!(
+        paths,
+        vec![
+            output_dir.path().join("calc.rs"),
+            output_dir.path().join("ping.rs"),
+            output_dir.path().join("mod.rs"),
+        ]
+    );
+
+    let calc = std::fs::read_to_string(&paths[0]).unwrap();
+    assert!(calc.contains("#[proxy(\"org.example.Calc\")]"));
+    let ping = std::fs::read_to_string(&paths[1]).unwrap();
+    assert!(ping.contains("#[proxy(\"org.example.ping\")]"));
+    let mod_rs = std::fs::read_to_string(&paths[2]).unwrap();
+    assert!(mod_rs.contains("pub mod calc;\npub mod ping;\n"));
+    // No version is stamped by default.
+    assert!(!ping.contains("pub const VERSION"));
+}
+
+#[test]
+fn test_generate_dir_with_version() {
+    use zlink_codegen::{generate_dir, CodegenOptions, Formatter};
+
+    let input_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        input_dir.path().join("org.example.ping.varlink"),
+        "interface org.example.ping\n\nmethod Ping() -> ()\n",
+    )
+    .unwrap();
+
+    let output_dir = tempfile::tempdir().unwrap();
+    let mut options = CodegenOptions::new(output_dir.path());
+    options.formatter = Formatter::None;
+    options.version = Some("1.2.0".to_string());
+    let paths = generate_dir(input_dir.path(), &options).unwrap();
+
+    let ping = std::fs::read_to_string(&paths[0]).unwrap();
+    assert!(ping.contains(r#"pub const VERSION: &str = "1.2.0";"#));
+}
+
+#[test]
+fn test_generate_dir_split_files() {
+    use zlink_codegen::{generate_dir, CodegenOptions, Formatter};
+
+    let input_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        input_dir.path().join("org.example.ping.varlink"),
+        "interface org.example.ping\n\n\
+         method Ping(message: string) -> (reply: string)\n\n\
+         error Timeout()\n",
+    )
+    .unwrap();
+
+    let output_dir = tempfile::tempdir().unwrap();
+    let mut options = CodegenOptions::new(output_dir.path());
+    options.generate_mod_rs = true;
+    options.split_files = true;
+    options.formatter = Formatter::None;
+    let paths = generate_dir(input_dir.path(), &options).unwrap();
+
+    let ping_dir = output_dir.path().join("ping");
+    assert_eq!(
+        paths,
+        vec![
+            ping_dir.join("types.rs"),
+            ping_dir.join("proxy.rs"),
+            ping_dir.join("server.rs"),
+            ping_dir.join("mod.rs"),
+            output_dir.path().join("mod.rs"),
+        ]
+    );
+
+    let types = std::fs::read_to_string(&paths[0]).unwrap();
+    assert!(types.contains("pub struct PingOutput"));
+    assert!(types.contains("pub enum PingError"));
+    assert!(!types.contains("#[proxy("));
+    let proxy = std::fs::read_to_string(&paths[1]).unwrap();
+    assert!(proxy.contains("use super::types::*;"));
+    assert!(proxy.contains("#[proxy(\"org.example.ping\")]"));
+    assert!(!proxy.contains("pub struct PingOutput"));
+    let server = std::fs::read_to_string(&paths[2]).unwrap();
+    assert!(server.contains("use super::types::*;"));
+    assert!(server.contains("pub trait PingHandler"));
+    let mod_rs = std::fs::read_to_string(&paths[3]).unwrap();
+    assert!(mod_rs.contains(
+        "mod types;\nmod proxy;\nmod server;\n\n\
+         pub use types::*;\npub use proxy::*;\npub use server::*;\n"
+    ));
+    let top_mod_rs = std::fs::read_to_string(&paths[4]).unwrap();
+    assert!(top_mod_rs.contains("pub mod ping;\n"));
+}
+
+#[test]
+fn test_generate_from_str() {
+    use zlink_codegen::{generate_from_str, CodegenOptions, Formatter};
+
+    let out_dir = tempfile::tempdir().unwrap();
+    let idl = "interface org.example.Ping\n\nmethod Ping(message: string) -> (reply: string)\n";
+    let mut options = CodegenOptions::new(out_dir.path());
+    options.formatter = Formatter::None;
+    let path = generate_from_str("org.example.Ping", idl, &options).unwrap();
+    assert_eq!(path, out_dir.path().join("ping.rs"));
+
+    let code = std::fs::read_to_string(&path).unwrap();
+    assert!(code.contains("#[proxy(\"org.example.Ping\")]"));
+    assert!(code.contains("async fn ping"));
+
+    // The interface name must match the IDL.
+    assert!(generate_from_str("org.example.Pong", idl, &options).is_err());
+}
+
+#[test]
+fn test_example_docs() {
+    use zlink_codegen::{generate_dir, CodegenOptions, Formatter};
+
+    let input_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        input_dir.path().join("org.example.ping.varlink"),
+        "interface org.example.ping\n\nmethod Ping(message: string, type: int) -> (reply: string)\n\
+         method Pong() -> ()\n",
+    )
+    .unwrap();
+
+    let output_dir = tempfile::tempdir().unwrap();
+    let mut options = CodegenOptions::new(output_dir.path());
+    options.formatter = Formatter::None;
+    let paths = generate_dir(input_dir.path(), &options).unwrap();
+    let ping = std::fs::read_to_string(&paths[0]).unwrap();
+    assert!(!ping.contains("//! # Example"));
+
+    options.example_docs = true;
+    let paths = generate_dir(input_dir.path(), &options).unwrap();
+    let ping = std::fs::read_to_string(&paths[0]).unwrap();
+    assert!(ping.contains("//! # Example\n//!\n//! ```rust,ignore\n"));
+    assert!(ping.contains("//! let reply = conn.ping(message, r#type).await??;\n"));
+    // The example calls a method that is actually generated.
+    assert!(ping.contains("async fn ping(&mut self, message: &str,"));
+    assert!(ping.contains(" r#type: i64)"));
+}
+
+#[test]
+fn test_rust_type_for() {
+    use zlink::idl::{Type, TypeRef};
+    use zlink_codegen::rust_type_for;
+
+    // Primitives.
+    assert_eq!(rust_type_for(&Type::Bool), "bool");
+    assert_eq!(rust_type_for(&Type::Int), "i64");
+    assert_eq!(rust_type_for(&Type::Float), "f64");
+    assert_eq!(rust_type_for(&Type::String), "String");
+    assert_eq!(rust_type_for(&Type::ForeignObject), "serde_json::Value");
+
+    // Optional.
+    let optional = Type::Optional(TypeRef::new_owned(Type::Int));
+    assert_eq!(rust_type_for(&optional), "Option<i64>");
+
+    // Array.
+    let array = Type::Array(TypeRef::new_owned(Type::String));
+    assert_eq!(rust_type_for(&array), "Vec<String>");
+
+    // Map.
+    let map = Type::Map(TypeRef::new_owned(Type::Custom("user_info")));
+    assert_eq!(
+        rust_type_for(&map),
+        "std::collections::HashMap<String, UserInfo>"
+    );
+
+    // Custom.
+    assert_eq!(rust_type_for(&Type::Custom("Point")), "Point");
+}
+
+#[test]
+fn test_formatters() {
+    use zlink_codegen::{generate_dir, CodegenOptions, Formatter};
+
+    let input_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        input_dir.path().join("org.example.ping.varlink"),
+        "interface org.example.ping\n\nmethod Ping(message: string) -> (reply: string)\n",
+    )
+    .unwrap();
+    let generate = |formatter| {
+        let output_dir = tempfile::tempdir().unwrap();
+        let mut options = CodegenOptions::new(output_dir.path());
+        options.formatter = formatter;
+        let paths = generate_dir(input_dir.path(), &options).unwrap();
+
+        std::fs::read_to_string(&paths[0]).unwrap()
+    };
+
+    // The generator already indents the code, so the built-in formatter keeps it as is.
+    let unformatted = generate(Formatter::None);
+    assert!(unformatted.contains("pub trait Ping {\n    async fn ping("));
+    let formatted = generate(Formatter::Builtin);
+    assert!(formatted.contains("pub trait Ping {\n    async fn ping("));
+    assert!(!formatted.contains("\n\n\n"));
+    assert!(formatted.lines().all(|line| line == line.trim_end()));
+
+    let code = "pub trait Foo {\n  fn foo(&mut self,\nx: i64) -> Result<()>;\n\n\n\
+                // Not a bracket: {\nconst S: &str = \"{(\";\n        }\n";
+    assert_eq!(
+        Formatter::Builtin.format(code).unwrap(),
+        "pub trait Foo {\n    fn foo(&mut self,\n        x: i64) -> Result<()>;\n\n    \
+         // Not a bracket: {\n    const S: &str = \"{(\";\n}\n"
+    );
+    assert_eq!(Formatter::None.format(code).unwrap(), code);
+}
+
+#[test]
+fn test_format_invalid_code() {
+    use zlink_codegen::{format_code, Error};
+
+    if std::process::Command::new("rustfmt")
+        .arg("--version")
+        .output()
+        .is_err()
+    {
+        // Without rustfmt, the built-in formatter is used, which doesn't validate the code.
+        return;
+    }
+
+    let err = format_code("pub struct Broken {").unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<Error>(),
+        Some(Error::FormatFailed(diagnostics)) if !diagnostics.is_empty()
+    ));
+
+    let code = format_code("pub struct Fine{x:i64}").unwrap();
+    assert_eq!(code, "pub struct Fine {\n    x: i64,\n}\n");
+}
@@ -40,6 +40,7 @@ where
                 let oneway_cell = Cell::new(None);
                 let more_cell = Cell::new(None);
                 let upgrade_cell = Cell::new(None);
+                let id_cell = Cell::new(None);
 
                 // 2) Streaming adapter capturing booleans by Cell refs
                 struct FilterMap<'a, MAcc> {
@@ -47,6 +48,7 @@ where
                     oneway: &'a Cell<Option<bool>>,
                     more: &'a Cell<Option<bool>>,
                     upgrade: &'a Cell<Option<bool>>,
+                    id: &'a Cell<Option<u64>>,
                 }
                 impl<'de, 'a, MAcc> MapAccess<'de> for FilterMap<'a, MAcc>
                 where
@@ -75,6 +77,11 @@ where
                                     self.upgrade.set(Some(v));
                                     continue;
                                 }
+                                "id" => {
+                                    let v = self.inner.next_value()?;
+                                    self.id.set(Some(v));
+                                    continue;
+                                }
                                 other => {
                                     let de = other.into_deserializer();
                                     return seed.deserialize(de).map(Some);
@@ -98,6 +105,7 @@ where
                     oneway: &oneway_cell,
                     more: &more_cell,
                     upgrade: &upgrade_cell,
+                    id: &id_cell,
                 };
                 let method = M::deserialize(MapAccessDeserializer::new(filter))
                     .map_err(de::Error::custom)?;
@@ -106,12 +114,14 @@ where
                 let oneway = oneway_cell.get().unwrap_or_default();
                 let more = more_cell.get().unwrap_or_default();
                 let upgrade = upgrade_cell.get().unwrap_or_default();
+                let id = id_cell.get();
 
                 Ok(Call {
                     method,
                     oneway,
                     more,
                     upgrade,
+                    id,
                 })
             }
         }
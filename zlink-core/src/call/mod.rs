@@ -2,18 +2,25 @@
 // `method` field and `serde` requires `alloc` for both serialization and deserialization when
 // using the `flatten` attribute.
 mod de;
+#[cfg(feature = "std")]
+mod params;
+#[cfg(feature = "std")]
+mod raw_method;
+#[cfg(feature = "std")]
+pub use raw_method::RawMethod;
 mod ser;
 
 #[cfg(test)]
 mod tests;
 
 /// A method call.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Call<M> {
     pub(super) method: M,
     pub(super) oneway: bool,
     pub(super) more: bool,
     pub(super) upgrade: bool,
+    pub(super) id: Option<u64>,
 }
 
 impl<M> Call<M> {
@@ -24,18 +31,30 @@ impl<M> Call<M> {
             oneway: false,
             more: false,
             upgrade: false,
+            id: None,
         }
     }
 
     /// Set the oneway flag.
+    ///
+    /// A oneway call can't ask for more replies, so setting this flag clears the more flag.
     pub fn set_oneway(mut self, oneway: bool) -> Self {
         self.oneway = oneway;
+        if oneway {
+            self.more = false;
+        }
         self
     }
 
     /// Set the more flag.
+    ///
+    /// A call asking for more replies can't be oneway, so setting this flag clears the oneway
+    /// flag.
     pub fn set_more(mut self, more: bool) -> Self {
         self.more = more;
+        if more {
+            self.oneway = false;
+        }
         self
     }
 
@@ -45,6 +64,16 @@ impl<M> Call<M> {
         self
     }
 
+    /// Set the correlation ID.
+    ///
+    /// **Note**: This is a non-standard extension to the Varlink protocol. A server that doesn't
+    /// support it will ignore the ID and won't include it in its replies. See
+    /// [`crate::Connection::call_method_correlated`] for details.
+    pub fn set_id(mut self, id: Option<u64>) -> Self {
+        self.id = id;
+        self
+    }
+
     /// The method call name and parameters.
     pub fn method(&self) -> &M {
         &self.method
@@ -64,6 +93,40 @@ impl<M> Call<M> {
     pub fn upgrade(&self) -> bool {
         self.upgrade
     }
+
+    /// The correlation ID of the method call, if any.
+    pub fn id(&self) -> Option<u64> {
+        self.id
+    }
+}
+
+#[cfg(feature = "std")]
+impl Call<serde_json::Value> {
+    /// Deserialize the parameters of a dynamic method call into a concrete type.
+    ///
+    /// This is useful when the method call is first received as a [`serde_json::Value`] (e.g for
+    /// routing it based on the method name) and then needs to be handled by typed code. Absent
+    /// parameters are deserialized from an empty object.
+    ///
+    /// On failure, [`crate::varlink_service::Error::InvalidParameter`] is returned, as the
+    /// top-level [`crate::Error::VarlinkService`] error.
+    pub fn deserialize_params<P>(&self) -> crate::Result<P>
+    where
+        P: serde::de::DeserializeOwned,
+    {
+        let empty = serde_json::Value::Object(Default::default());
+        let params = self.method.get("parameters").unwrap_or(&empty);
+
+        serde_path_to_error::deserialize(params).map_err(|e| {
+            let parameter = params::invalid_parameter::<P>(params, &e)
+                .and_then(|name| name.parse().ok())
+                .unwrap_or_else(|| "parameters".parse().unwrap());
+
+            crate::Error::VarlinkService(crate::varlink_service::Error::InvalidParameter {
+                parameter,
+            })
+        })
+    }
 }
 
 impl<M> From<M> for Call<M> {
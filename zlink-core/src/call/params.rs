@@ -0,0 +1,96 @@
+//! Finding the parameter at fault when deserializing the parameters of a dynamic call fails.
+
+use serde::de::{self, DeserializeOwned, Visitor};
+use serde_json::{Map, Value};
+use serde_path_to_error::{Error, Segment};
+
+/// The name of the parameter that caused `error` while deserializing `params` into `P`.
+///
+/// Returns `None` if the error isn't specific to a single parameter (e.g. `params` isn't an
+/// object).
+pub(super) fn invalid_parameter<P>(
+    params: &Value,
+    error: &Error<serde_json::Error>,
+) -> Option<String>
+where
+    P: DeserializeOwned,
+{
+    match error.path().iter().next() {
+        Some(Segment::Map { key }) => Some(key.clone()),
+        Some(_) => None,
+        // Errors about missing fields are raised on the struct itself, not on any field.
+        None => match params {
+            Value::Object(params) => missing_parameter::<P>(params).map(Into::into),
+            _ => None,
+        },
+    }
+}
+
+/// The first required field of `P` that is absent from `params`.
+fn missing_parameter<P>(params: &Map<String, Value>) -> Option<&'static str>
+where
+    P: DeserializeOwned,
+{
+    struct_fields::<P>()
+        .iter()
+        .copied()
+        .filter(|field| !params.contains_key(*field))
+        .find(|field| {
+            // An optional field accepts `null` and the deserialization still fails on the actual
+            // missing field, while a required one now fails on the field itself.
+            let mut params = params.clone();
+            params.insert(field.to_string(), Value::Null);
+
+            match serde_path_to_error::deserialize::<_, P>(&Value::Object(params)) {
+                Ok(_) => true,
+                Err(e) => matches!(
+                    e.path().iter().next(),
+                    Some(Segment::Map { key }) if key == field
+                ),
+            }
+        })
+}
+
+/// The names of the fields of `P`, if it's deserialized as a struct.
+fn struct_fields<P>() -> &'static [&'static str]
+where
+    P: DeserializeOwned,
+{
+    let mut fields: &'static [&'static str] = &[];
+    let _ = P::deserialize(FieldsDeserializer(&mut fields));
+
+    fields
+}
+
+/// A deserializer that only records the fields requested through `deserialize_struct`.
+struct FieldsDeserializer<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> de::Deserializer<'de> for FieldsDeserializer<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        *self.0 = fields;
+
+        Err(de::Error::custom("fields recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option
+        unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+
+/// A method with its parameters kept unparsed.
+///
+/// This can be used as the `Method` type of a [`super::Call`] to only parse the name of the
+/// method, while keeping the parameters as the raw JSON they were received as. This is useful for
+/// intermediaries that only route the method calls based on their names. See
+/// [`crate::connection::ReadConnection::receive_raw_call`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RawMethod<'a> {
+    /// The fully-qualified name of the method.
+    #[serde(borrow)]
+    pub method: &'a str,
+    /// The raw JSON of the parameters, if any.
+    #[serde(borrow, default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<&'a RawValue>,
+}
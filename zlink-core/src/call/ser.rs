@@ -13,7 +13,7 @@ where
     where
         S: Serializer,
     {
-        let mut map = serializer.serialize_map(Some(4))?;
+        let mut map = serializer.serialize_map(Some(5))?;
 
         let flat_ser = FlatSerializer(&mut map);
         self.method.serialize(flat_ser)?;
@@ -27,6 +27,9 @@ where
         if self.upgrade {
             map.serialize_entry("upgrade", &true)?;
         }
+        if let Some(id) = self.id {
+            map.serialize_entry("id", &id)?;
+        }
 
         map.end()
     }
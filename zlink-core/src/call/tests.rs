@@ -3,12 +3,127 @@
 use super::Call;
 use serde::{Deserialize, Serialize};
 
+#[test]
+fn clone_call() {
+    #[derive(Debug, Clone, PartialEq)]
+    struct Method {
+        value: u32,
+    }
+
+    let call = Call::new(Method { value: 42 })
+        .set_more(true)
+        .set_id(Some(1));
+    let cloned = call.clone();
+    assert_eq!(cloned, call);
+    assert_eq!(cloned.method().value, 42);
+    assert!(cloned.more());
+    assert_eq!(cloned.id(), Some(1));
+}
+
+#[test]
+fn oneway_and_more_are_exclusive() {
+    let call = Call::new(()).set_more(true).set_oneway(true);
+    assert!(call.oneway());
+    assert!(!call.more());
+
+    let call = call.set_more(true);
+    assert!(call.more());
+    assert!(!call.oneway());
+
+    // Clearing one flag leaves the other alone.
+    let call = call.set_oneway(false);
+    assert!(call.more());
+    assert!(!call.oneway());
+}
+
 #[cfg(feature = "std")]
 mod std {
     use serde_json::Value;
 
     use super::*;
 
+    #[test]
+    fn deserialize_params_from_dynamic_call() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Params {
+            name: String,
+            value: i32,
+        }
+
+        let json = r#"{"method":"org.example.test.Method","parameters":{"name":"foo","value":42}}"#;
+        let call: Call<Value> = serde_json::from_str(json).unwrap();
+        let params: Params = call.deserialize_params().unwrap();
+        assert_eq!(
+            params,
+            Params {
+                name: "foo".into(),
+                value: 42
+            }
+        );
+
+        let json = r#"{"method":"org.example.test.Method","parameters":{"name":"foo"}}"#;
+        let call: Call<Value> = serde_json::from_str(json).unwrap();
+        match call.deserialize_params::<Params>() {
+            Err(crate::Error::VarlinkService(
+                crate::varlink_service::Error::InvalidParameter { parameter },
+            )) => assert_eq!(parameter.as_str(), "value"),
+            res => panic!("unexpected result: {res:?}"),
+        }
+
+        let json =
+            r#"{"method":"org.example.test.Method","parameters":{"name":"foo","value":"42"}}"#;
+        let call: Call<Value> = serde_json::from_str(json).unwrap();
+        match call.deserialize_params::<Params>() {
+            Err(crate::Error::VarlinkService(
+                crate::varlink_service::Error::InvalidParameter { parameter },
+            )) => assert_eq!(parameter.as_str(), "value"),
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
+    #[test]
+    fn deserialize_params_invalid_parameter() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        #[serde(rename_all = "camelCase")]
+        struct Params {
+            comment: Option<String>,
+            user_name: String,
+            nested: Option<Nested>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Nested {
+            id: u8,
+        }
+
+        let invalid_parameter = |params: &str| {
+            let json = format!(r#"{{"method":"org.example.test.Method","parameters":{params}}}"#);
+            let call: Call<Value> = serde_json::from_str(&json).unwrap();
+            match call.deserialize_params::<Params>() {
+                Err(crate::Error::VarlinkService(
+                    crate::varlink_service::Error::InvalidParameter { parameter },
+                )) => parameter.as_str().to_string(),
+                res => panic!("unexpected result: {res:?}"),
+            }
+        };
+
+        // Absent optional parameters are skipped over.
+        assert_eq!(invalid_parameter("{}"), "userName");
+        // Errors in nested structures are reported on the top-level parameter.
+        assert_eq!(
+            invalid_parameter(r#"{"userName":"foo","nested":{"id":256}}"#),
+            "nested"
+        );
+        assert_eq!(
+            invalid_parameter(r#"{"userName":"foo","nested":{}}"#),
+            "nested"
+        );
+        // Errors not specific to a parameter.
+        assert_eq!(invalid_parameter("[]"), "parameters");
+    }
+
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     struct ExtendedParams<'a> {
         #[serde(flatten)]
@@ -3,6 +3,8 @@
 mod reply_stream;
 #[doc(hidden)]
 pub use reply_stream::ReplyStream;
+mod reply_stream_ext;
+pub use reply_stream_ext::ReplyStreamExt;
 
 use crate::{connection::Socket, reply, Call, Connection, Result};
 use core::fmt::Debug;
@@ -16,7 +18,8 @@ use serde::{Deserialize, Serialize};
 /// the entire chain using [`Chain::send`].
 ///
 /// With `std` feature enabled, this supports unlimited calls. Otherwise it is limited by how many
-/// calls can fit in our fixed-sized buffer.
+/// calls can fit in our fixed-sized buffer. Use [`Chain::set_max_calls`] to fail early and
+/// predictably instead of when the buffer is full.
 ///
 /// Oneway calls (where `Call::oneway() == Some(true)`) do not expect replies and are handled
 /// automatically by the chain.
@@ -25,6 +28,8 @@ pub struct Chain<'c, S: Socket, ReplyParams, ReplyError> {
     pub(super) connection: &'c mut Connection<S>,
     pub(super) call_count: usize,
     pub(super) reply_count: usize,
+    #[cfg(not(feature = "std"))]
+    max_calls: Option<usize>,
     _phantom: core::marker::PhantomData<(ReplyParams, ReplyError)>,
 }
 
@@ -48,6 +53,8 @@ where
             connection,
             call_count: 1,
             reply_count,
+            #[cfg(not(feature = "std"))]
+            max_calls: None,
             _phantom: core::marker::PhantomData,
         })
     }
@@ -59,10 +66,17 @@ where
     ///
     /// Calls with `more == Some(true)` will stream multiple replies until a reply with
     /// `continues != Some(true)` is received.
+    ///
+    /// Without the `std` feature, [`crate::Error::ChainTooLong`] is returned if the chain already
+    /// contains the maximum number of calls set through [`Chain::set_max_calls`].
     pub fn append<Method>(mut self, call: &Call<Method>) -> Result<Self>
     where
         Method: Serialize + Debug,
     {
+        #[cfg(not(feature = "std"))]
+        if self.max_calls.is_some_and(|max| self.call_count >= max) {
+            return Err(crate::Error::ChainTooLong);
+        }
         self.connection.write.enqueue_call(call)?;
         if !call.oneway() {
             self.reply_count += 1;
@@ -71,6 +85,15 @@ where
         Ok(self)
     }
 
+    /// Set the maximum number of calls in the chain.
+    ///
+    /// `None`, the default, means the number of calls is only limited by the size of the buffer.
+    #[cfg(not(feature = "std"))]
+    pub fn set_max_calls(mut self, max_calls: Option<usize>) -> Self {
+        self.max_calls = max_calls;
+        self
+    }
+
     /// Send all enqueued calls and return a replies stream.
     ///
     /// This will flush all enqueued calls in a single write operation and then return a stream
@@ -223,6 +246,107 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn more_call_replies_counted_until_terminal() -> crate::Result<()> {
+        use futures_util::stream::StreamExt;
+
+        let responses = [
+            r#"{"parameters":{"id":1},"continues":true}"#,
+            r#"{"parameters":{"id":2},"continues":true}"#,
+            r#"{"parameters":{"id":3},"continues":true}"#,
+            r#"{"parameters":{"id":4}}"#,
+            r#"{"parameters":{"id":5}}"#,
+            // Not part of the chain.
+            r#"{"parameters":{"id":6}}"#,
+        ];
+        let socket = MockSocket::new(&responses);
+        let mut conn = Connection::new(socket);
+
+        let more_call = Call::new(GetUser { id: 1 }).set_more(true);
+        let regular_call = Call::new(GetUser { id: 2 });
+
+        {
+            let replies = conn
+                .chain_call::<GetUser, User, ApiError>(&more_call)?
+                .append(&regular_call)?
+                .send()
+                .await?;
+            pin_mut!(replies);
+
+            let mut ids = mayheap::Vec::<_, 8>::new();
+            while let Some(reply) = replies.next().await {
+                ids.push(reply?.unwrap().parameters().unwrap().id).unwrap();
+            }
+            // All streamed replies of the `more` call are read, followed by the regular call's.
+            assert_eq!(ids.as_slice(), &[1, 2, 3, 4, 5]);
+        }
+
+        // The stream didn't read past the replies of the chain.
+        let reply = conn.receive_reply::<User, ApiError>().await?.unwrap();
+        assert_eq!(reply.parameters().unwrap().id, 6);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cancel_mid_stream() -> crate::Result<()> {
+        use futures_util::stream::StreamExt;
+
+        let responses = [
+            r#"{"parameters":{"id":1},"continues":true}"#,
+            r#"{"parameters":{"id":2},"continues":true}"#,
+            r#"{"parameters":{"id":3}}"#,
+            // Reply to the call made after cancellation.
+            r#"{"parameters":{"id":4}}"#,
+        ];
+        let socket = MockSocket::new(&responses);
+        let mut conn = Connection::new(socket);
+
+        let more_call = Call::new(GetUser { id: 1 }).set_more(true);
+        {
+            let replies = conn
+                .chain_call::<GetUser, User, ApiError>(&more_call)?
+                .send()
+                .await?;
+            pin_mut!(replies);
+
+            let user = replies.next().await.unwrap()?.unwrap();
+            assert_eq!(user.parameters().unwrap().id, 1);
+            replies.as_mut().cancel().await?;
+            assert!(replies.next().await.is_none());
+        }
+
+        // The connection is usable again.
+        let reply = conn
+            .call_method::<_, User, ApiError>(&Call::new(GetUser { id: 4 }))
+            .await?
+            .unwrap();
+        assert_eq!(reply.parameters().unwrap().id, 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn oneway_only_chain() -> crate::Result<()> {
+        use futures_util::stream::StreamExt;
+
+        let socket = MockSocket::new(&[]);
+        let mut conn = Connection::new(socket);
+
+        let oneway_call = Call::new(GetUser { id: 1 }).set_oneway(true);
+        let replies = conn
+            .chain_call::<GetUser, User, ApiError>(&oneway_call)?
+            .append(&oneway_call)?
+            .send()
+            .await?;
+        pin_mut!(replies);
+
+        // No replies are expected so the stream ends without reading from the socket.
+        assert!(replies.next().await.is_none());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn stream_interface_works() -> crate::Result<()> {
         use futures_util::stream::StreamExt;
@@ -364,4 +488,23 @@ mod tests {
         assert!(no_reply.is_none());
         Ok(())
     }
+
+    #[cfg(not(feature = "std"))]
+    #[tokio::test]
+    async fn max_calls() -> crate::Result<()> {
+        let socket = MockSocket::new(&[]);
+        let mut conn = Connection::new(socket);
+
+        let call = Call::new(GetUser { id: 1 });
+        let chain = conn
+            .chain_call::<GetUser, User, ApiError>(&call)?
+            .set_max_calls(Some(2))
+            .append(&call)?;
+        assert!(matches!(
+            chain.append(&call),
+            Err(crate::Error::ChainTooLong)
+        ));
+
+        Ok(())
+    }
 }
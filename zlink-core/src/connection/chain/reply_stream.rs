@@ -47,10 +47,34 @@ where
             func,
             call_count,
             current_index: 0,
-            done: false,
+            // Nothing to read if no replies are expected, e.g. all calls are oneway.
+            done: call_count == 0,
             _phantom: core::marker::PhantomData,
         }
     }
+
+    /// Cancel the stream.
+    ///
+    /// Since there is no way to tell the service to stop sending replies, this reads and discards
+    /// all the remaining replies to the calls, including any pending replies of a call with the
+    /// `more` flag, until the terminal one. Once the stream is dropped, the connection can be
+    /// reused for other calls.
+    ///
+    /// Errors returned by the service are ignored. If any other error occurs, it is returned and
+    /// the connection is likely in an inconsistent state so it should be discarded.
+    ///
+    /// **Note**: For a `more` call, this waits for the service to send the terminal reply, so it
+    /// should only be used with services that end the stream at some point.
+    pub async fn cancel(mut self: Pin<&mut Self>) -> Result<()> {
+        use futures_util::stream::StreamExt;
+
+        while let Some(reply) = self.next().await {
+            // We're not interested in the errors returned by the service.
+            let _ = reply?;
+        }
+
+        Ok(())
+    }
 }
 
 impl<'c, Read, F, Fut, Params, ReplyError> Stream
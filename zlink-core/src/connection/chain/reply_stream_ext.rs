@@ -0,0 +1,220 @@
+use core::{future::Future, pin::pin};
+use futures_util::stream::{Stream, StreamExt};
+
+#[cfg(feature = "std")]
+use crate::Reply;
+use crate::{reply, Result};
+
+/// Extension trait for streams of method call replies.
+///
+/// This is implemented for all streams yielding replies, such as [`super::ReplyStream`] returned
+/// by [`super::Chain::send`].
+pub trait ReplyStreamExt<Params, ReplyError>:
+    Stream<Item = Result<reply::Result<Params, ReplyError>>> + Sized
+{
+    /// Collect all replies into a bounded vector.
+    ///
+    /// The stream is drained until it ends, gathering up to `N` replies. If more than `N` replies
+    /// are received, the extra ones are discarded and [`crate::Error::BufferOverflow`] is returned
+    /// once the stream ends, so the connection is still usable for further calls. Unlike
+    /// [`ReplyStreamExt::collect_until_terminal`], this doesn't require allocation and method
+    /// errors don't stop the collection, so it's suitable for safely draining a chain of calls on
+    /// `no_std`.
+    fn collect_bounded<const N: usize>(
+        self,
+    ) -> impl Future<Output = Result<mayheap::Vec<reply::Result<Params, ReplyError>, N>>> {
+        async move {
+            let mut stream = pin!(self);
+            let mut replies = mayheap::Vec::new();
+            let mut overflowed = false;
+
+            while let Some(reply) = stream.next().await {
+                let reply = reply?;
+                if replies.len() == N {
+                    // Keep reading, so the replies to the chain don't get mixed up with later ones.
+                    overflowed = true;
+                    continue;
+                }
+                // The capacity was checked above.
+                let _ = replies.push(reply);
+            }
+
+            if overflowed {
+                return Err(crate::Error::BufferOverflow);
+            }
+
+            Ok(replies)
+        }
+    }
+
+    /// Collect all replies until the terminal reply.
+    ///
+    /// The terminal reply is the first reply that doesn't have the `continues` flag set to `true`.
+    /// It's included in the returned replies. If the stream ends before a terminal reply is
+    /// received, all the replies received until then are returned.
+    ///
+    /// Like the stream items, the return value has two layers of results: the outer one for
+    /// general errors and the inner one for method errors. In case of either error, all replies
+    /// received until then are discarded.
+    #[cfg(feature = "std")]
+    fn collect_until_terminal(
+        self,
+    ) -> impl Future<Output = Result<core::result::Result<Vec<Reply<Params>>, ReplyError>>> {
+        async move {
+            let mut stream = pin!(self);
+            let mut replies = Vec::new();
+
+            while let Some(reply) = stream.next().await {
+                let reply = match reply? {
+                    Ok(reply) => reply,
+                    Err(error) => return Ok(Err(error)),
+                };
+                let terminal = reply.continues() != Some(true);
+                replies.push(reply);
+
+                if terminal {
+                    break;
+                }
+            }
+
+            Ok(Ok(replies))
+        }
+    }
+}
+
+impl<S, Params, ReplyError> ReplyStreamExt<Params, ReplyError> for S where
+    S: Stream<Item = Result<reply::Result<Params, ReplyError>>>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::mock_socket::MockSocket, Call, Connection, ReplyError};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize)]
+    #[serde(tag = "method")]
+    enum Methods {
+        #[serde(rename = "org.example.GetUsers")]
+        GetUsers,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct User {
+        id: u32,
+    }
+
+    #[derive(Debug, PartialEq, ReplyError)]
+    #[zlink(interface = "org.example")]
+    enum ApiError {
+        PermissionDenied,
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn collect_until_terminal() -> crate::Result<()> {
+        let responses = [
+            r#"{"parameters":{"id":1},"continues":true}"#,
+            r#"{"parameters":{"id":2},"continues":true}"#,
+            r#"{"parameters":{"id":3}}"#,
+        ];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        let call = Call::new(Methods::GetUsers).set_more(true);
+        let replies = conn
+            .chain_call::<_, User, ApiError>(&call)?
+            .send()
+            .await?
+            .collect_until_terminal()
+            .await?
+            .unwrap();
+        let ids: Vec<_> = replies
+            .iter()
+            .map(|reply| reply.parameters().unwrap().id)
+            .collect();
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(replies[2].continues(), None);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn collect_until_terminal_mid_stream_error() -> crate::Result<()> {
+        let responses = [
+            r#"{"parameters":{"id":1},"continues":true}"#,
+            r#"{"error":"org.example.PermissionDenied"}"#,
+        ];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        let call = Call::new(Methods::GetUsers).set_more(true);
+        let error = conn
+            .chain_call::<_, User, ApiError>(&call)?
+            .send()
+            .await?
+            .collect_until_terminal()
+            .await?
+            .unwrap_err();
+        assert_eq!(error, ApiError::PermissionDenied);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn collect_bounded() -> crate::Result<()> {
+        let responses = [
+            r#"{"parameters":{"id":1}}"#,
+            r#"{"error":"org.example.PermissionDenied"}"#,
+            r#"{"parameters":{"id":3}}"#,
+        ];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        let call = Call::new(Methods::GetUsers);
+        let replies = conn
+            .chain_call::<_, User, ApiError>(&call)?
+            .append(&call)?
+            .append(&call)?
+            .send()
+            .await?
+            .collect_bounded::<3>()
+            .await?;
+        assert_eq!(replies.len(), 3);
+        assert_eq!(replies[0].as_ref().unwrap().parameters().unwrap().id, 1);
+        assert_eq!(
+            replies[1].as_ref().unwrap_err(),
+            &ApiError::PermissionDenied
+        );
+        assert_eq!(replies[2].as_ref().unwrap().parameters().unwrap().id, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn collect_bounded_overflow() -> crate::Result<()> {
+        let responses = [
+            r#"{"parameters":{"id":1}}"#,
+            r#"{"parameters":{"id":2}}"#,
+            r#"{"parameters":{"id":3}}"#,
+            r#"{"parameters":{"id":4}}"#,
+        ];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        let call = Call::new(Methods::GetUsers);
+        let result = conn
+            .chain_call::<_, User, ApiError>(&call)?
+            .append(&call)?
+            .append(&call)?
+            .send()
+            .await?
+            .collect_bounded::<2>()
+            .await;
+        assert!(matches!(result, Err(crate::Error::BufferOverflow)));
+
+        // The rest of the chain was drained, so a later call gets its own reply.
+        let reply = conn.call_method::<_, User, ApiError>(&call).await?.unwrap();
+        assert_eq!(reply.parameters().unwrap().id, 4);
+
+        Ok(())
+    }
+}
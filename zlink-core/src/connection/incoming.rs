@@ -0,0 +1,18 @@
+//! Messages received by a client, including the ones pushed by the service.
+
+use crate::{Call, RawMethod, Reply};
+
+/// A message received from a service.
+///
+/// See [`super::Connection::receive_any`].
+#[derive(Debug)]
+pub enum Incoming<'r, ReplyParams, ReplyError> {
+    /// A successful reply to a method call.
+    Reply(Reply<ReplyParams>),
+    /// An error reply to a method call.
+    Error(ReplyError),
+    /// A message that the service sent on its own, without a matching method call.
+    ///
+    /// It's received in the form of a method call, with the parameters kept as raw JSON.
+    Push(Call<RawMethod<'r>>),
+}
@@ -2,8 +2,16 @@
 
 mod read_connection;
 pub use read_connection::ReadConnection;
+#[cfg(feature = "std")]
+mod incoming;
+#[cfg(feature = "std")]
+pub use incoming::Incoming;
 pub mod chain;
 pub mod socket;
+#[cfg(feature = "std")]
+pub mod transcript;
+#[cfg(feature = "std")]
+pub use transcript::TranscriptConnection;
 mod write_connection;
 use crate::{
     reply::{self, Reply},
@@ -48,6 +56,36 @@ where
         }
     }
 
+    /// Create a new connection with the given initial size of the read and write buffers.
+    ///
+    /// By default, the buffers are initially allocated with a size fixed at build time through the
+    /// `io-buffer-*` cargo features. This allows choosing the size per connection instead, e.g
+    /// smaller buffers for connections that only exchange tiny messages. The buffers still grow
+    /// as needed.
+    ///
+    /// # Panics
+    ///
+    /// If `buffer_size` is zero.
+    #[cfg(feature = "std")]
+    pub fn new_with_buffer_size(socket: S, buffer_size: usize) -> Self {
+        assert!(buffer_size > 0, "buffer size must be non-zero");
+
+        let (read, write) = socket.split();
+        let id = NEXT_ID.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+        Self {
+            read: ReadConnection::with_buffer_size(read, id, buffer_size),
+            write: WriteConnection::with_buffer_size(write, id, buffer_size),
+        }
+    }
+
+    /// Enable or disable coalescing of sent calls.
+    ///
+    /// Convenience wrapper around [`WriteConnection::set_coalesce`].
+    #[cfg(feature = "std")]
+    pub fn set_coalesce(&mut self, duration: Option<core::time::Duration>) {
+        self.write.set_coalesce(duration)
+    }
+
     /// The reference to the read half of the connection.
     pub fn read(&self) -> &ReadConnection<S::ReadHalf> {
         &self.read
@@ -84,6 +122,15 @@ where
         self.read.id()
     }
 
+    /// When data was last sent or received over the connection.
+    ///
+    /// This is the creation time of the connection if nothing has been sent or received yet. It's
+    /// useful for finding connections that have been idle for too long.
+    #[cfg(feature = "std")]
+    pub fn last_activity(&self) -> std::time::Instant {
+        self.read.last_activity().max(self.write.last_activity())
+    }
+
     /// Sends a method call.
     ///
     /// Convenience wrapper around [`WriteConnection::send_call`].
@@ -96,7 +143,8 @@ where
 
     /// Receives a method call reply.
     ///
-    /// Convenience wrapper around [`ReadConnection::receive_reply`].
+    /// Convenience wrapper around [`ReadConnection::receive_reply`]. If coalescing is enabled
+    /// (see [`Connection::set_coalesce`]), the calls held back by it are flushed first.
     pub async fn receive_reply<'r, ReplyParams, ReplyError>(
         &'r mut self,
     ) -> Result<reply::Result<ReplyParams, ReplyError>>
@@ -104,9 +152,41 @@ where
         ReplyParams: Deserialize<'r> + Debug,
         ReplyError: Deserialize<'r> + Debug,
     {
+        self.flush_coalesced().await?;
         self.read.receive_reply().await
     }
 
+    /// Receives a method call reply or a message pushed by the service.
+    ///
+    /// Convenience wrapper around [`ReadConnection::receive_any`]. If coalescing is enabled (see
+    /// [`Connection::set_coalesce`]), the calls held back by it are flushed first.
+    #[cfg(feature = "std")]
+    pub async fn receive_any<'r, ReplyParams, ReplyError>(
+        &'r mut self,
+    ) -> Result<Incoming<'r, ReplyParams, ReplyError>>
+    where
+        ReplyParams: Deserialize<'r> + Debug,
+        ReplyError: Deserialize<'r> + Debug,
+    {
+        self.flush_coalesced().await?;
+        self.read.receive_any().await
+    }
+
+    /// Receives a method call reply, if one is immediately available.
+    ///
+    /// Convenience wrapper around [`ReadConnection::try_receive_reply`]. Unlike
+    /// [`Connection::receive_reply`], calls enqueued on the write half are not flushed, so make
+    /// sure to call [`Connection::flush`] first if you enabled coalescing.
+    pub fn try_receive_reply<'r, ReplyParams, ReplyError>(
+        &'r mut self,
+    ) -> Result<Option<reply::Result<ReplyParams, ReplyError>>>
+    where
+        ReplyParams: Deserialize<'r> + Debug,
+        ReplyError: Deserialize<'r> + Debug,
+    {
+        self.read.try_receive_reply()
+    }
+
     /// Call a method and receive a reply.
     ///
     /// This is a convenience method that combines [`Connection::send_call`] and
@@ -124,6 +204,170 @@ where
         self.receive_reply().await
     }
 
+    /// Call a method without any flags and receive a reply.
+    ///
+    /// This is a convenience method that wraps `method` in a [`Call`] and calls
+    /// [`Connection::call_method`]. Use [`Connection::call_method`] directly if you need to set any
+    /// flags on the call.
+    pub async fn call<'r, Method, ReplyParams, ReplyError>(
+        &'r mut self,
+        method: Method,
+    ) -> Result<reply::Result<ReplyParams, ReplyError>>
+    where
+        Method: Serialize + Debug,
+        ReplyParams: Deserialize<'r> + Debug,
+        ReplyError: Deserialize<'r> + Debug,
+    {
+        self.call_method(&Call::new(method)).await
+    }
+
+    /// Call a borrowed method without any flags and receive a reply.
+    ///
+    /// Same as [`Connection::call`] but `method` is wrapped in a [`Call`] by reference, so that
+    /// large method parameters don't need to be moved or cloned to be kept around after the call.
+    pub async fn call_ref<'r, Method, ReplyParams, ReplyError>(
+        &'r mut self,
+        method: &Method,
+    ) -> Result<reply::Result<ReplyParams, ReplyError>>
+    where
+        Method: Serialize + Debug,
+        ReplyParams: Deserialize<'r> + Debug,
+        ReplyError: Deserialize<'r> + Debug,
+    {
+        self.call_method(&Call::new(method)).await
+    }
+
+    /// Call a method without any flags and receive its reply parameters.
+    ///
+    /// Unlike [`Connection::call`], both the transport errors and the errors returned by the
+    /// service are converted into a boxed [`std::error::Error`], so that `?` just works in
+    /// application code that doesn't need to tell them apart. A reply without any parameters
+    /// results in [`Error::MissingParameters`](crate::Error::MissingParameters).
+    #[cfg(feature = "std")]
+    pub async fn call_std<'r, Method, ReplyParams, ReplyError>(
+        &'r mut self,
+        method: Method,
+    ) -> core::result::Result<ReplyParams, Box<dyn std::error::Error>>
+    where
+        Method: Serialize + Debug,
+        ReplyParams: Deserialize<'r> + Debug,
+        ReplyError: Deserialize<'r> + std::error::Error + 'static,
+    {
+        self.call::<_, ReplyParams, ReplyError>(method)
+            .await??
+            .into_parameters()
+            .ok_or_else(|| crate::Error::MissingParameters.into())
+    }
+
+    /// Call a method with the `more` flag set and collect all its replies.
+    ///
+    /// The `more` flag is set on `call` and the replies are received until the last one, i-e
+    /// either a reply without the `continues` flag or an error reply. This is a convenience for
+    /// bounded streams of replies, where it's fine to collect all of them before processing them.
+    #[cfg(feature = "std")]
+    pub async fn call_more_collect<Method, ReplyParams, ReplyError>(
+        &mut self,
+        call: Call<Method>,
+    ) -> Result<std::vec::Vec<reply::Result<ReplyParams, ReplyError>>>
+    where
+        Method: Serialize + Debug,
+        ReplyParams: serde::de::DeserializeOwned + Debug,
+        ReplyError: serde::de::DeserializeOwned + Debug,
+    {
+        self.send_call(&call.set_more(true)).await?;
+
+        let mut replies = std::vec::Vec::new();
+        loop {
+            let reply = self.receive_reply::<ReplyParams, ReplyError>().await?;
+            let last = !matches!(&reply, Ok(reply) if reply.continues() == Some(true));
+            replies.push(reply);
+            if last {
+                return Ok(replies);
+            }
+        }
+    }
+
+    /// Call a method that upgrades the connection to a different protocol.
+    ///
+    /// The call is sent with the upgrade flag set. If the service acknowledges the upgrade in its
+    /// reply (see [`Reply::set_upgrade`]), the connection is consumed and the halves of the
+    /// underlying socket are returned for the new protocol, along with any bytes that the service
+    /// already sent after the reply. Reading stops right after the reply, so these don't need to
+    /// end with a null byte.
+    ///
+    /// If the service replies without acknowledging the upgrade,
+    /// [`Error::UpgradeRejected`](crate::Error::UpgradeRejected) is returned.
+    #[cfg(feature = "std")]
+    pub async fn upgrade<Method, ReplyError>(
+        mut self,
+        method: Method,
+    ) -> Result<core::result::Result<(S::ReadHalf, S::WriteHalf, std::vec::Vec<u8>), ReplyError>>
+    where
+        Method: Serialize + Debug,
+        ReplyError: serde::de::DeserializeOwned + Debug,
+    {
+        self.send_call(&Call::new(method).set_upgrade(true)).await?;
+        self.flush_coalesced().await?;
+        match self
+            .read
+            .receive_upgrade_reply::<serde::de::IgnoredAny, ReplyError>()
+            .await?
+        {
+            Ok(reply) if reply.upgrade() => (),
+            Ok(_) => return Err(crate::Error::UpgradeRejected),
+            Err(e) => return Ok(Err(e)),
+        }
+        let (read, buffered) = self.read.into_parts();
+
+        Ok(Ok((read, self.write.into_socket(), buffered)))
+    }
+
+    /// Receives the reply to the method call with the given correlation ID.
+    ///
+    /// Convenience wrapper around [`ReadConnection::receive_correlated_reply`]. If coalescing is
+    /// enabled (see [`Connection::set_coalesce`]), the calls held back by it are flushed first.
+    #[cfg(feature = "std")]
+    pub async fn receive_correlated_reply<'r, ReplyParams, ReplyError>(
+        &'r mut self,
+        call_id: u64,
+    ) -> Result<reply::Result<ReplyParams, ReplyError>>
+    where
+        ReplyParams: Deserialize<'r> + Debug,
+        ReplyError: Deserialize<'r> + Debug,
+    {
+        self.flush_coalesced().await?;
+        self.read.receive_correlated_reply(call_id).await
+    }
+
+    /// Call a method and receive the reply correlated to it by its ID.
+    ///
+    /// Unlike [`Connection::call_method`], which expects the replies to arrive in the same order as
+    /// the method calls, this matches the reply to the call by the correlation ID set through
+    /// [`Call::set_id`]. Replies to other calls received in the meantime are buffered, and can be
+    /// retrieved through [`Connection::receive_correlated_reply`]. This allows multiplexing method
+    /// calls on a single connection with servers that reply out of order.
+    ///
+    /// If the call has no ID set, this is equivalent to [`Connection::call_method`].
+    ///
+    /// **Note**: Correlation IDs are a non-standard extension to the Varlink protocol. Both sides
+    /// need to support it: the server must echo the `id` field of the method call in its reply.
+    #[cfg(feature = "std")]
+    pub async fn call_method_correlated<'r, Method, ReplyParams, ReplyError>(
+        &'r mut self,
+        call: &Call<Method>,
+    ) -> Result<reply::Result<ReplyParams, ReplyError>>
+    where
+        Method: Serialize + Debug,
+        ReplyParams: Deserialize<'r> + Debug,
+        ReplyError: Deserialize<'r> + Debug,
+    {
+        self.send_call(call).await?;
+        match call.id() {
+            Some(id) => self.receive_correlated_reply(id).await,
+            None => self.receive_reply().await,
+        }
+    }
+
     /// Receive a method call over the socket.
     ///
     /// Convenience wrapper around [`ReadConnection::receive_call`].
@@ -154,6 +398,27 @@ where
         self.write.send_error(error).await
     }
 
+    /// Send a reply or an error reply over the socket, depending on `result`.
+    ///
+    /// Convenience wrapper around [`WriteConnection::send_result`].
+    pub async fn send_result<ReplyParams, ReplyError>(
+        &mut self,
+        result: &reply::Result<ReplyParams, ReplyError>,
+    ) -> Result<()>
+    where
+        ReplyParams: Serialize + Debug,
+        ReplyError: Serialize + Debug,
+    {
+        self.write.send_result(result).await
+    }
+
+    /// Send a raw message over the socket.
+    ///
+    /// Convenience wrapper around [`WriteConnection::send_raw`].
+    pub async fn send_raw(&mut self, message: &[u8]) -> Result<()> {
+        self.write.send_raw(message).await
+    }
+
     /// Enqueue a call to the server.
     ///
     /// Convenience wrapper around [`WriteConnection::enqueue_call`].
@@ -171,6 +436,26 @@ where
         self.write.flush().await
     }
 
+    // Flush the calls held back by coalescing, so that their replies can be received.
+    async fn flush_coalesced(&mut self) -> Result<()> {
+        #[cfg(feature = "std")]
+        if self.write.coalesce().is_some() {
+            return self.write.flush().await;
+        }
+
+        Ok(())
+    }
+
+    /// Flush the connection and shut down its write side, leaving the read side usable.
+    ///
+    /// This is useful for protocols where the client signals the end of its requests by closing
+    /// its side of the connection, while still reading the replies.
+    ///
+    /// Convenience wrapper around [`WriteConnection::shutdown`].
+    pub async fn shutdown_write(&mut self) -> Result<()> {
+        self.write.shutdown().await
+    }
+
     /// Start a chain of method calls.
     ///
     /// This allows batching multiple calls together and sending them in a single write operation.
@@ -312,6 +597,15 @@ where
     }
 }
 
+impl<S> AsMut<Connection<S>> for Connection<S>
+where
+    S: Socket,
+{
+    fn as_mut(&mut self) -> &mut Connection<S> {
+        self
+    }
+}
+
 #[cfg(feature = "io-buffer-1mb")]
 pub(crate) const BUFFER_SIZE: usize = 1024 * 1024;
 #[cfg(all(not(feature = "io-buffer-1mb"), feature = "io-buffer-16kb"))]
@@ -331,5 +625,293 @@ pub(crate) const BUFFER_SIZE: usize = 4 * 1024;
 
 #[cfg(feature = "std")]
 const MAX_BUFFER_SIZE: usize = 100 * 1024 * 1024; // Don't allow buffers over 100MB.
+#[cfg(feature = "std")]
+const MAX_PENDING_REPLIES: usize = 64; // Don't buffer more than 64 out-of-order replies.
 
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::mock_socket::MockSocket, ReplyError};
+
+    #[derive(Debug, Serialize)]
+    #[serde(tag = "method", content = "parameters")]
+    enum Methods {
+        #[serde(rename = "org.example.Add")]
+        Add { a: i64, b: i64 },
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Sum {
+        sum: i64,
+    }
+
+    #[derive(Debug, ReplyError)]
+    #[zlink(interface = "org.example")]
+    enum AddError {
+        Overflow,
+    }
+
+    impl core::fmt::Display for AddError {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("overflow")
+        }
+    }
+
+    impl core::error::Error for AddError {}
+
+    #[tokio::test]
+    async fn call() -> crate::Result<()> {
+        let responses = [r#"{"parameters":{"sum":5}}"#];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        let reply = conn
+            .call::<_, Sum, AddError>(Methods::Add { a: 2, b: 3 })
+            .await?
+            .unwrap();
+        assert_eq!(reply.parameters().unwrap().sum, 5);
+
+        let written = core::str::from_utf8(conn.write().write_half().written_data()).unwrap();
+        assert_eq!(
+            written,
+            "{\"method\":\"org.example.Add\",\"parameters\":{\"a\":2,\"b\":3}}\0"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn call_ref() -> crate::Result<()> {
+        let responses = [r#"{"parameters":{"sum":5}}"#, r#"{"parameters":{"sum":5}}"#];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        let method = Methods::Add { a: 2, b: 3 };
+        for _ in 0..2 {
+            let reply = conn.call_ref::<_, Sum, AddError>(&method).await?.unwrap();
+            assert_eq!(reply.parameters().unwrap().sum, 5);
+        }
+
+        let written = core::str::from_utf8(conn.write().write_half().written_data()).unwrap();
+        assert_eq!(
+            written,
+            "{\"method\":\"org.example.Add\",\"parameters\":{\"a\":2,\"b\":3}}\0\
+             {\"method\":\"org.example.Add\",\"parameters\":{\"a\":2,\"b\":3}}\0"
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn call_std() {
+        let responses = [
+            r#"{"parameters":{"sum":5}}"#,
+            r#"{"error":"org.example.Overflow"}"#,
+        ];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        let sum = conn
+            .call_std::<_, Sum, AddError>(Methods::Add { a: 2, b: 3 })
+            .await
+            .unwrap();
+        assert_eq!(sum.sum, 5);
+
+        // Varlink errors.
+        let err = conn
+            .call_std::<_, Sum, AddError>(Methods::Add { a: i64::MAX, b: 1 })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AddError>(),
+            Some(AddError::Overflow)
+        ));
+
+        // Transport errors.
+        let err = conn
+            .call_std::<_, Sum, AddError>(Methods::Add { a: 2, b: 3 })
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<crate::Error>().is_some());
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn call_more_collect() -> crate::Result<()> {
+        let responses = [
+            r#"{"parameters":{"sum":1},"continues":true}"#,
+            r#"{"parameters":{"sum":3},"continues":true}"#,
+            r#"{"parameters":{"sum":5}}"#,
+            // Not part of the replies to the call.
+            r#"{"parameters":{"sum":7}}"#,
+        ];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        let replies = conn
+            .call_more_collect::<_, Sum, AddError>(Call::new(Methods::Add { a: 2, b: 3 }))
+            .await?;
+        let sums: std::vec::Vec<_> = replies
+            .iter()
+            .map(|reply| reply.as_ref().unwrap().parameters().unwrap().sum)
+            .collect();
+        assert_eq!(sums, [1, 3, 5]);
+        let written = core::str::from_utf8(conn.write().write_half().written_data()).unwrap();
+        assert!(written.contains(r#""more":true"#));
+
+        // An error reply ends the stream.
+        let responses = [
+            r#"{"parameters":{"sum":1},"continues":true}"#,
+            r#"{"error":"org.example.Overflow"}"#,
+        ];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+        let replies = conn
+            .call_more_collect::<_, Sum, AddError>(Call::new(Methods::Add { a: 2, b: 3 }))
+            .await?;
+        assert_eq!(replies.len(), 2);
+        assert!(matches!(replies[1], Err(AddError::Overflow)));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn receive_any() -> crate::Result<()> {
+        let responses = [
+            r#"{"method":"org.example.Changed","parameters":{"sum":7}}"#,
+            r#"{"parameters":{"sum":5}}"#,
+            r#"{"error":"org.example.Overflow"}"#,
+        ];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        match conn.receive_any::<Sum, AddError>().await? {
+            Incoming::Push(call) => {
+                assert_eq!(call.method().method, "org.example.Changed");
+                assert_eq!(call.method().parameters.unwrap().get(), r#"{"sum":7}"#);
+            }
+            incoming => panic!("Expected a push, got {incoming:?}"),
+        }
+        assert!(matches!(
+            conn.receive_any::<Sum, AddError>().await?,
+            Incoming::Reply(reply) if reply.parameters().unwrap().sum == 5
+        ));
+        assert!(matches!(
+            conn.receive_any::<Sum, AddError>().await?,
+            Incoming::Error(AddError::Overflow)
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn upgrade() {
+        // The service replies without acknowledging the upgrade.
+        let responses = [r#"{}"#];
+        let conn = Connection::new(MockSocket::new(&responses));
+        let res = conn
+            .upgrade::<_, AddError>(Methods::Add { a: 2, b: 3 })
+            .await;
+        assert!(matches!(res, Err(crate::Error::UpgradeRejected)));
+
+        // The service acknowledges the upgrade. Unlike `MockSocket`, this doesn't send an extra
+        // null byte after the reply, which would be returned as part of the new protocol.
+        let (socket, handle) = crate::testing::ChannelSocket::new();
+        handle.feed(b"{\"upgrade\":true}\0");
+        let conn = Connection::new(socket);
+        let (_, _, buffered) = conn
+            .upgrade::<_, AddError>(Methods::Add { a: 2, b: 3 })
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(buffered.is_empty());
+        assert_eq!(
+            core::str::from_utf8(&handle.written()).unwrap(),
+            "{\"method\":\"org.example.Add\",\"parameters\":{\"a\":2,\"b\":3},\"upgrade\":true}\0"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn upgrade_with_trailing_bytes() {
+        use crate::testing::ChannelSocket;
+
+        // The service sends the first bytes of the new protocol in the same read as the reply.
+        let (socket, handle) = ChannelSocket::new();
+        handle.feed(b"{\"upgrade\":true}\0raw bytes");
+        let conn = Connection::new(socket);
+        let (_, _, buffered) = conn
+            .upgrade::<_, AddError>(Methods::Add { a: 2, b: 3 })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(buffered, b"raw bytes");
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn last_activity() -> crate::Result<()> {
+        let responses = [r#"{"parameters":{"sum":5}}"#];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+        let created = conn.last_activity();
+
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        conn.send_call(&Call::new(Methods::Add { a: 2, b: 3 }))
+            .await?;
+        let sent = conn.last_activity();
+        assert!(sent > created);
+        assert_eq!(conn.write().last_activity(), sent);
+
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        conn.receive_reply::<Sum, AddError>().await?.unwrap();
+        assert!(conn.last_activity() > sent);
+        assert_eq!(conn.read().last_activity(), conn.last_activity());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn send_result() -> crate::Result<()> {
+        let mut conn = Connection::new(MockSocket::new(&[]));
+
+        let result: reply::Result<Sum, AddError> = Ok(Reply::new(Some(Sum { sum: 5 })));
+        conn.send_result(&result).await?;
+        let written = core::str::from_utf8(conn.write().write_half().written_data()).unwrap();
+        assert_eq!(written, "{\"parameters\":{\"sum\":5}}\0");
+
+        let result: reply::Result<Sum, AddError> = Err(AddError::Overflow);
+        conn.send_result(&result).await?;
+        let written = core::str::from_utf8(conn.write().write_half().written_data()).unwrap();
+        let error = written
+            .strip_prefix("{\"parameters\":{\"sum\":5}}\0")
+            .unwrap();
+        assert!(error.starts_with("{\"error\":\"org.example.Overflow\""));
+        assert!(error.ends_with("}\0"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn new_with_buffer_size() -> crate::Result<()> {
+        // The messages are larger than the smallest buffer so it has to grow.
+        for buffer_size in [8, 64, 4096] {
+            let responses = [r#"{"parameters":{"sum":5}}"#];
+            let mut conn =
+                Connection::new_with_buffer_size(MockSocket::new(&responses), buffer_size);
+
+            let reply = conn
+                .call::<_, Sum, AddError>(Methods::Add { a: 2, b: 3 })
+                .await?
+                .unwrap();
+            assert_eq!(reply.parameters().unwrap().sum, 5);
+
+            let written = core::str::from_utf8(conn.write().write_half().written_data()).unwrap();
+            assert_eq!(
+                written,
+                "{\"method\":\"org.example.Add\",\"parameters\":{\"a\":2,\"b\":3}}\0"
+            );
+        }
+
+        Ok(())
+    }
+}
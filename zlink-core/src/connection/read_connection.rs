@@ -4,13 +4,13 @@ use core::{fmt::Debug, str::from_utf8_unchecked};
 
 use crate::{varlink_service, Result};
 
-#[cfg(feature = "std")]
-use super::MAX_BUFFER_SIZE;
 use super::{
     reply::{self, Reply},
     socket::ReadHalf,
     Call, BUFFER_SIZE,
 };
+#[cfg(feature = "std")]
+use super::{MAX_BUFFER_SIZE, MAX_PENDING_REPLIES};
 use mayheap::Vec;
 use memchr::memchr;
 use serde::Deserialize;
@@ -28,17 +28,44 @@ pub struct ReadConnection<Read: ReadHalf> {
     msg_pos: usize,
     buffer: Vec<u8, BUFFER_SIZE>,
     id: usize,
+    // Replies received out of order, keyed by their correlation ID.
+    #[cfg(feature = "std")]
+    pending_replies: std::collections::HashMap<u64, std::vec::Vec<u8>>,
+    // The last reply returned by `receive_correlated_reply`.
+    #[cfg(feature = "std")]
+    correlated_reply: std::vec::Vec<u8>,
+    #[cfg(feature = "std")]
+    last_activity: std::time::Instant,
 }
 
 impl<Read: ReadHalf> ReadConnection<Read> {
     /// Create a new connection.
     pub(super) fn new(socket: Read, id: usize) -> Self {
+        Self::with_buffer(socket, id, Vec::from_slice(&[0; BUFFER_SIZE]).unwrap())
+    }
+
+    /// Create a new connection with an initial buffer of `buffer_size` bytes.
+    #[cfg(feature = "std")]
+    pub(super) fn with_buffer_size(socket: Read, id: usize, buffer_size: usize) -> Self {
+        let mut buffer = Vec::new();
+        buffer.extend(core::iter::repeat_n(0, buffer_size));
+
+        Self::with_buffer(socket, id, buffer)
+    }
+
+    fn with_buffer(socket: Read, id: usize, buffer: Vec<u8, BUFFER_SIZE>) -> Self {
         Self {
             socket,
             read_pos: 0,
             msg_pos: 0,
             id,
-            buffer: Vec::from_slice(&[0; BUFFER_SIZE]).unwrap(),
+            buffer,
+            #[cfg(feature = "std")]
+            pending_replies: std::collections::HashMap::new(),
+            #[cfg(feature = "std")]
+            correlated_reply: std::vec::Vec::new(),
+            #[cfg(feature = "std")]
+            last_activity: std::time::Instant::now(),
         }
     }
 
@@ -83,35 +110,121 @@ impl<Read: ReadHalf> ReadConnection<Read> {
         let id = self.id;
         let buffer = self.read_message_bytes().await?;
 
-        // First, check if the message has an "error" field to determine how to deserialize.
-        // FIXME: This will mean the document will be parsed twice. We should instead try to
-        // quickly check if `error` field is present and then parse to the appropriate type based on
-        // that information. Perhaps a simple parser using `winnow`?
-        let error_name = extract_error_name(buffer);
-        if error_name.is_some() {
-            // SAFETY: If an error name was successfully extracted, it is safe to assume that the
-            // buffer contains valid UTF-8 data.
+        parse_reply(buffer, id)
+    }
+
+    /// Receives a method call reply or a message pushed by the service.
+    ///
+    /// Same as [`ReadConnection::receive_reply`], except that messages that are not replies to a
+    /// method call but were sent by the service on its own, are returned as
+    /// [`Incoming::Push`](super::Incoming::Push) instead of failing to deserialize. Such messages
+    /// are recognized by their `method` field, i-e they're method calls from the service.
+    ///
+    /// **Note**: Pushing messages from the service is an extension to the Varlink protocol, that
+    /// only some services implement.
+    #[cfg(feature = "std")]
+    pub async fn receive_any<'r, ReplyParams, ReplyError>(
+        &'r mut self,
+    ) -> Result<super::Incoming<'r, ReplyParams, ReplyError>>
+    where
+        ReplyParams: Deserialize<'r> + Debug,
+        ReplyError: Deserialize<'r> + Debug,
+    {
+        let id = self.id;
+        let buffer = self.read_message_bytes().await?;
+
+        if extract_method_name(buffer).is_some() {
+            let call = from_slice::<Call<crate::RawMethod<'_>>>(buffer)?;
+            // SAFETY: Since the parsing from JSON already succeeded, we can be sure that the
+            // buffer contains a valid UTF-8 string.
             unsafe { log_message(buffer, id) };
+            debug!("connection {}: received a push: {:?}", id, call);
+
+            return Ok(super::Incoming::Push(call));
+        }
+
+        Ok(match parse_reply(buffer, id)? {
+            Ok(reply) => super::Incoming::Reply(reply),
+            Err(e) => super::Incoming::Error(e),
+        })
+    }
+
+    /// Receives a method call reply, if one is immediately available.
+    ///
+    /// Same as [`ReadConnection::receive_reply`], except that it never waits for data. If no
+    /// complete message is buffered and the socket has no data immediately available (see
+    /// [`ReadHalf::try_read`]), `Ok(None)` is returned. This is useful for integrating a connection
+    /// into a custom event loop.
+    pub fn try_receive_reply<'r, ReplyParams, ReplyError>(
+        &'r mut self,
+    ) -> Result<Option<reply::Result<ReplyParams, ReplyError>>>
+    where
+        ReplyParams: Deserialize<'r> + Debug,
+        ReplyError: Deserialize<'r> + Debug,
+    {
+        let id = self.id;
+        if !self.try_read_from_socket()? {
+            return Ok(None);
         }
-        match error_name {
-            Some(error_name) if error_name.starts_with(varlink_service::INTERFACE_NAME) => {
-                // Varlink service interface error need to be returned as the top-level error.
-                Err(crate::Error::VarlinkService(from_slice::<
-                    varlink_service::Error,
-                >(buffer)?))
+        let buffer = self.next_message_bytes();
+
+        parse_reply(buffer, id).map(Some)
+    }
+
+    /// Receives the reply to the method call with the given correlation ID.
+    ///
+    /// Replies to other method calls that are received in the meantime, are buffered and returned
+    /// by subsequent calls to this method with their respective IDs. Replies without an ID are
+    /// assumed to be for the method call being waited on, since a server that doesn't support
+    /// correlation IDs replies to the method calls in order.
+    ///
+    /// At most 64 replies are buffered. If another one arrives,
+    /// [`crate::Error::TooManyPendingReplies`] is returned and the reply is dropped.
+    ///
+    /// **Note**: Correlation IDs are a non-standard extension to the Varlink protocol. See
+    /// [`super::Connection::call_method_correlated`] for details.
+    #[cfg(feature = "std")]
+    pub async fn receive_correlated_reply<'r, ReplyParams, ReplyError>(
+        &'r mut self,
+        call_id: u64,
+    ) -> Result<reply::Result<ReplyParams, ReplyError>>
+    where
+        ReplyParams: Deserialize<'r> + Debug,
+        ReplyError: Deserialize<'r> + Debug,
+    {
+        let id = self.id;
+
+        loop {
+            if let Some(reply) = self.pending_replies.remove(&call_id) {
+                self.correlated_reply = reply;
+                break;
             }
-            Some(_) => from_slice::<ReplyError>(buffer).map(Err),
-            None => {
-                // It's a success response.
-                let ret = from_slice::<Reply<ReplyParams>>(buffer).map(Ok);
-                // SAFETY: Since the parsing from JSON already succeeded, we can be sure that the
-                // buffer contains a valid UTF-8 string.
-                unsafe { log_message(buffer, id) };
-                debug!("connection {}: received reply: {:?}", id, ret);
-
-                ret
+
+            let buffer = self.read_message_bytes().await?;
+            let reply_id = extract_reply_id(buffer);
+            let reply = buffer.to_vec();
+            match reply_id {
+                Some(reply_id) if reply_id != call_id => {
+                    if self.pending_replies.len() >= MAX_PENDING_REPLIES
+                        && !self.pending_replies.contains_key(&reply_id)
+                    {
+                        return Err(crate::Error::TooManyPendingReplies);
+                    }
+                    trace!(
+                        "connection {}: buffering reply to call with ID {}",
+                        id,
+                        reply_id
+                    );
+                    self.pending_replies.insert(reply_id, reply);
+                }
+                _ => {
+                    self.correlated_reply = reply;
+                    break;
+                }
             }
         }
+
+        parse_reply(&self.correlated_reply, id)
     }
 
     /// Receive a method call over the socket.
@@ -137,15 +250,104 @@ impl<Read: ReadHalf> ReadConnection<Read> {
         Ok(call)
     }
 
+    /// Receive a method call over the socket, without parsing its parameters.
+    ///
+    /// Same as [`ReadConnection::receive_call`], except that only the method name and the flags
+    /// are parsed, while the parameters are kept as the raw JSON they were received as. This is
+    /// useful for intermediaries that only route the method calls based on their names.
+    #[cfg(feature = "std")]
+    pub async fn receive_raw_call(&mut self) -> Result<Call<crate::RawMethod<'_>>> {
+        self.receive_call().await
+    }
+
+    /// Receive a method call over the socket, if it's for the given interface.
+    ///
+    /// Same as [`ReadConnection::receive_call`], except that if `interface` is `Some` and the
+    /// method of the received call is not a member of it (or of the `org.varlink.service`
+    /// interface), the call is not deserialized and the method name is returned as the inner
+    /// error instead.
+    pub(crate) async fn receive_call_for<'m, Method>(
+        &'m mut self,
+        interface: Option<&str>,
+    ) -> Result<core::result::Result<Call<Method>, &'m str>>
+    where
+        Method: Deserialize<'m> + Debug,
+    {
+        let id = self.id;
+        let buffer = self.read_message_bytes().await?;
+
+        if let Some(interface) = interface {
+            if let Some(method) = extract_method_name(buffer) {
+                if !is_member_of(method, interface)
+                    && !is_member_of(method, varlink_service::INTERFACE_NAME)
+                {
+                    // SAFETY: If a method name was successfully extracted, it is safe to assume
+                    // that the buffer contains valid UTF-8 data.
+                    unsafe { log_message(buffer, id) };
+                    debug!(
+                        "connection {}: received a call for unknown method: {}",
+                        id, method
+                    );
+
+                    return Ok(Err(method));
+                }
+            }
+        }
+
+        let call = from_slice::<Call<Method>>(buffer)?;
+        // SAFETY: Since the parsing from JSON already succeeded, we can be sure that the
+        // buffer contains a valid UTF-8 string.
+        unsafe { log_message(buffer, id) };
+        debug!("connection {}: received a call: {:?}", id, call);
+
+        Ok(Ok(call))
+    }
+
+    // Receives the reply to a method call that upgrades the connection.
+    //
+    // Unlike `receive_reply`, this stops reading as soon as the reply is complete, since the bytes
+    // following it belong to the new protocol and so don't necessarily end with a null byte.
+    // These bytes are left in the buffer, for `into_parts` to return.
+    #[cfg(feature = "std")]
+    pub(super) async fn receive_upgrade_reply<'r, ReplyParams, ReplyError>(
+        &'r mut self,
+    ) -> Result<reply::Result<ReplyParams, ReplyError>>
+    where
+        ReplyParams: Deserialize<'r> + Debug,
+        ReplyError: Deserialize<'r> + Debug,
+    {
+        let null_index = loop {
+            if let Some(i) = memchr(b'\0', &self.buffer[self.msg_pos..self.read_pos]) {
+                break self.msg_pos + i;
+            }
+
+            let bytes_read = self.socket.read(&mut self.buffer[self.read_pos..]).await?;
+            self.handle_read(bytes_read)?;
+        };
+        let msg_pos = self.msg_pos;
+        self.msg_pos = null_index + 1;
+
+        parse_reply(&self.buffer[msg_pos..null_index], self.id)
+    }
+
     // Reads at least one full message from the socket and return a single message bytes.
     pub(super) async fn read_message_bytes(&mut self) -> Result<&'_ [u8]> {
         self.read_from_socket().await?;
 
-        // Unwrap is safe because `read_from_socket` call above ensures at least one null byte in
-        // the buffer.
-        let null_index = memchr(b'\0', &self.buffer[self.msg_pos..]).unwrap() + self.msg_pos;
+        Ok(self.next_message_bytes())
+    }
+
+    // Returns the bytes of the next message in the buffer.
+    //
+    // Must only be called after at least one full message has been read from the socket.
+    fn next_message_bytes(&mut self) -> &'_ [u8] {
+        // Unwrap is safe because the callers ensure at least one null byte in the buffer.
+        let null_index =
+            memchr(b'\0', &self.buffer[self.msg_pos..self.read_pos]).unwrap() + self.msg_pos;
         let buffer = &self.buffer[self.msg_pos..null_index];
-        if self.buffer[null_index + 1] == b'\0' {
+        // The message can end right at the end of the buffer, in which case there is no room for
+        // the terminating null byte after it.
+        if null_index + 1 == self.read_pos || self.buffer[null_index + 1] == b'\0' {
             // This means we're reading the last message and can now reset the indices.
             self.read_pos = 0;
             self.msg_pos = 0;
@@ -153,7 +355,7 @@ impl<Read: ReadHalf> ReadConnection<Read> {
             self.msg_pos = null_index + 1;
         }
 
-        Ok(buffer)
+        buffer
     }
 
     // Reads at least one full message from the socket.
@@ -165,44 +367,102 @@ impl<Read: ReadHalf> ReadConnection<Read> {
 
         loop {
             let bytes_read = self.socket.read(&mut self.buffer[self.read_pos..]).await?;
-            if bytes_read == 0 {
-                #[cfg(not(feature = "std"))]
-                return Err(crate::Error::SocketRead);
-                #[cfg(feature = "std")]
-                return Err(crate::Error::Io(std::io::Error::new(
-                    std::io::ErrorKind::UnexpectedEof,
-                    "unexpected EOF",
-                )));
+            if self.handle_read(bytes_read)? {
+                break;
             }
-            self.read_pos += bytes_read;
+        }
 
-            #[cfg(feature = "std")]
-            if self.read_pos == self.buffer.len() {
-                if self.read_pos >= MAX_BUFFER_SIZE {
-                    return Err(crate::Error::BufferOverflow);
-                }
+        Ok(())
+    }
 
-                self.buffer.extend(core::iter::repeat_n(0, BUFFER_SIZE));
+    // Same as `read_from_socket` but without waiting for data.
+    //
+    // Returns `false` if a full message isn't available yet.
+    fn try_read_from_socket(&mut self) -> Result<bool> {
+        if self.msg_pos > 0 {
+            return Ok(true);
+        }
+
+        loop {
+            let Some(bytes_read) = self.socket.try_read(&mut self.buffer[self.read_pos..])? else {
+                return Ok(false);
+            };
+            if self.handle_read(bytes_read)? {
+                return Ok(true);
             }
+        }
+    }
 
-            // This marks end of all messages. After this loop is finished, we'll have 2 consecutive
-            // null bytes at the end. This is then used by the callers to determine that they've
-            // read all messages and can now reset the `read_pos`.
-            self.buffer[self.read_pos] = b'\0';
+    // Accounts for `bytes_read` bytes read into the buffer.
+    //
+    // Returns `true` if one or more full messages are now in the buffer.
+    fn handle_read(&mut self, bytes_read: usize) -> Result<bool> {
+        if bytes_read == 0 {
+            #[cfg(not(feature = "std"))]
+            return Err(crate::Error::SocketRead);
+            #[cfg(feature = "std")]
+            return Err(crate::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "unexpected EOF",
+            )));
+        }
+        self.read_pos += bytes_read;
+        #[cfg(feature = "std")]
+        {
+            self.last_activity = std::time::Instant::now();
+        }
 
-            if self.buffer[self.read_pos - 1] == b'\0' {
-                // One or more full messages were read.
-                break;
+        #[cfg(feature = "std")]
+        if self.read_pos == self.buffer.len() {
+            if self.read_pos >= MAX_BUFFER_SIZE {
+                return Err(crate::Error::BufferOverflow);
             }
+
+            self.buffer.extend(core::iter::repeat_n(0, BUFFER_SIZE));
         }
 
-        Ok(())
+        // One or more full messages were read, if the last byte read is a null byte.
+        let complete = self.buffer[self.read_pos - 1] == b'\0';
+        if self.read_pos == self.buffer.len() {
+            // Without `std`, the buffer can't grow, so there is no room to read the rest of an
+            // incomplete message. There is no room for the null byte marking the end of all
+            // messages either, but that's not needed if the last message ends right here.
+            return if complete {
+                Ok(true)
+            } else {
+                Err(crate::Error::BufferOverflow)
+            };
+        }
+
+        // This marks end of all messages. Once a full message is read, we'll have 2 consecutive
+        // null bytes at the end. This is then used by the callers to determine that they've
+        // read all messages and can now reset the `read_pos`.
+        self.buffer[self.read_pos] = b'\0';
+
+        Ok(complete)
+    }
+
+    /// When data was last read from the socket.
+    ///
+    /// This is the creation time of the connection if nothing has been read yet.
+    #[cfg(feature = "std")]
+    pub fn last_activity(&self) -> std::time::Instant {
+        self.last_activity
     }
 
     /// The underlying read half of the socket.
     pub fn read_half(&self) -> &Read {
         &self.socket
     }
+
+    /// Consume the connection and return the read half of the socket, along with any bytes that
+    /// were read from it but not consumed yet.
+    #[cfg(feature = "std")]
+    pub(super) fn into_parts(self) -> (Read, std::vec::Vec<u8>) {
+        let buffered = self.buffer[self.msg_pos..self.read_pos].to_vec();
+
+        (self.socket, buffered)
+    }
 }
 
 fn from_slice<'a, T>(buffer: &'a [u8]) -> Result<T>
@@ -222,6 +482,56 @@ where
     }
 }
 
+/// Parses a reply message.
+fn parse_reply<'r, ReplyParams, ReplyError>(
+    buffer: &'r [u8],
+    id: usize,
+) -> Result<reply::Result<ReplyParams, ReplyError>>
+where
+    ReplyParams: Deserialize<'r> + Debug,
+    ReplyError: Deserialize<'r> + Debug,
+{
+    // First, check if the message has an "error" field to determine how to deserialize.
+    // FIXME: This will mean the document will be parsed twice. We should instead try to
+    // quickly check if `error` field is present and then parse to the appropriate type based on
+    // that information. Perhaps a simple parser using `winnow`?
+    let error_name = extract_error_name(buffer);
+    if error_name.is_some() {
+        // SAFETY: If an error name was successfully extracted, it is safe to assume that the
+        // buffer contains valid UTF-8 data.
+        unsafe { log_message(buffer, id) };
+    }
+    match error_name {
+        Some(error_name) if error_name.starts_with(varlink_service::INTERFACE_NAME) => {
+            // Varlink service interface error need to be returned as the top-level error.
+            Err(crate::Error::VarlinkService(from_slice::<
+                varlink_service::Error,
+            >(buffer)?))
+        }
+        Some(_) => from_slice::<ReplyError>(buffer).map(Err),
+        None => {
+            // It's a success response.
+            let ret = from_slice::<Reply<ReplyParams>>(buffer).map(Ok);
+            // SAFETY: Since the parsing from JSON already succeeded, we can be sure that the
+            // buffer contains a valid UTF-8 string.
+            unsafe { log_message(buffer, id) };
+            debug!("connection {}: received reply: {:?}", id, ret);
+
+            ret
+        }
+    }
+}
+
+/// If the buffer contains a JSON object with an "id" field, this function will fetch it.
+#[cfg(feature = "std")]
+fn extract_reply_id(buffer: &[u8]) -> Option<u64> {
+    #[derive(Deserialize)]
+    struct Reply {
+        id: Option<u64>,
+    }
+    from_slice::<Reply>(buffer).ok().and_then(|reply| reply.id)
+}
+
 /// If the buffer contains a JSON object with an "error" field, this function will fetch it.
 fn extract_error_name(buffer: &[u8]) -> Option<&str> {
     #[derive(Deserialize)]
@@ -233,6 +543,23 @@ fn extract_error_name(buffer: &[u8]) -> Option<&str> {
         .map(|error| error.error)
 }
 
+/// If the buffer contains a JSON object with a "method" field, this function will fetch it.
+fn extract_method_name(buffer: &[u8]) -> Option<&str> {
+    #[derive(Deserialize)]
+    struct Call<'a> {
+        method: &'a str,
+    }
+    from_slice::<Call<'_>>(buffer).ok().map(|call| call.method)
+}
+
+/// Whether the fully-qualified `method` name is a member of `interface`.
+fn is_member_of(method: &str, interface: &str) -> bool {
+    method
+        .strip_prefix(interface)
+        .and_then(|name| name.strip_prefix('.'))
+        .is_some_and(|name| !name.is_empty() && !name.contains('.'))
+}
+
 /// Logs a message received by the connection.
 ///
 /// # Safety
@@ -246,3 +573,181 @@ unsafe fn log_message(buffer: &[u8], connection_id: usize) {
         unsafe { from_utf8_unchecked(buffer) },
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::mock_socket::MockSocket, Connection, ReplyError};
+
+    #[derive(Debug, PartialEq, ReplyError)]
+    #[zlink(interface = "com.example")]
+    enum TestError {
+        NotFound,
+        InvalidInput { reason: i32 },
+    }
+
+    #[tokio::test]
+    async fn parameterless_error_reply() -> crate::Result<()> {
+        let responses = [
+            r#"{"error":"com.example.NotFound"}"#,
+            r#"{"error":"com.example.NotFound","parameters":{}}"#,
+            r#"{"error":"com.example.InvalidInput","parameters":{"reason":42}}"#,
+        ];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        let reply = conn.receive_reply::<(), TestError>().await?;
+        assert_eq!(reply.unwrap_err(), TestError::NotFound);
+        let reply = conn.receive_reply::<(), TestError>().await?;
+        assert_eq!(reply.unwrap_err(), TestError::NotFound);
+        let reply = conn.receive_reply::<(), TestError>().await?;
+        assert_eq!(reply.unwrap_err(), TestError::InvalidInput { reason: 42 });
+
+        Ok(())
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Pad<'a> {
+        pad: &'a str,
+    }
+
+    // With `std`, the buffer grows as soon as it's full, so only the fixed-size buffer can end up
+    // full with a complete message.
+    #[cfg(not(feature = "std"))]
+    #[tokio::test]
+    async fn message_ending_at_buffer_boundary() -> crate::Result<()> {
+        // A message that fills the buffer exactly, including its null byte.
+        let mut message = Vec::<u8, BUFFER_SIZE>::new();
+        message
+            .extend_from_slice(br#"{"parameters":{"pad":""#)
+            .unwrap();
+        while message.len() < BUFFER_SIZE - 4 {
+            message.push(b'x').unwrap();
+        }
+        message.extend_from_slice(b"\"}}").unwrap();
+        assert_eq!(message.len(), BUFFER_SIZE - 1);
+        let message = core::str::from_utf8(&message).unwrap();
+
+        let mut conn = Connection::new(MockSocket::new(&[message]));
+        let reply = conn.receive_reply::<Pad<'_>, TestError>().await?.unwrap();
+        assert_eq!(reply.parameters().unwrap().pad.len(), BUFFER_SIZE - 26);
+        assert_eq!(conn.read_mut().read_pos, 0);
+        assert_eq!(conn.read_mut().msg_pos, 0);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn buffer_growth() -> crate::Result<()> {
+        use crate::{connection::socket::Socket, testing::ChannelSocket};
+
+        // A message larger than the initial buffer, split across reads smaller than it.
+        let mut message = std::string::String::from(r#"{"parameters":{"pad":""#);
+        message.extend(core::iter::repeat_n('x', BUFFER_SIZE * 2));
+        message.push_str("\"}}\0");
+        let (socket, handle) = ChannelSocket::new();
+        for chunk in message.as_bytes().chunks(BUFFER_SIZE / 2) {
+            handle.feed(chunk);
+        }
+
+        let (read, _write) = socket.split();
+        let mut conn = ReadConnection::new(read, 0);
+        let reply = conn.receive_reply::<Pad<'_>, TestError>().await?.unwrap();
+        assert_eq!(reply.parameters().unwrap().pad.len(), BUFFER_SIZE * 2);
+        assert!(conn.buffer.len() > BUFFER_SIZE);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn raw_call_parameters_preserved() -> crate::Result<()> {
+        let parameters = r#"{"name":"big", "values":[1, 2.50, {"nested":null}]}"#;
+        let call =
+            format!(r#"{{"method":"com.example.Store","parameters":{parameters},"more":true}}"#);
+        let mut conn = Connection::new(MockSocket::new(&[&call]));
+
+        let call = conn.read_mut().receive_raw_call().await?;
+        assert_eq!(call.method().method, "com.example.Store");
+        assert!(call.more());
+        assert_eq!(call.method().parameters.unwrap().get(), parameters);
+
+        // Re-serializing keeps the parameters byte-for-byte.
+        let serialized = serde_json::to_string(&call)?;
+        let expected =
+            format!(r#"{{"method":"com.example.Store","parameters":{parameters},"more":true}}"#);
+        assert_eq!(serialized, expected);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn correlated_replies() -> crate::Result<()> {
+        use crate::Call;
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Debug, Serialize)]
+        #[serde(tag = "method", content = "parameters")]
+        enum Methods {
+            #[serde(rename = "com.example.Get")]
+            Get { key: u32 },
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Value {
+            value: u32,
+        }
+
+        // The server replies to the second call first.
+        let responses = [
+            r#"{"parameters":{"value":20},"id":2}"#,
+            r#"{"parameters":{"value":10},"id":1}"#,
+        ];
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        let first = Call::new(Methods::Get { key: 1 }).set_id(Some(1));
+        let second = Call::new(Methods::Get { key: 2 }).set_id(Some(2));
+        conn.send_call(&first).await?;
+        let reply = conn
+            .call_method_correlated::<_, Value, TestError>(&second)
+            .await?
+            .unwrap();
+        assert_eq!(reply.id(), Some(2));
+        assert_eq!(reply.parameters().unwrap().value, 20);
+
+        // The reply to the first call was buffered.
+        let reply = conn
+            .receive_correlated_reply::<Value, TestError>(1)
+            .await?
+            .unwrap();
+        assert_eq!(reply.id(), Some(1));
+        assert_eq!(reply.parameters().unwrap().value, 10);
+
+        let written = std::str::from_utf8(conn.write().write_half().written_data()).unwrap();
+        assert!(written.contains(r#""parameters":{"key":1},"id":1}"#));
+        assert!(written.contains(r#""parameters":{"key":2},"id":2}"#));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn too_many_pending_replies() -> crate::Result<()> {
+        // None of the replies are for the call being waited on.
+        let responses: std::vec::Vec<_> = (0..=MAX_PENDING_REPLIES)
+            .map(|id| format!(r#"{{"id":{}}}"#, id + 2))
+            .collect();
+        let responses: std::vec::Vec<_> = responses.iter().map(|r| r.as_str()).collect();
+        let mut conn = Connection::new(MockSocket::new(&responses));
+
+        let err = conn
+            .receive_correlated_reply::<(), TestError>(1)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::Error::TooManyPendingReplies));
+        assert_eq!(conn.read().pending_replies.len(), MAX_PENDING_REPLIES);
+
+        Ok(())
+    }
+}
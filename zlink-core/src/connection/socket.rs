@@ -2,9 +2,18 @@
 
 use core::future::Future;
 
+use futures_util::FutureExt;
+
 /// The socket trait.
 ///
 /// This is the trait that needs to be implemented for a type to be used as a socket/transport.
+///
+/// The trait is independent of any async runtime. Besides the implementations provided by the
+/// runtime-specific crates (e.g `zlink-tokio`), an implementation over [`embedded-io-async`]
+/// devices is available through the `embedded-io-async` feature as `EmbeddedIoSocket`, for use in
+/// `no_std` environments.
+///
+/// [`embedded-io-async`]: https://docs.rs/embedded-io-async
 pub trait Socket: core::fmt::Debug {
     /// The read half of the socket.
     type ReadHalf: ReadHalf;
@@ -30,6 +39,18 @@ pub trait ReadHalf: core::fmt::Debug {
     ///   implemention that use `async fn`, which is undesirable for embedded use cases. See [this
     ///   issue](https://github.com/rust-lang/rust/issues/82187) for details.
     fn read(&mut self, buf: &mut [u8]) -> impl Future<Output = crate::Result<usize>>;
+
+    /// Read from a socket, without waiting for data to become available.
+    ///
+    /// Returns `None` if no data is immediately available. Otherwise, the number of bytes read is
+    /// returned.
+    ///
+    /// The default implementation polls the future returned by [`ReadHalf::read`] once and drops
+    /// it if it's not ready, relying on its cancel safety. Implementers may override this with a
+    /// native nonblocking read.
+    fn try_read(&mut self, buf: &mut [u8]) -> crate::Result<Option<usize>> {
+        self.read(buf).now_or_never().transpose()
+    }
 }
 
 /// The write half of a socket.
@@ -38,6 +59,16 @@ pub trait WriteHalf: core::fmt::Debug {
     ///
     /// The returned future has the same requirements as that of [`ReadHalf::read`].
     fn write(&mut self, buf: &[u8]) -> impl Future<Output = crate::Result<()>>;
+
+    /// Shut down the write side of the socket.
+    ///
+    /// After this, the peer sees the end of the stream while the read half remains usable.
+    ///
+    /// The default implementation does nothing, for transports that have no notion of a
+    /// half-close.
+    fn shutdown(&mut self) -> impl Future<Output = crate::Result<()>> {
+        core::future::ready(Ok(()))
+    }
 }
 
 /// Documentation-only socket implementations for doc tests.
@@ -0,0 +1,232 @@
+//! Recording of the messages exchanged over a connection.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::SystemTime,
+    vec::Vec,
+};
+
+use super::{
+    socket::{ReadHalf, Socket, WriteHalf},
+    Connection,
+};
+
+/// A connection that records a transcript of all the messages sent and received over it.
+///
+/// Since this is just a [`Connection`] over a [`TranscriptSocket`], it provides the exact same API
+/// as any other connection. Use [`Connection::transcript`] to get the recorded messages.
+///
+/// Unlike logging, the transcript captures the full payloads as they were sent or received, which
+/// is useful for debugging the interaction between a client and a service, or to replay it later.
+pub type TranscriptConnection<S> = Connection<TranscriptSocket<S>>;
+
+impl<S: Socket> TranscriptConnection<S> {
+    /// Create a new connection that records a transcript of its messages.
+    pub fn with_transcript(socket: S) -> Self {
+        Self::new(TranscriptSocket::new(socket))
+    }
+
+    /// The messages sent and received so far, in the order they were sent or received.
+    pub fn transcript(&self) -> Vec<TranscriptEntry> {
+        self.read().read_half().transcript.lock().unwrap().clone()
+    }
+}
+
+/// The direction of a message in a transcript.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// The message was sent.
+    Sent,
+    /// The message was received.
+    Received,
+}
+
+/// A message recorded in a transcript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptEntry {
+    direction: Direction,
+    timestamp: SystemTime,
+    bytes: Vec<u8>,
+}
+
+impl TranscriptEntry {
+    /// The direction of the message.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// When the message was sent or received.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// The bytes of the message, without the null terminator.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+type Transcript = Arc<Mutex<Vec<TranscriptEntry>>>;
+
+/// A [`Socket`] that records the messages sent and received through it.
+///
+/// See [`TranscriptConnection`].
+#[derive(Debug)]
+pub struct TranscriptSocket<S> {
+    socket: S,
+    transcript: Transcript,
+}
+
+impl<S: Socket> TranscriptSocket<S> {
+    /// Wrap `socket` to record the messages sent and received through it.
+    pub fn new(socket: S) -> Self {
+        Self {
+            socket,
+            transcript: Transcript::default(),
+        }
+    }
+}
+
+impl<S: Socket> Socket for TranscriptSocket<S> {
+    type ReadHalf = TranscriptReadHalf<S::ReadHalf>;
+    type WriteHalf = TranscriptWriteHalf<S::WriteHalf>;
+
+    fn split(self) -> (Self::ReadHalf, Self::WriteHalf) {
+        let (read, write) = self.socket.split();
+
+        (
+            TranscriptReadHalf {
+                socket: read,
+                transcript: self.transcript.clone(),
+                pending: Vec::new(),
+            },
+            TranscriptWriteHalf {
+                socket: write,
+                transcript: self.transcript,
+            },
+        )
+    }
+}
+
+/// The read half of a [`TranscriptSocket`].
+#[derive(Debug)]
+pub struct TranscriptReadHalf<R> {
+    socket: R,
+    transcript: Transcript,
+    // Bytes of a message that has only been partially read so far.
+    pending: Vec<u8>,
+}
+
+impl<R: ReadHalf> TranscriptReadHalf<R> {
+    fn record(&mut self, bytes: &[u8]) {
+        let mut transcript = self.transcript.lock().unwrap();
+        for byte in bytes {
+            if *byte != b'\0' {
+                self.pending.push(*byte);
+            } else if !self.pending.is_empty() {
+                transcript.push(TranscriptEntry {
+                    direction: Direction::Received,
+                    timestamp: SystemTime::now(),
+                    bytes: core::mem::take(&mut self.pending),
+                });
+            }
+        }
+    }
+}
+
+impl<R: ReadHalf> ReadHalf for TranscriptReadHalf<R> {
+    async fn read(&mut self, buf: &mut [u8]) -> crate::Result<usize> {
+        let len = self.socket.read(buf).await?;
+        self.record(&buf[..len]);
+
+        Ok(len)
+    }
+
+    fn try_read(&mut self, buf: &mut [u8]) -> crate::Result<Option<usize>> {
+        let len = self.socket.try_read(buf)?;
+        if let Some(len) = len {
+            self.record(&buf[..len]);
+        }
+
+        Ok(len)
+    }
+}
+
+/// The write half of a [`TranscriptSocket`].
+#[derive(Debug)]
+pub struct TranscriptWriteHalf<W> {
+    socket: W,
+    transcript: Transcript,
+}
+
+impl<W: WriteHalf> WriteHalf for TranscriptWriteHalf<W> {
+    async fn write(&mut self, buf: &[u8]) -> crate::Result<()> {
+        self.socket.write(buf).await?;
+
+        // Each write contains one or more complete messages.
+        let timestamp = SystemTime::now();
+        let mut transcript = self.transcript.lock().unwrap();
+        transcript.extend(
+            buf.split(|b| *b == b'\0')
+                .filter(|message| !message.is_empty())
+                .map(|message| TranscriptEntry {
+                    direction: Direction::Sent,
+                    timestamp,
+                    bytes: message.to_vec(),
+                }),
+        );
+
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> crate::Result<()> {
+        self.socket.shutdown().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::mock_socket::MockSocket, Call};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize)]
+    #[serde(tag = "method", content = "parameters")]
+    enum Methods {
+        #[serde(rename = "org.example.Get")]
+        Get { id: u32 },
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Value {
+        value: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct TestError {}
+
+    #[tokio::test]
+    async fn records_both_directions() -> crate::Result<()> {
+        let responses = [r#"{"parameters":{"value":42}}"#];
+        let mut conn = TranscriptConnection::with_transcript(MockSocket::new(&responses));
+
+        let reply = conn
+            .call_method::<_, Value, TestError>(&Call::new(Methods::Get { id: 1 }))
+            .await?
+            .unwrap();
+        assert_eq!(reply.parameters().unwrap().value, 42);
+
+        let transcript = conn.transcript();
+        assert_eq!(transcript.len(), 2);
+        assert_eq!(transcript[0].direction(), Direction::Sent);
+        assert_eq!(
+            transcript[0].bytes(),
+            br#"{"method":"org.example.Get","parameters":{"id":1}}"#
+        );
+        assert_eq!(transcript[1].direction(), Direction::Received);
+        assert_eq!(transcript[1].bytes(), responses[0].as_bytes());
+        assert!(transcript[0].timestamp() <= transcript[1].timestamp());
+
+        Ok(())
+    }
+}
@@ -21,16 +21,50 @@ pub struct WriteConnection<Write: WriteHalf> {
     buffer: Vec<u8, BUFFER_SIZE>,
     pos: usize,
     id: usize,
+    #[cfg(feature = "idl")]
+    interface: Option<&'static crate::idl::Interface<'static>>,
+    #[cfg(feature = "std")]
+    coalesce: Option<std::time::Duration>,
+    // When the oldest of the currently coalesced calls was enqueued.
+    #[cfg(feature = "std")]
+    coalesce_start: Option<std::time::Instant>,
+    #[cfg(feature = "std")]
+    last_activity: std::time::Instant,
+    #[cfg(feature = "std")]
+    watermark: Option<Watermark>,
 }
 
 impl<Write: WriteHalf> WriteConnection<Write> {
     /// Create a new connection.
     pub(super) fn new(socket: Write, id: usize) -> Self {
+        Self::with_buffer(socket, id, Vec::from_slice(&[0; BUFFER_SIZE]).unwrap())
+    }
+
+    /// Create a new connection with an initial buffer of `buffer_size` bytes.
+    #[cfg(feature = "std")]
+    pub(super) fn with_buffer_size(socket: Write, id: usize, buffer_size: usize) -> Self {
+        let mut buffer = Vec::new();
+        buffer.extend(core::iter::repeat_n(0, buffer_size));
+
+        Self::with_buffer(socket, id, buffer)
+    }
+
+    fn with_buffer(socket: Write, id: usize, buffer: Vec<u8, BUFFER_SIZE>) -> Self {
         Self {
             socket,
             id,
-            buffer: Vec::from_slice(&[0; BUFFER_SIZE]).unwrap(),
+            buffer,
             pos: 0,
+            #[cfg(feature = "idl")]
+            interface: None,
+            #[cfg(feature = "std")]
+            coalesce: None,
+            #[cfg(feature = "std")]
+            coalesce_start: None,
+            #[cfg(feature = "std")]
+            last_activity: std::time::Instant::now(),
+            #[cfg(feature = "std")]
+            watermark: None,
         }
     }
 
@@ -40,6 +74,72 @@ impl<Write: WriteHalf> WriteConnection<Write> {
         self.id
     }
 
+    /// The interface that sent calls and replies are validated against.
+    #[cfg(feature = "idl")]
+    #[inline]
+    pub fn interface(&self) -> Option<&'static crate::idl::Interface<'static>> {
+        self.interface
+    }
+
+    /// Set the interface that sent calls and replies are validated against.
+    ///
+    /// In debug builds, [`WriteConnection::send_call`] and [`WriteConnection::send_reply`] check
+    /// the serialized message against the method signatures of this interface and log a warning
+    /// on any mismatch. This helps catch discrepancies between hand-written types and the IDL
+    /// during development. The message is sent regardless. In release builds, no validation is
+    /// performed.
+    #[cfg(feature = "idl")]
+    pub fn set_interface(&mut self, interface: Option<&'static crate::idl::Interface<'static>>) {
+        self.interface = interface;
+    }
+
+    /// The duration for which sent calls are coalesced, if enabled.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn coalesce(&self) -> Option<std::time::Duration> {
+        self.coalesce
+    }
+
+    /// Enable or disable coalescing of sent calls.
+    ///
+    /// When enabled, [`WriteConnection::send_call`] only enqueues the call. The enqueued calls are
+    /// written together by the first [`WriteConnection::send_call`] made after `duration` has
+    /// passed since the oldest of them was enqueued, that fills up the buffer, or whose call has
+    /// [`Call::more`] or [`Call::oneway`] set. This reduces the number of writes when sending many
+    /// small calls back-to-back.
+    ///
+    /// There is no timer involved, so the enqueued calls are **not** written on their own once
+    /// `duration` has passed. Call [`WriteConnection::flush`] to write them, e.g after the last of
+    /// a batch of calls. The receiving methods of [`super::Connection`] do that for you.
+    #[cfg(feature = "std")]
+    pub fn set_coalesce(&mut self, duration: Option<std::time::Duration>) {
+        self.coalesce = duration;
+    }
+
+    /// Set a callback to be invoked when the enqueued data crosses `bytes`.
+    ///
+    /// The callback is invoked with the number of buffered bytes whenever enqueuing a message
+    /// makes the amount of data that is enqueued but not yet flushed go from below `bytes` to
+    /// `bytes` or more. This allows applying backpressure, e.g. by pausing the production of
+    /// messages until [`WriteConnection::flush`] is called. Any previously set callback is
+    /// replaced.
+    #[cfg(feature = "std")]
+    pub fn set_watermark<F>(&mut self, bytes: usize, callback: F)
+    where
+        F: FnMut(usize) + Send + 'static,
+    {
+        self.watermark = Some(Watermark {
+            bytes,
+            callback: Box::new(callback),
+        });
+    }
+
+    /// Remove the callback set through [`WriteConnection::set_watermark`].
+    #[cfg(feature = "std")]
+    pub fn clear_watermark(&mut self) {
+        self.watermark = None;
+    }
+
     /// Sends a method call.
     ///
     /// The generic `Method` is the type of the method name and its input parameters. This should be
@@ -66,6 +166,29 @@ impl<Write: WriteHalf> WriteConnection<Write> {
         Method: Serialize + Debug,
     {
         trace!("connection {}: sending call: {:?}", self.id, call);
+        #[cfg(all(debug_assertions, feature = "std", feature = "idl"))]
+        if let Err(e) = self.validate_call(call) {
+            warn!(
+                "connection {}: call doesn't match the interface: {}",
+                self.id, e
+            );
+        }
+        #[cfg(feature = "std")]
+        if let Some(duration) = self.coalesce {
+            // The buffer grows if the call doesn't fit, so check against its size before that.
+            let capacity = self.buffer.len();
+            self.enqueue(call)?;
+            let start = *self
+                .coalesce_start
+                .get_or_insert_with(std::time::Instant::now);
+            // The replies of `more` calls are read through streams that don't flush, and `oneway`
+            // calls get no reply whose receipt would flush them, so don't hold these back.
+            if start.elapsed() < duration && self.pos < capacity && !call.more() && !call.oneway() {
+                return Ok(());
+            }
+
+            return self.flush().await;
+        }
         self.write(call).await
     }
 
@@ -78,6 +201,13 @@ impl<Write: WriteHalf> WriteConnection<Write> {
         Params: Serialize + Debug,
     {
         trace!("connection {}: sending reply: {:?}", self.id, reply);
+        #[cfg(all(debug_assertions, feature = "std", feature = "idl"))]
+        if let Err(e) = self.validate_reply(reply) {
+            warn!(
+                "connection {}: reply doesn't match the interface: {}",
+                self.id, e
+            );
+        }
         self.write(reply).await
     }
 
@@ -95,6 +225,45 @@ impl<Write: WriteHalf> WriteConnection<Write> {
         self.write(error).await
     }
 
+    /// Send a reply or an error reply over the socket, depending on `result`.
+    ///
+    /// This dispatches to [`WriteConnection::send_reply`] or [`WriteConnection::send_error`], so
+    /// the outcome of a method call can be sent without matching on it.
+    pub async fn send_result<Params, ReplyError>(
+        &mut self,
+        result: &crate::reply::Result<Params, ReplyError>,
+    ) -> crate::Result<()>
+    where
+        Params: Serialize + Debug,
+        ReplyError: Serialize + Debug,
+    {
+        match result {
+            Ok(reply) => self.send_reply(reply).await,
+            Err(error) => self.send_error(error).await,
+        }
+    }
+
+    /// Send a raw message over the socket.
+    ///
+    /// The bytes are sent as is, followed by the NUL terminator, bypassing serialization. This is
+    /// useful for forwarding or replaying messages that were captured before. A single trailing
+    /// NUL in `message` is ignored, but [`crate::Error::InteriorNul`] is returned if it contains
+    /// any other NUL byte.
+    pub async fn send_raw(&mut self, message: &[u8]) -> crate::Result<()> {
+        let message = message.strip_suffix(b"\0").unwrap_or(message);
+        if message.contains(&b'\0') {
+            return Err(crate::Error::InteriorNul);
+        }
+
+        trace!(
+            "connection {}: sending raw message of {} bytes",
+            self.id,
+            message.len()
+        );
+        self.enqueue_raw(message)?;
+        self.flush().await
+    }
+
     /// Enqueue a call to be sent over the socket.
     ///
     /// Similar to [`WriteConnection::send_call`], except that the call is not sent immediately but
@@ -115,16 +284,54 @@ impl<Write: WriteHalf> WriteConnection<Write> {
         }
 
         trace!("connection {}: flushing {} bytes", self.id, self.pos);
-        self.socket.write(&self.buffer[..self.pos]).await?;
+        self.socket
+            .write(&self.buffer[..self.pos])
+            .await
+            .map_err(|e| crate::Error::write(self.id, e))?;
         self.pos = 0;
+        #[cfg(feature = "std")]
+        {
+            self.coalesce_start = None;
+            self.last_activity = std::time::Instant::now();
+        }
         Ok(())
     }
 
+    /// Flush the enqueued messages and shut down the write side of the socket.
+    ///
+    /// The peer then sees the end of the stream, while replies can still be read from the other
+    /// half of the connection. Nothing must be sent after this.
+    pub async fn shutdown(&mut self) -> crate::Result<()> {
+        self.flush().await?;
+
+        trace!("connection {}: shutting down the write side", self.id);
+        self.socket
+            .shutdown()
+            .await
+            .map_err(|e| crate::Error::write(self.id, e))
+    }
+
+    /// When data was last written to the socket.
+    ///
+    /// This is the creation time of the connection if nothing has been written yet.
+    #[cfg(feature = "std")]
+    pub fn last_activity(&self) -> std::time::Instant {
+        self.last_activity
+    }
+
     /// The underlying write half of the socket.
     pub fn write_half(&self) -> &Write {
         &self.socket
     }
 
+    /// Consume the connection and return the write half of the socket.
+    ///
+    /// Any enqueued data that wasn't flushed yet is dropped.
+    #[cfg(feature = "std")]
+    pub(super) fn into_socket(self) -> Write {
+        self.socket
+    }
+
     async fn write<T>(&mut self, value: &T) -> crate::Result<()>
     where
         T: Serialize + ?Sized + Debug,
@@ -141,12 +348,12 @@ impl<Write: WriteHalf> WriteConnection<Write> {
             match to_slice_at_pos(value, &mut self.buffer, self.pos) {
                 Ok(len) => break len,
                 #[cfg(feature = "std")]
-                Err(crate::Error::Json(e)) if e.is_io() => {
+                Err(crate::Error::BufferOverflow) => {
                     // This can only happens if `serde-json` failed to write all bytes and that
                     // means we're running out of space or already are out of space.
                     self.grow_buffer()?;
                 }
-                Err(e) => return Err(e),
+                Err(e) => return Err(crate::Error::write(self.id, e)),
             }
         };
 
@@ -162,19 +369,99 @@ impl<Write: WriteHalf> WriteConnection<Write> {
             }
         }
         self.buffer[self.pos + len] = b'\0';
-        self.pos += len + 1;
+        self.advance(len + 1);
         Ok(())
     }
 
+    fn enqueue_raw(&mut self, message: &[u8]) -> crate::Result<()> {
+        let end = self.pos + message.len();
+        // Make room for the message and its null terminator.
+        #[cfg(feature = "std")]
+        while end >= self.buffer.len() {
+            self.grow_buffer()?;
+        }
+        #[cfg(not(feature = "std"))]
+        if end >= self.buffer.len() {
+            return Err(crate::Error::BufferOverflow);
+        }
+        self.buffer[self.pos..end].copy_from_slice(message);
+        self.buffer[end] = b'\0';
+        self.advance(message.len() + 1);
+        Ok(())
+    }
+
+    // Account for `len` newly enqueued bytes, notifying the watermark callback if needed.
+    fn advance(&mut self, len: usize) {
+        #[cfg(feature = "std")]
+        let previous = self.pos;
+        self.pos += len;
+
+        #[cfg(feature = "std")]
+        if let Some(watermark) = &mut self.watermark {
+            if previous < watermark.bytes && self.pos >= watermark.bytes {
+                (watermark.callback)(self.pos);
+            }
+        }
+    }
+
+    /// Validates `call` against the interface, if one is set.
+    ///
+    /// Calls to methods of other interfaces are not validated.
+    #[cfg(all(debug_assertions, feature = "std", feature = "idl"))]
+    fn validate_call<Method>(&self, call: &Call<Method>) -> Result<(), std::string::String>
+    where
+        Method: Serialize + Debug,
+    {
+        let Some(interface) = self.interface else {
+            return Ok(());
+        };
+        let value = serde_json::to_value(call).map_err(|e| e.to_string())?;
+        let Some(name) = value["method"]
+            .as_str()
+            .and_then(|m| m.strip_prefix(interface.name()))
+            .and_then(|m| m.strip_prefix('.'))
+        else {
+            return Ok(());
+        };
+
+        let method = interface
+            .methods()
+            .find(|m| m.name() == name)
+            .ok_or_else(|| format!("method `{name}` not found"))?;
+
+        crate::idl::validate_parameters(method.inputs(), value.get("parameters"), interface)
+    }
+
+    /// Validates `reply` against the interface, if one is set.
+    #[cfg(all(debug_assertions, feature = "std", feature = "idl"))]
+    fn validate_reply<Params>(&self, reply: &Reply<Params>) -> Result<(), std::string::String>
+    where
+        Params: Serialize + Debug,
+    {
+        let Some(interface) = self.interface else {
+            return Ok(());
+        };
+        let value = serde_json::to_value(reply).map_err(|e| e.to_string())?;
+
+        // Replies don't carry the method name so the best we can do is to check if the parameters
+        // match the outputs of any of the methods.
+        let parameters = value.get("parameters");
+        interface
+            .methods()
+            .any(|m| crate::idl::validate_parameters(m.outputs(), parameters, interface).is_ok())
+            .then_some(())
+            .ok_or_else(|| "parameters don't match the outputs of any method".into())
+    }
+
     #[cfg(feature = "std")]
     fn grow_buffer(&mut self) -> crate::Result<()> {
         if self.buffer.len() >= super::MAX_BUFFER_SIZE {
-            return Err(crate::Error::BufferOverflow);
+            return Err(crate::Error::write(self.id, crate::Error::BufferOverflow));
         }
 
-        self.buffer.extend_from_slice(&[0; BUFFER_SIZE])?;
-
-        Ok(())
+        self.buffer
+            .extend_from_slice(&[0; BUFFER_SIZE])
+            .map_err(|e| crate::Error::write(self.id, e.into()))
     }
 }
 
@@ -185,7 +472,14 @@ where
     #[cfg(feature = "std")]
     {
         let mut cursor = std::io::Cursor::new(&mut buf[pos..]);
-        serde_json::to_writer(&mut cursor, value)?;
+        serde_json::to_writer(&mut cursor, value).map_err(|e| {
+            if e.is_io() {
+                // The only I/O error possible here is running out of space in the buffer.
+                crate::Error::BufferOverflow
+            } else {
+                e.into()
+            }
+        })?;
 
         Ok(cursor.position() as usize)
     }
@@ -196,6 +490,22 @@ where
     }
 }
 
+/// The callback set through [`WriteConnection::set_watermark`].
+#[cfg(feature = "std")]
+struct Watermark {
+    bytes: usize,
+    callback: Box<dyn FnMut(usize) + Send>,
+}
+
+#[cfg(feature = "std")]
+impl Debug for Watermark {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Watermark")
+            .field("bytes", &self.bytes)
+            .finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -294,6 +604,30 @@ mod tests {
         ));
     }
 
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn watermark() {
+        use std::sync::{Arc, Mutex};
+
+        let mut write_conn = WriteConnection::new(TestWriteHalf::new(7), 1); // "42\03\07\0"
+        let crossings = Arc::new(Mutex::new(std::vec::Vec::new()));
+        let recorded = crossings.clone();
+        write_conn.set_watermark(5, move |len| recorded.lock().unwrap().push(len));
+
+        write_conn.enqueue(&42u32).unwrap();
+        assert!(crossings.lock().unwrap().is_empty());
+        write_conn.enqueue(&3u32).unwrap();
+        assert_eq!(*crossings.lock().unwrap(), [5]);
+        // Already above the watermark, so no new crossing.
+        write_conn.enqueue(&7u32).unwrap();
+        assert_eq!(*crossings.lock().unwrap(), [5]);
+
+        // Flushing brings the buffered size back below the watermark.
+        write_conn.flush().await.unwrap();
+        write_conn.enqueue(&12345u32).unwrap();
+        assert_eq!(*crossings.lock().unwrap(), [5, 6]);
+    }
+
     #[tokio::test]
     async fn flush_empty_buffer() {
         // Test that flushing an empty buffer is a no-op.
@@ -304,6 +638,49 @@ mod tests {
         assert_eq!(write_conn.pos, 0);
     }
 
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn write_error_carries_id() {
+        use crate::test_utils::mock_socket::FailingWriteHalf;
+
+        let mut write_conn = WriteConnection::new(FailingWriteHalf, 42);
+
+        let res = write_conn.send_reply(&Reply::new(Some(1u32))).await;
+        match res {
+            Err(crate::Error::Write { id, source }) => {
+                assert_eq!(id, 42);
+                assert!(matches!(*source, crate::Error::SocketWrite));
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+
+        write_conn.enqueue(&1u32).unwrap();
+        let res = write_conn.flush().await;
+        assert!(matches!(res, Err(crate::Error::Write { id: 42, .. })));
+
+        // Serialization failures carry the ID as well.
+        #[derive(Debug)]
+        struct Unserializable;
+
+        impl Serialize for Unserializable {
+            fn serialize<S>(&self, _serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                Err(serde::ser::Error::custom("unserializable"))
+            }
+        }
+
+        let res = write_conn.send_error(&Unserializable).await;
+        match res {
+            Err(crate::Error::Write { id, source }) => {
+                assert_eq!(id, 42);
+                assert!(matches!(*source, crate::Error::Json(_)));
+            }
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
     #[tokio::test]
     async fn multiple_flushes() {
         // Test multiple flushes in a row.
@@ -443,4 +820,96 @@ mod tests {
         write_conn_pipelined.flush().await.unwrap();
         assert_eq!(write_conn_pipelined.socket.count(), 1);
     }
+
+    #[cfg(feature = "std")]
+    #[tokio::test]
+    async fn coalesce() {
+        use super::super::Call;
+        use crate::test_utils::mock_socket::CountingWriteHalf;
+        use std::time::Duration;
+
+        #[derive(Debug, Serialize)]
+        struct TestMethod {
+            id: u32,
+        }
+
+        let mut write_conn = WriteConnection::new(CountingWriteHalf::new(), 1);
+        write_conn.set_coalesce(Some(Duration::from_millis(50)));
+
+        write_conn
+            .send_call(&Call::new(TestMethod { id: 1 }))
+            .await
+            .unwrap();
+        write_conn
+            .send_call(&Call::new(TestMethod { id: 2 }))
+            .await
+            .unwrap();
+        assert_eq!(write_conn.socket.count(), 0);
+
+        // Once the duration has passed, all calls are written together.
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        write_conn
+            .send_call(&Call::new(TestMethod { id: 3 }))
+            .await
+            .unwrap();
+        assert_eq!(write_conn.socket.count(), 1);
+        assert_eq!(write_conn.pos, 0);
+
+        // Flushing sends the enqueued calls immediately.
+        write_conn
+            .send_call(&Call::new(TestMethod { id: 4 }))
+            .await
+            .unwrap();
+        assert_eq!(write_conn.socket.count(), 1);
+        write_conn.flush().await.unwrap();
+        assert_eq!(write_conn.socket.count(), 2);
+    }
+
+    #[cfg(all(debug_assertions, feature = "std", feature = "idl"))]
+    #[tokio::test]
+    async fn validate_against_interface() {
+        use crate::idl::{Interface, Method, Parameter, Type};
+
+        static NAME: Parameter<'static> = Parameter::new("name", &Type::String, &[]);
+        static GREETING: Parameter<'static> = Parameter::new("greeting", &Type::String, &[]);
+        static GREET: Method<'static> = Method::new("Greet", &[&NAME], &[&GREETING], &[]);
+        static INTERFACE: Interface<'static> =
+            Interface::new("org.example.greet", &[&GREET], &[], &[], &[]);
+
+        #[derive(Debug, Serialize)]
+        #[serde(tag = "method", content = "parameters")]
+        enum Methods<N> {
+            #[serde(rename = "org.example.greet.Greet")]
+            Greet { name: N },
+        }
+
+        #[derive(Debug, Serialize)]
+        struct Greeting<G> {
+            greeting: G,
+        }
+
+        let call = Call::new(Methods::Greet { name: "Jane" });
+        let wrong_call = Call::new(Methods::Greet { name: 42 });
+        let reply = Reply::new(Some(Greeting { greeting: "Hi" }));
+        let wrong_reply = Reply::new(Some(Greeting { greeting: false }));
+
+        let mut write_conn = WriteConnection::new(TestWriteHalf::new(0), 1);
+        // Nothing is validated without an interface.
+        assert!(write_conn.validate_call(&wrong_call).is_ok());
+
+        write_conn.set_interface(Some(&INTERFACE));
+        assert!(write_conn.validate_call(&call).is_ok());
+        assert_eq!(
+            write_conn.validate_call(&wrong_call),
+            Err("name: expected `string`".into())
+        );
+        assert!(write_conn.validate_reply(&reply).is_ok());
+        assert!(write_conn.validate_reply(&wrong_reply).is_err());
+
+        // The mismatching call is only warned about and still sent.
+        let expected = serde_json::to_string(&wrong_call).unwrap();
+        let mut write_conn = WriteConnection::new(TestWriteHalf::new(expected.len() + 1), 1);
+        write_conn.set_interface(Some(&INTERFACE));
+        write_conn.send_call(&wrong_call).await.unwrap();
+    }
 }
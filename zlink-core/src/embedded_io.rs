@@ -0,0 +1,167 @@
+//! [`Socket`](socket::Socket) implementation over [`embedded_io_async`] devices.
+
+use embedded_io_async::{Read, Write};
+
+use crate::{connection::socket, Error, Result};
+
+/// A [`Socket`](socket::Socket) implementation over a pair of [`embedded_io_async`] devices.
+///
+/// This allows using zlink without any particular async runtime (e.g with embassy or RTIC on
+/// embedded targets) over any transport that implements [`Read`] and [`Write`], e.g a UART or a
+/// TCP socket of an embedded network stack. Since these typically come as separate receive and
+/// transmit halves, the socket is created from a pair of devices, one for each direction.
+///
+/// Since the errors of the devices are opaque, they are reported as [`Error::SocketRead`] and
+/// [`Error::SocketWrite`].
+#[derive(Debug)]
+pub struct EmbeddedIoSocket<R, W> {
+    read: R,
+    write: W,
+}
+
+impl<R, W> EmbeddedIoSocket<R, W>
+where
+    R: Read + core::fmt::Debug,
+    W: Write + core::fmt::Debug,
+{
+    /// Create a new socket that reads from `read` and writes to `write`.
+    pub fn new(read: R, write: W) -> Self {
+        Self { read, write }
+    }
+}
+
+impl<R, W> socket::Socket for EmbeddedIoSocket<R, W>
+where
+    R: Read + core::fmt::Debug,
+    W: Write + core::fmt::Debug,
+{
+    type ReadHalf = EmbeddedIoReadHalf<R>;
+    type WriteHalf = EmbeddedIoWriteHalf<W>;
+
+    fn split(self) -> (Self::ReadHalf, Self::WriteHalf) {
+        (
+            EmbeddedIoReadHalf(self.read),
+            EmbeddedIoWriteHalf(self.write),
+        )
+    }
+}
+
+/// The [`ReadHalf`](socket::ReadHalf) implementation for [`EmbeddedIoSocket`].
+#[derive(Debug)]
+pub struct EmbeddedIoReadHalf<R>(R);
+
+impl<R> socket::ReadHalf for EmbeddedIoReadHalf<R>
+where
+    R: Read + core::fmt::Debug,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        self.0.read(buf).await.map_err(|_| Error::SocketRead)
+    }
+}
+
+/// The [`WriteHalf`](socket::WriteHalf) implementation for [`EmbeddedIoSocket`].
+#[derive(Debug)]
+pub struct EmbeddedIoWriteHalf<W>(W);
+
+impl<W> socket::WriteHalf for EmbeddedIoWriteHalf<W>
+where
+    W: Write + core::fmt::Debug,
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<()> {
+        self.0
+            .write_all(buf)
+            .await
+            .map_err(|_| Error::SocketWrite)?;
+        // Devices like UARTs may buffer the written data internally.
+        self.0.flush().await.map_err(|_| Error::SocketWrite)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::Infallible;
+
+    use super::*;
+    use crate::{Call, Connection, ReplyError};
+    use embedded_io_async::ErrorType;
+    use serde::{Deserialize, Serialize};
+
+    // A mock device, receiving preset data.
+    #[derive(Debug)]
+    struct Rx<'a>(&'a [u8]);
+
+    impl ErrorType for Rx<'_> {
+        type Error = Infallible;
+    }
+
+    impl Read for Rx<'_> {
+        async fn read(&mut self, buf: &mut [u8]) -> core::result::Result<usize, Infallible> {
+            let len = buf.len().min(self.0.len());
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+
+            Ok(len)
+        }
+    }
+
+    // A mock device, recording the transmitted data.
+    #[derive(Debug)]
+    struct Tx {
+        data: [u8; 128],
+        len: usize,
+    }
+
+    impl ErrorType for Tx {
+        type Error = Infallible;
+    }
+
+    impl Write for Tx {
+        async fn write(&mut self, buf: &[u8]) -> core::result::Result<usize, Infallible> {
+            let len = buf.len().min(self.data.len() - self.len);
+            self.data[self.len..self.len + len].copy_from_slice(&buf[..len]);
+            self.len += len;
+
+            Ok(len)
+        }
+    }
+
+    #[derive(Debug, Serialize)]
+    #[serde(tag = "method", content = "parameters")]
+    enum Methods {
+        #[serde(rename = "org.example.echo.Echo")]
+        Echo { value: u32 },
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct EchoReply {
+        value: u32,
+    }
+
+    #[derive(Debug, ReplyError)]
+    #[zlink(interface = "org.example.echo")]
+    enum EchoError {}
+
+    #[tokio::test]
+    async fn call_over_device() {
+        let rx = Rx(b"{\"parameters\":{\"value\":42}}\0");
+        let tx = Tx {
+            data: [0; 128],
+            len: 0,
+        };
+        let mut conn = Connection::new(EmbeddedIoSocket::new(rx, tx));
+
+        let call = Call::new(Methods::Echo { value: 42 });
+        let reply = conn
+            .call_method::<_, EchoReply, EchoError>(&call)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply.parameters().unwrap().value, 42);
+
+        let tx = &conn.write().write_half().0;
+        assert_eq!(
+            &tx.data[..tx.len],
+            b"{\"method\":\"org.example.echo.Echo\",\"parameters\":{\"value\":42}}\0"
+        );
+    }
+}
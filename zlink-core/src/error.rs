@@ -14,6 +14,8 @@ pub enum Error {
     InvalidUtf8(Utf8Error),
     /// Error serializing or deserializing to/from JSON.
     #[cfg(feature = "std")]
+    ///
+    /// Use [`Error::line`] and [`Error::column`] to get the location of the error.
     Json(serde_json::Error),
     /// Error serialization to JSON.
     #[cfg(not(feature = "std"))]
@@ -24,11 +26,33 @@ pub enum Error {
     /// An I/O error.
     #[cfg(feature = "std")]
     Io(std::io::Error),
+    /// An error occurred while writing to a connection.
+    #[cfg(feature = "std")]
+    Write {
+        /// The ID of the connection.
+        id: usize,
+        /// The underlying error.
+        source: Box<Error>,
+    },
     /// An error occurred while parsing IDL.
     #[cfg(feature = "idl-parse")]
     IdlParse(String),
     /// Missing required parameters.
     MissingParameters,
+    /// A raw message contains a NUL byte, which is reserved for terminating messages.
+    InteriorNul,
+    /// The operation timed out.
+    Timeout,
+    /// The service didn't acknowledge the upgrade of the connection.
+    UpgradeRejected,
+    /// A chain of calls already contains the maximum number of calls.
+    ///
+    /// Only returned without the `std` feature, where chains have a fixed capacity.
+    ChainTooLong,
+    /// Too many replies to other method calls arrived while waiting for a correlated reply.
+    ///
+    /// Only returned with the `std` feature, where correlated replies are supported.
+    TooManyPendingReplies,
     /// A general service error.
     VarlinkService(crate::varlink_service::Error),
 }
@@ -36,6 +60,53 @@ pub enum Error {
 /// The Result type for the zlink crate.
 pub type Result<T> = core::result::Result<T, Error>;
 
+impl Error {
+    /// Wrap `source` as an error writing to the connection with the given `id`.
+    ///
+    /// Without the `std` feature, `source` is returned as is.
+    pub(crate) fn write(id: usize, source: Error) -> Self {
+        #[cfg(feature = "std")]
+        {
+            Error::Write {
+                id,
+                source: Box::new(source),
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            let _ = id;
+
+            source
+        }
+    }
+
+    /// The line (starting from 1) where a JSON error occurred.
+    ///
+    /// Returns `None` if this is not a JSON error or the error has no location. Looks through
+    /// [`Error::Write`] to the underlying error.
+    #[cfg(feature = "std")]
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Error::Json(e) if e.line() != 0 => Some(e.line()),
+            Error::Write { source, .. } => source.line(),
+            _ => None,
+        }
+    }
+
+    /// The column (starting from 1) where a JSON error occurred.
+    ///
+    /// Returns `None` if this is not a JSON error or the error has no location. Looks through
+    /// [`Error::Write`] to the underlying error.
+    #[cfg(feature = "std")]
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            Error::Json(e) if e.line() != 0 => Some(e.column()),
+            Error::Write { source, .. } => source.column(),
+            _ => None,
+        }
+    }
+}
+
 impl core::error::Error for Error {
     fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
         match self {
@@ -47,6 +118,8 @@ impl core::error::Error for Error {
             Error::JsonDeserialize(e) => Some(e),
             #[cfg(feature = "std")]
             Error::Io(e) => Some(e),
+            #[cfg(feature = "std")]
+            Error::Write { source, .. } => Some(source.as_ref()),
             Error::InvalidUtf8(e) => Some(e),
             #[cfg(feature = "idl-parse")]
             Error::IdlParse(_) => None,
@@ -108,9 +181,18 @@ impl core::fmt::Display for Error {
             Error::JsonDeserialize(e) => write!(f, "Error deserializing from JSON: {e}"),
             #[cfg(feature = "std")]
             Error::Io(e) => write!(f, "I/O error: {e}"),
+            #[cfg(feature = "std")]
+            Error::Write { id, source } => {
+                write!(f, "Error writing to connection {id}: {source}")
+            }
             #[cfg(feature = "idl-parse")]
             Error::IdlParse(e) => write!(f, "IDL parse error: {e}"),
             Error::MissingParameters => write!(f, "Missing required parameters"),
+            Error::InteriorNul => write!(f, "Message contains a NUL byte"),
+            Error::Timeout => write!(f, "Operation timed out"),
+            Error::UpgradeRejected => write!(f, "The service rejected the connection upgrade"),
+            Error::ChainTooLong => write!(f, "Too many calls in the chain"),
+            Error::TooManyPendingReplies => write!(f, "Too many pending replies"),
             Error::VarlinkService(e) => write!(f, "{e}"),
         }
     }
@@ -138,10 +220,46 @@ impl defmt::Format for Error {
             Error::JsonDeserialize(_) => defmt::write!(fmt, "Error deserializing from JSON"),
             #[cfg(feature = "std")]
             Error::Io(_) => defmt::write!(fmt, "I/O error"),
+            #[cfg(feature = "std")]
+            Error::Write { id, .. } => defmt::write!(fmt, "Error writing to connection {}", id),
             #[cfg(feature = "idl-parse")]
             Error::IdlParse(_) => defmt::write!(fmt, "IDL parse error"),
             Error::MissingParameters => defmt::write!(fmt, "Missing required parameters"),
+            Error::InteriorNul => defmt::write!(fmt, "Message contains a NUL byte"),
+            Error::Timeout => defmt::write!(fmt, "Operation timed out"),
+            Error::UpgradeRejected => {
+                defmt::write!(fmt, "The service rejected the connection upgrade")
+            }
+            Error::ChainTooLong => defmt::write!(fmt, "Too many calls in the chain"),
+            Error::TooManyPendingReplies => defmt::write!(fmt, "Too many pending replies"),
             Error::VarlinkService(_) => defmt::write!(fmt, "Varlink service error"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn json_error_location() {
+        let json_err =
+            serde_json::from_str::<serde_json::Value>("{\n  \"a\": 1,\n  oops\n}").unwrap_err();
+        let err = Error::from(json_err);
+        assert!(matches!(err, Error::Json(_)));
+        assert_eq!(err.line(), Some(3));
+        assert_eq!(err.column(), Some(3));
+        assert!(err.to_string().contains("line 3 column 3"));
+
+        // The underlying `serde_json::Error` is the source.
+        let source = core::error::Error::source(&err).unwrap();
+        assert!(source.downcast_ref::<serde_json::Error>().is_some());
+
+        // The location is also available through a write error.
+        let err = Error::write(1, err);
+        assert_eq!((err.line(), err.column()), (Some(3), Some(3)));
+
+        assert_eq!(Error::BufferOverflow.line(), None);
+    }
+}
@@ -0,0 +1,378 @@
+//! Random JSON value generation for Varlink IDL types.
+
+use rand::{distr::Alphanumeric, Rng};
+use serde_json::{Map, Value};
+
+use super::{default_json, CustomType, EnumVariant, Field, Interface, List, Type, TypeVisitor};
+
+/// The maximum number of elements in the generated arrays and maps.
+const MAX_ELEMENTS: usize = 4;
+
+/// The maximum length of the generated strings.
+const MAX_STRING_LEN: usize = 16;
+
+/// Returns a random JSON value conforming to the given type.
+///
+/// This is useful for property testing, e.g to check that a service handles all the inputs that
+/// conform to its interface. The generated values are:
+///
+/// * `bool`: `true` or `false`
+/// * `int`: any 64-bit signed integer
+/// * `float`: any finite number between `-1e6` and `1e6`
+/// * `string`: up to 16 alphanumeric characters
+/// * optional types: either `null` or a value of the inner type
+/// * arrays and maps: up to 4 values of the element type, the map keys being random strings
+/// * foreign objects: `{}`
+/// * enums: the name of one of the variants
+/// * objects: an object with a value for each field
+///
+/// The deeper optional types, arrays and maps are nested, the more likely they're `null` or empty,
+/// so that the values of recursive types stay small.
+///
+/// Custom type references are resolved through `interface`. References to types not defined in
+/// `interface` get an empty object. Types nested deeper than [`Type::MAX_DEPTH`], including
+/// through custom type references, get their [`default_json`] value.
+pub fn arbitrary_json<R>(ty: &Type<'_>, interface: &Interface<'_>, rng: &mut R) -> Value
+where
+    R: Rng,
+{
+    arbitrary_json_at(ty, interface, rng, 0)
+}
+
+fn arbitrary_json_at<R>(
+    ty: &Type<'_>,
+    interface: &Interface<'_>,
+    rng: &mut R,
+    depth: usize,
+) -> Value
+where
+    R: Rng,
+{
+    if depth >= Type::MAX_DEPTH {
+        return default_json(ty);
+    }
+
+    let mut generator = Generator {
+        interface,
+        rng,
+        depth,
+        stack: Vec::new(),
+        value: None,
+    };
+    if !ty.accept(&mut generator) {
+        return default_json(ty);
+    }
+
+    generator.value.unwrap_or_else(|| default_json(ty))
+}
+
+/// Generates a value while [`Type::accept`] walks its type.
+///
+/// Since the types are visited parents first, the containers are kept on a stack until the values
+/// of their children have been generated. The walk visits the element type of arrays and maps
+/// only once, so their other elements are generated separately.
+struct Generator<'i, 'd, 'r, R> {
+    interface: &'i Interface<'d>,
+    rng: &'r mut R,
+    // The depth of the walked type in the top-level type.
+    depth: usize,
+    stack: Vec<Partial>,
+    value: Option<Value>,
+}
+
+/// A container waiting for the value of its next child.
+enum Partial {
+    /// The child value is used if `some` is set, `null` otherwise.
+    Optional { some: bool },
+    /// The child value is added to `elements`, unless the array is to be empty.
+    Array { elements: Vec<Value>, empty: bool },
+    /// The child value is added under `key`, unless the map is to be empty.
+    Map {
+        entries: Map<String, Value>,
+        key: Option<String>,
+    },
+    /// The child value is the value of the `field` member, `remaining` fields being left.
+    Object {
+        members: Map<String, Value>,
+        field: String,
+        remaining: usize,
+    },
+}
+
+impl<R> Generator<'_, '_, '_, R>
+where
+    R: Rng,
+{
+    /// Generate a value of `ty` independently of the walk, e.g for a child of a container.
+    fn generate(&mut self, ty: &Type<'_>) -> Value {
+        let depth = self.current_depth() + 1;
+
+        arbitrary_json_at(ty, self.interface, self.rng, depth)
+    }
+
+    // The depth of the currently visited type in the top-level type.
+    fn current_depth(&self) -> usize {
+        self.depth + self.stack.len()
+    }
+
+    // If the currently visited type is that of a value to be discarded, e.g in a `null` optional.
+    fn discarding(&self) -> bool {
+        self.stack.iter().any(|partial| {
+            matches!(
+                partial,
+                Partial::Optional { some: false }
+                    | Partial::Array { empty: true, .. }
+                    | Partial::Map { key: None, .. }
+            )
+        })
+    }
+
+    fn arbitrary_len(&mut self) -> usize {
+        let max = MAX_ELEMENTS / (self.current_depth() + 1);
+
+        self.rng.random_range(0..=max)
+    }
+
+    fn arbitrary_variant<'v, 'a: 'v>(
+        &mut self,
+        mut variants: impl ExactSizeIterator<Item = &'v EnumVariant<'a>>,
+    ) -> Value {
+        let name = match variants.len() {
+            0 => "",
+            len => variants.nth(self.rng.random_range(0..len)).unwrap().name(),
+        };
+
+        Value::String(name.into())
+    }
+
+    /// Add a generated value to the pending container, completing it if that was its last child.
+    fn complete(&mut self, mut value: Value) {
+        while let Some(partial) = self.stack.pop() {
+            value = match partial {
+                Partial::Optional { some } => {
+                    if some {
+                        value
+                    } else {
+                        Value::Null
+                    }
+                }
+                Partial::Array {
+                    mut elements,
+                    empty,
+                } => {
+                    if !empty {
+                        elements.push(value);
+                    }
+                    Value::Array(elements)
+                }
+                Partial::Map { mut entries, key } => {
+                    if let Some(key) = key {
+                        entries.insert(key, value);
+                    }
+                    Value::Object(entries)
+                }
+                Partial::Object {
+                    mut members,
+                    field,
+                    remaining,
+                } => {
+                    members.insert(field, value);
+                    if remaining > 0 {
+                        // The name of the next field is set when it's visited.
+                        self.stack.push(Partial::Object {
+                            members,
+                            field: String::new(),
+                            remaining,
+                        });
+
+                        return;
+                    }
+                    Value::Object(members)
+                }
+            };
+        }
+
+        self.value = Some(value);
+    }
+}
+
+impl<'a, R> TypeVisitor<'a> for Generator<'_, '_, '_, R>
+where
+    R: Rng,
+{
+    fn visit_primitive(&mut self, ty: &Type<'a>) {
+        let value = match ty {
+            Type::Bool => Value::Bool(self.rng.random()),
+            Type::Int => Value::from(self.rng.random::<i64>()),
+            Type::Float => Value::from(self.rng.random_range(-1e6..=1e6)),
+            Type::String => Value::String(arbitrary_string(self.rng)),
+            _ => Value::Object(Map::new()),
+        };
+        self.complete(value);
+    }
+
+    fn visit_optional(&mut self, _inner: &Type<'a>) {
+        let some = self.rng.random_ratio(1, self.current_depth() as u32 + 2);
+        self.stack.push(Partial::Optional { some });
+    }
+
+    fn visit_array(&mut self, element: &Type<'a>) {
+        let len = self.arbitrary_len();
+        // The last element is the one generated by the walk.
+        let elements = (1..len).map(|_| self.generate(element)).collect();
+        self.stack.push(Partial::Array {
+            elements,
+            empty: len == 0,
+        });
+    }
+
+    fn visit_map(&mut self, value: &Type<'a>) {
+        let len = self.arbitrary_len();
+        // The last entry is the one generated by the walk.
+        let entries = (1..len)
+            .map(|_| (arbitrary_string(self.rng), self.generate(value)))
+            .collect();
+        let key = (len > 0).then(|| arbitrary_string(self.rng));
+        self.stack.push(Partial::Map { entries, key });
+    }
+
+    fn visit_custom(&mut self, name: &'a str) {
+        if self.discarding() {
+            // Don't resolve the type, which could be recursive, for nothing.
+            self.complete(Value::Null);
+
+            return;
+        }
+
+        let interface = self.interface;
+        let value = match interface.custom_types().find(|t| t.name() == name) {
+            Some(CustomType::Object(object)) => Value::Object(
+                object
+                    .fields()
+                    .map(|field| (field.name().into(), self.generate(field.ty())))
+                    .collect(),
+            ),
+            Some(CustomType::Enum(custom_enum)) => {
+                let variants = custom_enum.variants().collect::<Vec<_>>();
+                self.arbitrary_variant(variants.into_iter())
+            }
+            None => Value::Object(Map::new()),
+        };
+        self.complete(value);
+    }
+
+    fn visit_enum(&mut self, variants: &List<'a, EnumVariant<'a>>) {
+        let variants = variants.iter().collect::<Vec<_>>();
+        let value = self.arbitrary_variant(variants.into_iter());
+        self.complete(value);
+    }
+
+    fn visit_object(&mut self, fields: &List<'a, Field<'a>>) {
+        let remaining = fields.iter().count();
+        if remaining == 0 {
+            self.complete(Value::Object(Map::new()));
+
+            return;
+        }
+
+        self.stack.push(Partial::Object {
+            members: Map::new(),
+            field: String::new(),
+            remaining,
+        });
+    }
+
+    fn visit_field(&mut self, field: &Field<'a>) {
+        if let Some(Partial::Object {
+            field: name,
+            remaining,
+            ..
+        }) = self.stack.last_mut()
+        {
+            *name = field.name().into();
+            *remaining -= 1;
+        }
+    }
+}
+
+fn arbitrary_string<R>(rng: &mut R) -> String
+where
+    R: Rng,
+{
+    let len = rng.random_range(0..=MAX_STRING_LEN);
+
+    (0..len)
+        .map(|_| char::from(rng.sample(Alphanumeric)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl::{validate_value, CustomEnum, CustomObject, TypeRef};
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    #[test]
+    fn conforming_values() {
+        let string = Type::String;
+        let int = Type::Int;
+        let optional_string = Type::Optional(TypeRef::new(&string));
+        let tags = Type::Array(TypeRef::new(&string));
+        let scores = Type::Map(TypeRef::new(&int));
+        let variants = [
+            &EnumVariant::new("happy", &[]),
+            &EnumVariant::new("sad", &[]),
+        ];
+        let mood = Type::Enum(variants.as_slice().into());
+        let settings_fields = [
+            &Field::new("enabled", &Type::Bool, &[]),
+            &Field::new("ratio", &Type::Float, &[]),
+        ];
+        let settings = Type::Object(settings_fields.as_slice().into());
+        let color = Type::Custom("Color");
+        let contact = Type::Custom("Contact");
+        let contacts = Type::Array(TypeRef::new(&contact));
+        let fields = [
+            &Field::new("name", &Type::String, &[]),
+            &Field::new("nickname", &optional_string, &[]),
+            &Field::new("tags", &tags, &[]),
+            &Field::new("scores", &scores, &[]),
+            &Field::new("mood", &mood, &[]),
+            &Field::new("settings", &settings, &[]),
+            &Field::new("color", &color, &[]),
+            &Field::new("contacts", &contacts, &[]),
+        ];
+        let profile = Type::Object(fields.as_slice().into());
+
+        // A recursive type.
+        let optional_contact = Type::Optional(TypeRef::new(&contact));
+        let contact_fields = [
+            &Field::new("name", &Type::String, &[]),
+            &Field::new("contacts", &contacts, &[]),
+            &Field::new("referrer", &optional_contact, &[]),
+        ];
+        let contact_type = CustomType::from(CustomObject::new("Contact", &contact_fields, &[]));
+        let color_variants = [
+            &EnumVariant::new("red", &[]),
+            &EnumVariant::new("green", &[]),
+        ];
+        let color_type = CustomType::from(CustomEnum::new("Color", &color_variants, &[]));
+        let custom_types = [&contact_type, &color_type];
+        let interface = Interface::new("org.example.test", &[], &custom_types, &[], &[]);
+
+        let mut rng = SmallRng::seed_from_u64(42);
+        for _ in 0..100 {
+            let value = arbitrary_json(&profile, &interface, &mut rng);
+            if let Err(e) = validate_value(&profile, &value, &interface) {
+                panic!("Generated non-conforming value {value}: {e}");
+            }
+        }
+
+        // References to unknown types can't be resolved.
+        let unknown = Type::Custom("Unknown");
+        assert_eq!(
+            arbitrary_json(&unknown, &interface, &mut rng),
+            Value::Object(Map::new())
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Builders for constructing interfaces programmatically.
+
+use super::{Comment, CustomType, Error, Field, Interface, Method, Parameter, Type};
+
+/// A builder for [`Interface`].
+///
+/// This is more convenient than assembling the members of an interface by hand and then passing
+/// them to [`Interface::new_owned`], e.g when generating an interface from another schema:
+///
+/// ```
+/// use zlink_core::idl::{InterfaceBuilder, Type};
+///
+/// let interface = InterfaceBuilder::new("org.example.ping")
+///     .method("Ping")
+///     .input("ping", Type::String)
+///     .output("pong", Type::String)
+///     .build()
+///     .build();
+/// assert_eq!(
+///     interface.to_string(),
+///     "interface org.example.ping\n\nmethod Ping(ping: string) -> (pong: string)",
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct InterfaceBuilder<'a> {
+    name: &'a str,
+    methods: Vec<Method<'a>>,
+    custom_types: Vec<CustomType<'a>>,
+    errors: Vec<Error<'a>>,
+    comments: Vec<Comment<'a>>,
+}
+
+impl<'a> InterfaceBuilder<'a> {
+    /// Create a builder for an interface with the given name.
+    pub fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            methods: Vec::new(),
+            custom_types: Vec::new(),
+            errors: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    /// Add a comment line to the interface.
+    pub fn comment(mut self, content: &'a str) -> Self {
+        self.comments.push(Comment::new(content));
+        self
+    }
+
+    /// Start adding a method with the given name.
+    ///
+    /// Use [`MethodBuilder::build`] to add the method and get back this builder.
+    pub fn method(self, name: &'a str) -> MethodBuilder<'a> {
+        MethodBuilder {
+            interface: self,
+            name,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            comments: Vec::new(),
+        }
+    }
+
+    /// Add an error with the given name and fields.
+    pub fn error(mut self, name: &'a str, fields: Vec<Field<'a>>) -> Self {
+        self.errors.push(Error::new_owned(name, fields, Vec::new()));
+        self
+    }
+
+    /// Add a custom type.
+    pub fn custom_type(mut self, custom_type: CustomType<'a>) -> Self {
+        self.custom_types.push(custom_type);
+        self
+    }
+
+    /// Build the interface.
+    ///
+    /// The members are kept in the order they were added. Their names are not validated.
+    pub fn build(self) -> Interface<'a> {
+        Interface::new_owned(
+            self.name,
+            self.methods,
+            self.custom_types,
+            self.errors,
+            self.comments,
+        )
+    }
+}
+
+/// A builder for a method of an interface.
+///
+/// See [`InterfaceBuilder::method`].
+#[derive(Debug, Clone)]
+pub struct MethodBuilder<'a> {
+    interface: InterfaceBuilder<'a>,
+    name: &'a str,
+    inputs: Vec<Parameter<'a>>,
+    outputs: Vec<Parameter<'a>>,
+    comments: Vec<Comment<'a>>,
+}
+
+impl<'a> MethodBuilder<'a> {
+    /// Add an input parameter.
+    pub fn input(mut self, name: &'a str, ty: Type<'a>) -> Self {
+        self.inputs.push(Parameter::new_owned(name, ty, Vec::new()));
+        self
+    }
+
+    /// Add an output parameter.
+    pub fn output(mut self, name: &'a str, ty: Type<'a>) -> Self {
+        self.outputs
+            .push(Parameter::new_owned(name, ty, Vec::new()));
+        self
+    }
+
+    /// Add a comment line to the method.
+    pub fn comment(mut self, content: &'a str) -> Self {
+        self.comments.push(Comment::new(content));
+        self
+    }
+
+    /// Add the method to the interface and return the interface builder.
+    pub fn build(self) -> InterfaceBuilder<'a> {
+        let mut interface = self.interface;
+        interface.methods.push(Method::new_owned(
+            self.name,
+            self.inputs,
+            self.outputs,
+            self.comments,
+        ));
+
+        interface
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl::{CustomObject, TypeRef};
+
+    #[test]
+    fn two_methods() {
+        let person = CustomObject::new_owned(
+            "Person",
+            vec![
+                Field::new_owned("name", Type::String, Vec::new()),
+                Field::new_owned("age", Type::Int, Vec::new()),
+            ],
+            Vec::new(),
+        );
+        let interface = InterfaceBuilder::new("org.example.people")
+            .comment("People registry.")
+            .custom_type(CustomType::Object(person))
+            .method("Get")
+            .comment("Get a person by name.")
+            .input("name", Type::String)
+            .output("person", Type::Custom("Person"))
+            .build()
+            .method("List")
+            .output(
+                "people",
+                Type::Array(TypeRef::new_owned(Type::Custom("Person"))),
+            )
+            .build()
+            .error(
+                "NotFound",
+                vec![Field::new_owned("name", Type::String, Vec::new())],
+            )
+            .build();
+
+        assert_eq!(interface.method_count(), 2);
+        assert_eq!(
+            interface.to_string(),
+            "# People registry.\n\
+             interface org.example.people\n\n\
+             type Person (name: string, age: int)\n\n\
+             # Get a person by name.\n\
+             method Get(name: string) -> (person: Person)\n\n\
+             method List() -> (people: []Person)\n\n\
+             error NotFound (name: string)"
+        );
+    }
+}
@@ -24,6 +24,21 @@ impl<'a> Comment<'a> {
     pub fn text(&self) -> &'a str {
         self.content
     }
+
+    /// Returns the deprecation note if this is a deprecation marker comment.
+    ///
+    /// A deprecation marker is a comment starting with `@deprecated`, optionally followed by a
+    /// colon and a note, e.g `# @deprecated: use Bar instead`. The note is empty if none is given.
+    pub fn deprecation_note(&self) -> Option<&'a str> {
+        let note = self.content.trim_start().strip_prefix("@deprecated")?;
+        if note.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+            // Not a marker but a word that happens to start with it, e.g `@deprecatedness`.
+            return None;
+        }
+        let note = note.trim_start();
+
+        Some(note.strip_prefix(':').unwrap_or(note).trim())
+    }
 }
 
 impl<'a> fmt::Display for Comment<'a> {
@@ -32,6 +47,21 @@ impl<'a> fmt::Display for Comment<'a> {
     }
 }
 
+/// Joins the text of the given comments into a single documentation string.
+///
+/// The lines are separated by newlines and any leading and trailing whitespace is trimmed. This is
+/// useful for turning the comments of an interface member into a doc string.
+#[cfg(feature = "std")]
+pub fn comments_to_doc<'c, 'a: 'c>(comments: impl IntoIterator<Item = &'c Comment<'a>>) -> String {
+    let doc = comments
+        .into_iter()
+        .map(Comment::text)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    doc.trim().to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,6 +80,18 @@ mod tests {
         assert_eq!(comment.text(), "This is not a proper comment");
     }
 
+    #[test]
+    fn deprecation_note() {
+        let comment = Comment::new("@deprecated: use Bar instead");
+        assert_eq!(comment.deprecation_note(), Some("use Bar instead"));
+        let comment = Comment::new("@deprecated");
+        assert_eq!(comment.deprecation_note(), Some(""));
+        let comment = Comment::new("Not @deprecated");
+        assert_eq!(comment.deprecation_note(), None);
+        let comment = Comment::new("@deprecatedness");
+        assert_eq!(comment.deprecation_note(), None);
+    }
+
     #[test]
     fn comment_display() {
         let comment = Comment::new("A enum field allowing to gracefully get metadata");
@@ -61,4 +103,19 @@ mod tests {
             "# A enum field allowing to gracefully get metadata"
         );
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn join_comments() {
+        let comments = [
+            Comment::new("Returns information about the service."),
+            Comment::new(""),
+            Comment::new("The result includes the vendor and the version. "),
+        ];
+        assert_eq!(
+            comments_to_doc(&comments),
+            "Returns information about the service.\n\nThe result includes the vendor and the version."
+        );
+        assert_eq!(comments_to_doc(&[]), "");
+    }
 }
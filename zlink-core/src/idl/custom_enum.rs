@@ -13,6 +13,10 @@ pub struct CustomEnum<'a> {
     variants: List<'a, EnumVariant<'a>>,
     /// The comments associated with this enum type.
     comments: List<'a, super::Comment<'a>>,
+    /// Whether this enum type was preceded by a blank line in the parsed IDL.
+    preceded_by_blank: bool,
+    /// The byte range of this enum type in the parsed IDL.
+    span: Option<(usize, usize)>,
 }
 
 impl<'a> CustomEnum<'a> {
@@ -26,6 +30,8 @@ impl<'a> CustomEnum<'a> {
             name,
             variants: List::Borrowed(variants),
             comments: List::Borrowed(comments),
+            preceded_by_blank: false,
+            span: None,
         }
     }
 
@@ -40,6 +46,8 @@ impl<'a> CustomEnum<'a> {
             name,
             variants: List::from(variants),
             comments: List::from(comments),
+            preceded_by_blank: false,
+            span: None,
         }
     }
 
@@ -57,6 +65,33 @@ impl<'a> CustomEnum<'a> {
     pub fn comments(&self) -> impl Iterator<Item = &super::Comment<'a>> {
         self.comments.iter()
     }
+
+    /// Returns true if this enum type was preceded by a blank line in the parsed IDL.
+    ///
+    /// This is used by [`super::Interface::format`] to preserve the grouping of members.
+    pub fn preceded_by_blank(&self) -> bool {
+        self.preceded_by_blank
+    }
+
+    /// Set whether this enum type is preceded by a blank line.
+    pub fn set_preceded_by_blank(mut self, preceded_by_blank: bool) -> Self {
+        self.preceded_by_blank = preceded_by_blank;
+        self
+    }
+
+    /// Returns the `(start, end)` byte offsets of this enum type in the IDL it was parsed from.
+    ///
+    /// The range includes the preceding comments. Returns `None` if the enum type wasn't parsed.
+    pub fn span(&self) -> Option<(usize, usize)> {
+        self.span
+    }
+
+    /// Set the byte offsets of this enum type in the parsed IDL.
+    #[cfg(feature = "idl-parse")]
+    pub(crate) fn set_span(mut self, span: (usize, usize)) -> Self {
+        self.span = Some(span);
+        self
+    }
 }
 
 impl<'a> fmt::Display for CustomEnum<'a> {
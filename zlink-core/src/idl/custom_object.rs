@@ -13,6 +13,10 @@ pub struct CustomObject<'a> {
     fields: List<'a, Field<'a>>,
     /// The comments associated with this object type.
     comments: List<'a, super::Comment<'a>>,
+    /// Whether this object type was preceded by a blank line in the parsed IDL.
+    preceded_by_blank: bool,
+    /// The byte range of this object type in the parsed IDL.
+    span: Option<(usize, usize)>,
 }
 
 impl<'a> CustomObject<'a> {
@@ -26,6 +30,8 @@ impl<'a> CustomObject<'a> {
             name,
             fields: List::Borrowed(fields),
             comments: List::Borrowed(comments),
+            preceded_by_blank: false,
+            span: None,
         }
     }
 
@@ -40,6 +46,8 @@ impl<'a> CustomObject<'a> {
             name,
             fields: List::from(fields),
             comments: List::from(comments),
+            preceded_by_blank: false,
+            span: None,
         }
     }
 
@@ -57,6 +65,33 @@ impl<'a> CustomObject<'a> {
     pub fn comments(&self) -> impl Iterator<Item = &super::Comment<'a>> {
         self.comments.iter()
     }
+
+    /// Returns true if this object type was preceded by a blank line in the parsed IDL.
+    ///
+    /// This is used by [`super::Interface::format`] to preserve the grouping of members.
+    pub fn preceded_by_blank(&self) -> bool {
+        self.preceded_by_blank
+    }
+
+    /// Set whether this object type is preceded by a blank line.
+    pub fn set_preceded_by_blank(mut self, preceded_by_blank: bool) -> Self {
+        self.preceded_by_blank = preceded_by_blank;
+        self
+    }
+
+    /// Returns the `(start, end)` byte offsets of this object type in the IDL it was parsed from.
+    ///
+    /// The range includes the preceding comments. Returns `None` if the object type wasn't parsed.
+    pub fn span(&self) -> Option<(usize, usize)> {
+        self.span
+    }
+
+    /// Set the byte offsets of this object type in the parsed IDL.
+    #[cfg(feature = "idl-parse")]
+    pub(crate) fn set_span(mut self, span: (usize, usize)) -> Self {
+        self.span = Some(span);
+        self
+    }
 }
 
 impl<'a> fmt::Display for CustomObject<'a> {
@@ -65,16 +100,8 @@ impl<'a> fmt::Display for CustomObject<'a> {
         for comment in self.comments.iter() {
             writeln!(f, "{comment}")?;
         }
-        write!(f, "type {} (", self.name)?;
-        let mut first = true;
-        for field in self.fields.iter() {
-            if !first {
-                write!(f, ", ")?;
-            }
-            first = false;
-            write!(f, "{field}")?;
-        }
-        write!(f, ")")
+        write!(f, "type {} ", self.name)?;
+        super::field::write_fields(f, &self.fields)
     }
 }
 
@@ -50,6 +50,39 @@ impl<'a> CustomType<'a> {
             CustomType::Enum(enm) => Some(enm),
         }
     }
+
+    /// Returns true if this custom type was preceded by a blank line in the parsed IDL.
+    pub fn preceded_by_blank(&self) -> bool {
+        match self {
+            CustomType::Object(obj) => obj.preceded_by_blank(),
+            CustomType::Enum(enm) => enm.preceded_by_blank(),
+        }
+    }
+
+    /// Set whether this custom type is preceded by a blank line.
+    pub fn set_preceded_by_blank(self, preceded_by_blank: bool) -> Self {
+        match self {
+            CustomType::Object(obj) => obj.set_preceded_by_blank(preceded_by_blank).into(),
+            CustomType::Enum(enm) => enm.set_preceded_by_blank(preceded_by_blank).into(),
+        }
+    }
+
+    /// Returns the `(start, end)` byte offsets of this custom type in the IDL it was parsed from.
+    pub fn span(&self) -> Option<(usize, usize)> {
+        match self {
+            CustomType::Object(obj) => obj.span(),
+            CustomType::Enum(enm) => enm.span(),
+        }
+    }
+
+    /// Set the byte offsets of this custom type in the parsed IDL.
+    #[cfg(feature = "idl-parse")]
+    pub(crate) fn set_span(self, span: (usize, usize)) -> Self {
+        match self {
+            CustomType::Object(obj) => obj.set_span(span).into(),
+            CustomType::Enum(enm) => enm.set_span(span).into(),
+        }
+    }
 }
 
 impl<'a> From<CustomObject<'a>> for CustomType<'a> {
@@ -0,0 +1,109 @@
+//! Default JSON value generation for Varlink IDL types.
+
+use serde_json::{Map, Value};
+
+use super::Type;
+
+/// Returns a default JSON value for the given type.
+///
+/// This is useful for generating example payloads or test fixtures. The default values are:
+///
+/// * `bool`: `false`
+/// * `int`: `0`
+/// * `float`: `0.0`
+/// * `string`: `""`
+/// * optional types: `null`
+/// * arrays: `[]`
+/// * maps and foreign objects: `{}`
+/// * inline enums: the name of the first variant
+/// * inline objects: an object with the default value of each field
+///
+/// Since custom type references can't be resolved without the interface, an empty object is
+/// returned for them.
+pub fn default_json(ty: &Type<'_>) -> Value {
+    match ty {
+        Type::Bool => Value::Bool(false),
+        Type::Int => Value::from(0),
+        Type::Float => Value::from(0.0),
+        Type::String => Value::String(String::new()),
+        Type::Optional(_) => Value::Null,
+        Type::Array(_) => Value::Array(Vec::new()),
+        Type::Map(_) | Type::ForeignObject | Type::Custom(_) => Value::Object(Map::new()),
+        Type::Enum(variants) => variants
+            .iter()
+            .next()
+            .map(|variant| Value::String(variant.name().into()))
+            .unwrap_or_else(|| Value::String(String::new())),
+        Type::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|field| (field.name().into(), default_json(field.ty())))
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl::{EnumVariant, Field, TypeRef};
+    use serde_json::json;
+
+    #[test]
+    fn primitives() {
+        assert_eq!(default_json(&Type::Bool), json!(false));
+        assert_eq!(default_json(&Type::Int), json!(0));
+        assert_eq!(default_json(&Type::Float), json!(0.0));
+        assert_eq!(default_json(&Type::String), json!(""));
+        assert_eq!(default_json(&Type::ForeignObject), json!({}));
+        assert_eq!(default_json(&Type::Custom("Person")), json!({}));
+    }
+
+    #[test]
+    fn containers() {
+        let int = Type::Int;
+        assert_eq!(
+            default_json(&Type::Optional(TypeRef::new(&int))),
+            json!(null)
+        );
+        assert_eq!(default_json(&Type::Array(TypeRef::new(&int))), json!([]));
+        assert_eq!(default_json(&Type::Map(TypeRef::new(&int))), json!({}));
+
+        let variants = [
+            &EnumVariant::new("red", &[]),
+            &EnumVariant::new("green", &[]),
+        ];
+        assert_eq!(
+            default_json(&Type::Enum(variants.as_slice().into())),
+            json!("red")
+        );
+    }
+
+    #[test]
+    fn nested_object() {
+        let string = Type::String;
+        let optional_string = Type::Optional(TypeRef::new(&string));
+        let inner_fields = [
+            &Field::new("enabled", &Type::Bool, &[]),
+            &Field::new("nickname", &optional_string, &[]),
+        ];
+        let inner = Type::Object(inner_fields.as_slice().into());
+        let fields = [
+            &Field::new("name", &Type::String, &[]),
+            &Field::new("age", &Type::Int, &[]),
+            &Field::new("settings", &inner, &[]),
+        ];
+
+        assert_eq!(
+            default_json(&Type::Object(fields.as_slice().into())),
+            json!({
+                "name": "",
+                "age": 0,
+                "settings": {
+                    "enabled": false,
+                    "nickname": null,
+                },
+            })
+        );
+    }
+}
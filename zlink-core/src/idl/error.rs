@@ -13,6 +13,10 @@ pub struct Error<'a> {
     fields: List<'a, Field<'a>>,
     /// Comments associated with this error.
     comments: List<'a, Comment<'a>>,
+    /// Whether this error was preceded by a blank line in the parsed IDL.
+    preceded_by_blank: bool,
+    /// The byte range of this error in the parsed IDL.
+    span: Option<(usize, usize)>,
 }
 
 impl<'a> Error<'a> {
@@ -26,6 +30,8 @@ impl<'a> Error<'a> {
             name,
             fields: List::Borrowed(fields),
             comments: List::Borrowed(comments),
+            preceded_by_blank: false,
+            span: None,
         }
     }
 
@@ -37,6 +43,8 @@ impl<'a> Error<'a> {
             name,
             fields: List::from(fields),
             comments: List::from(comments),
+            preceded_by_blank: false,
+            span: None,
         }
     }
 
@@ -45,6 +53,14 @@ impl<'a> Error<'a> {
         self.name
     }
 
+    /// Returns the fully-qualified name of the error in the given interface.
+    ///
+    /// This is the name used on the wire, e.g `org.varlink.service.InvalidParameter`.
+    #[cfg(feature = "std")]
+    pub fn fully_qualified_name(&self, interface: &str) -> String {
+        format!("{interface}.{}", self.name)
+    }
+
     /// Returns an iterator over the fields of the error.
     pub fn fields(&self) -> impl Iterator<Item = &Field<'a>> {
         self.fields.iter()
@@ -59,6 +75,33 @@ impl<'a> Error<'a> {
     pub fn comments(&self) -> impl Iterator<Item = &Comment<'a>> {
         self.comments.iter()
     }
+
+    /// Returns true if this error was preceded by a blank line in the parsed IDL.
+    ///
+    /// This is used by [`super::Interface::format`] to preserve the grouping of members.
+    pub fn preceded_by_blank(&self) -> bool {
+        self.preceded_by_blank
+    }
+
+    /// Set whether this error is preceded by a blank line.
+    pub fn set_preceded_by_blank(mut self, preceded_by_blank: bool) -> Self {
+        self.preceded_by_blank = preceded_by_blank;
+        self
+    }
+
+    /// Returns the `(start, end)` byte offsets of this error in the IDL it was parsed from.
+    ///
+    /// The range includes the preceding comments. Returns `None` if the error wasn't parsed.
+    pub fn span(&self) -> Option<(usize, usize)> {
+        self.span
+    }
+
+    /// Set the byte offsets of this error in the parsed IDL.
+    #[cfg(feature = "idl-parse")]
+    pub(crate) fn set_span(mut self, span: (usize, usize)) -> Self {
+        self.span = Some(span);
+        self
+    }
 }
 
 impl<'a> fmt::Display for Error<'a> {
@@ -67,16 +110,8 @@ impl<'a> fmt::Display for Error<'a> {
         for comment in self.comments.iter() {
             writeln!(f, "{comment}")?;
         }
-        write!(f, "error {} (", self.name)?;
-        let mut first = true;
-        for field in self.fields.iter() {
-            if !first {
-                write!(f, ", ")?;
-            }
-            first = false;
-            write!(f, "{field}")?;
-        }
-        write!(f, ")")
+        write!(f, "error {} ", self.name)?;
+        super::field::write_fields(f, &self.fields)
     }
 }
 
@@ -117,6 +152,16 @@ mod tests {
         assert!(error.has_no_fields());
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn fully_qualified_name() {
+        let error = Error::new("InterfaceNotFound", &[], &[]);
+        assert_eq!(
+            error.fully_qualified_name("org.varlink.service"),
+            "org.varlink.service.InterfaceNotFound"
+        );
+    }
+
     #[test]
     fn display_with_comments() {
         use crate::idl::Comment;
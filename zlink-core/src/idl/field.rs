@@ -13,6 +13,8 @@ pub struct Field<'a> {
     ty: TypeRef<'a>,
     /// Comments associated with this field.
     comments: List<'a, Comment<'a>>,
+    /// The byte range of this field in the parsed IDL.
+    span: Option<(usize, usize)>,
 }
 
 /// Type alias for method parameters, which have the same structure as fields.
@@ -25,6 +27,7 @@ impl<'a> Field<'a> {
             name,
             ty: TypeRef::new(ty),
             comments: List::Borrowed(comments),
+            span: None,
         }
     }
 
@@ -36,6 +39,7 @@ impl<'a> Field<'a> {
             name,
             ty: TypeRef::new_owned(ty),
             comments: List::from(comments),
+            span: None,
         }
     }
 
@@ -53,6 +57,21 @@ impl<'a> Field<'a> {
     pub fn comments(&self) -> impl Iterator<Item = &Comment<'a>> {
         self.comments.iter()
     }
+
+    /// Returns the `(start, end)` byte offsets of this field in the IDL it was parsed from.
+    ///
+    /// The range includes the preceding comments. Returns `None` if the field wasn't parsed, or if
+    /// it belongs to an inline type.
+    pub fn span(&self) -> Option<(usize, usize)> {
+        self.span
+    }
+
+    /// Set the byte offsets of this field in the parsed IDL.
+    #[cfg(feature = "idl-parse")]
+    pub(crate) fn set_span(mut self, span: (usize, usize)) -> Self {
+        self.span = Some(span);
+        self
+    }
 }
 
 impl<'a> fmt::Display for Field<'a> {
@@ -65,6 +84,39 @@ impl<'a> fmt::Display for Field<'a> {
     }
 }
 
+/// Write a parenthesized list of fields, e.g the fields of an object or the parameters of a method.
+///
+/// The fields are written on a single line, unless any of them has comments. In that case, each
+/// field is written on its own line, preceded by its comments, so the result remains valid IDL.
+pub(super) fn write_fields(
+    f: &mut fmt::Formatter<'_>,
+    fields: &List<'_, Field<'_>>,
+) -> fmt::Result {
+    if !fields.iter().any(|field| !field.comments.is_empty()) {
+        write!(f, "(")?;
+        let mut first = true;
+        for field in fields.iter() {
+            if !first {
+                write!(f, ", ")?;
+            }
+            first = false;
+            write!(f, "{field}")?;
+        }
+        return write!(f, ")");
+    }
+
+    writeln!(f, "(")?;
+    let mut fields = fields.iter().peekable();
+    while let Some(field) = fields.next() {
+        for comment in field.comments() {
+            writeln!(f, "\t{comment}")?;
+        }
+        let separator = if fields.peek().is_some() { "," } else { "" };
+        writeln!(f, "\t{}: {}{separator}", field.name, field.ty)?;
+    }
+    write!(f, ")")
+}
+
 impl<'a> PartialEq for Field<'a> {
     fn eq(&self, other: &Self) -> bool {
         self.name == other.name && self.ty == other.ty
@@ -58,6 +58,18 @@ impl<'a> Interface<'a> {
         }
     }
 
+    /// Parse an interface from IDL, with the given policy for detached comments.
+    ///
+    /// Parsing through [`TryFrom<&str>`] associates comments with the following definition even
+    /// if they're separated from it by a blank line, i-e it uses [`super::CommentPolicy::Attach`].
+    #[cfg(feature = "idl-parse")]
+    pub fn parse_with_comment_policy(
+        input: &'a str,
+        policy: super::CommentPolicy,
+    ) -> Result<Self, Error> {
+        super::parse::parse_interface_with_comment_policy(input, policy)
+    }
+
     /// Returns the name of the interface.
     pub fn name(&self) -> &'a str {
         self.name
@@ -78,6 +90,138 @@ impl<'a> Interface<'a> {
         self.errors.iter()
     }
 
+    /// Returns an iterator over the methods of the interface that have output parameters.
+    pub fn methods_with_outputs(&self) -> impl Iterator<Item = &super::Method<'a>> {
+        self.methods().filter(|m| !m.has_no_outputs())
+    }
+
+    /// Returns an iterator over the errors of the interface that have fields.
+    pub fn errors_with_fields(&self) -> impl Iterator<Item = &super::Error<'a>> {
+        self.errors().filter(|e| !e.has_no_fields())
+    }
+
+    /// Returns an iterator over the methods of the interface that plausibly support the `more`
+    /// flag, i-e multiple replies.
+    ///
+    /// Since the IDL has no way to declare this, it's only a heuristic: methods with names starting
+    /// with a verb typically used for streaming (e.g `Monitor` or `Watch`), or with comments
+    /// mentioning the `more` flag are selected.
+    pub fn streaming_candidates(&self) -> impl Iterator<Item = &super::Method<'a>> {
+        const STREAMING_PREFIXES: [&str; 6] = [
+            "Monitor",
+            "Watch",
+            "Subscribe",
+            "Listen",
+            "Follow",
+            "Stream",
+        ];
+
+        self.methods().filter(|m| {
+            STREAMING_PREFIXES.iter().any(|p| m.name().starts_with(p))
+                || m.comments().any(|c| c.text().contains("`more`"))
+        })
+    }
+
+    /// Returns the number of methods in the interface.
+    pub fn method_count(&self) -> usize {
+        self.methods.len()
+    }
+
+    /// Returns the number of custom types in the interface.
+    pub fn custom_type_count(&self) -> usize {
+        self.custom_types.len()
+    }
+
+    /// Returns the number of errors in the interface.
+    pub fn error_count(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Add a method to the interface.
+    ///
+    /// Returns `false`, without adding the method, if the interface already has a method with the
+    /// same name. A borrowed interface is converted into an owned one.
+    #[cfg(feature = "std")]
+    pub fn add_method(&mut self, method: super::Method<'a>) -> bool {
+        if self.methods.iter().any(|m| m.name() == method.name()) {
+            return false;
+        }
+        self.methods.to_mut().push(method);
+
+        true
+    }
+
+    /// Remove the method with the given name from the interface.
+    ///
+    /// Returns `false` if the interface has no such method. A borrowed interface is converted into
+    /// an owned one.
+    #[cfg(feature = "std")]
+    pub fn remove_method_by_name(&mut self, name: &str) -> bool {
+        let methods = self.methods.to_mut();
+        let len = methods.len();
+        methods.retain(|m| m.name() != name);
+
+        methods.len() != len
+    }
+
+    /// Add a custom type to the interface.
+    ///
+    /// Returns `false`, without adding the type, if the interface already has a custom type with
+    /// the same name. A borrowed interface is converted into an owned one.
+    #[cfg(feature = "std")]
+    pub fn add_custom_type(&mut self, custom_type: super::CustomType<'a>) -> bool {
+        if self
+            .custom_types
+            .iter()
+            .any(|t| t.name() == custom_type.name())
+        {
+            return false;
+        }
+        self.custom_types.to_mut().push(custom_type);
+
+        true
+    }
+
+    /// Remove the custom type with the given name from the interface.
+    ///
+    /// Returns `false` if the interface has no such type. A borrowed interface is converted into
+    /// an owned one.
+    #[cfg(feature = "std")]
+    pub fn remove_custom_type_by_name(&mut self, name: &str) -> bool {
+        let custom_types = self.custom_types.to_mut();
+        let len = custom_types.len();
+        custom_types.retain(|t| t.name() != name);
+
+        custom_types.len() != len
+    }
+
+    /// Add an error to the interface.
+    ///
+    /// Returns `false`, without adding the error, if the interface already has an error with the
+    /// same name. A borrowed interface is converted into an owned one.
+    #[cfg(feature = "std")]
+    pub fn add_error(&mut self, error: super::Error<'a>) -> bool {
+        if self.errors.iter().any(|e| e.name() == error.name()) {
+            return false;
+        }
+        self.errors.to_mut().push(error);
+
+        true
+    }
+
+    /// Remove the error with the given name from the interface.
+    ///
+    /// Returns `false` if the interface has no such error. A borrowed interface is converted into
+    /// an owned one.
+    #[cfg(feature = "std")]
+    pub fn remove_error_by_name(&mut self, name: &str) -> bool {
+        let errors = self.errors.to_mut();
+        let len = errors.len();
+        errors.retain(|e| e.name() != name);
+
+        errors.len() != len
+    }
+
     /// Returns an iterator over the comments associated with this interface.
     pub fn comments(&self) -> impl Iterator<Item = &super::Comment<'a>> {
         self.comments.iter()
@@ -87,6 +231,128 @@ impl<'a> Interface<'a> {
     pub fn is_empty(&self) -> bool {
         self.methods.is_empty() && self.custom_types.is_empty() && self.errors.is_empty()
     }
+
+    /// Whether this interface defines the same contract as `other`.
+    ///
+    /// Unlike `==`, this ignores all comments and the order in which members, fields and enum
+    /// variants are defined, so it's suitable for checking if two descriptions of an interface are
+    /// compatible.
+    pub fn structurally_eq(&self, other: &Interface<'a>) -> bool {
+        self.name == other.name
+            && same_members(
+                || self.methods(),
+                || other.methods(),
+                |a, b| {
+                    a.name() == b.name()
+                        && same_fields(|| a.inputs(), || b.inputs())
+                        && same_fields(|| a.outputs(), || b.outputs())
+                },
+            )
+            && same_members(
+                || self.errors(),
+                || other.errors(),
+                |a, b| a.name() == b.name() && same_fields(|| a.fields(), || b.fields()),
+            )
+            && same_members(
+                || self.custom_types(),
+                || other.custom_types(),
+                |a, b| match (a, b) {
+                    (super::CustomType::Object(a), super::CustomType::Object(b)) => {
+                        a.name() == b.name() && same_fields(|| a.fields(), || b.fields())
+                    }
+                    (super::CustomType::Enum(a), super::CustomType::Enum(b)) => {
+                        a.name() == b.name()
+                            && same_members(
+                                || a.variants(),
+                                || b.variants(),
+                                |a, b| a.name() == b.name(),
+                            )
+                    }
+                    _ => false,
+                },
+            )
+    }
+
+    /// A stable hash of the contract defined by this interface.
+    ///
+    /// Like [`Interface::structurally_eq`], this ignores all comments and the order in which
+    /// members, fields and enum variants are defined, so structurally equal interfaces have the
+    /// same fingerprint. Since it's computed with a fixed hash function (FNV-1a), the fingerprint
+    /// is the same across runs and platforms, and can be stored or exchanged with a peer to check
+    /// if both agree on the contract.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+        hasher.write(self.name.as_bytes());
+        hasher.write_u64(unordered_hash(self.methods().map(|method| {
+            let mut hasher = Fnv1a::new();
+            hasher.write(method.name().as_bytes());
+            hasher.write_u64(fields_hash(method.inputs()));
+            hasher.write_u64(fields_hash(method.outputs()));
+            hasher.finish()
+        })));
+        hasher.write_u64(unordered_hash(self.errors().map(|error| {
+            let mut hasher = Fnv1a::new();
+            hasher.write(error.name().as_bytes());
+            hasher.write_u64(fields_hash(error.fields()));
+            hasher.finish()
+        })));
+        hasher.write_u64(unordered_hash(self.custom_types().map(|custom_type| {
+            let mut hasher = Fnv1a::new();
+            match custom_type {
+                super::CustomType::Object(object) => {
+                    hasher.write(b"object ");
+                    hasher.write(object.name().as_bytes());
+                    hasher.write_u64(fields_hash(object.fields()));
+                }
+                super::CustomType::Enum(enum_type) => {
+                    hasher.write(b"enum ");
+                    hasher.write(enum_type.name().as_bytes());
+                    hasher.write_u64(unordered_hash(
+                        enum_type
+                            .variants()
+                            .map(|v| bytes_hash(v.name().as_bytes())),
+                    ));
+                }
+            }
+            hasher.finish()
+        })));
+
+        hasher.finish()
+    }
+
+    /// Write the interface in IDL form, preserving the blank-line grouping of members.
+    ///
+    /// Unlike the [`fmt::Display`] implementation, which always separates members with a blank
+    /// line, this only puts a blank line before members that were preceded by one in the parsed
+    /// IDL (see [`super::Method::preceded_by_blank`]). Members are still written in the order:
+    /// custom types, methods and then errors.
+    pub fn format<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        fn separator(preceded_by_blank: bool) -> &'static str {
+            if preceded_by_blank {
+                "\n\n"
+            } else {
+                "\n"
+            }
+        }
+
+        for comment in self.comments.iter() {
+            writeln!(w, "{comment}")?;
+        }
+        write!(w, "interface {}", self.name)?;
+        for custom_type in self.custom_types.iter() {
+            let sep = separator(custom_type.preceded_by_blank());
+            write!(w, "{sep}{custom_type}")?;
+        }
+        for method in self.methods.iter() {
+            let sep = separator(method.preceded_by_blank());
+            write!(w, "{sep}{method}")?;
+        }
+        for error in self.errors.iter() {
+            let sep = separator(error.preceded_by_blank());
+            write!(w, "{sep}{error}")?;
+        }
+        Ok(())
+    }
 }
 
 impl<'a> fmt::Display for Interface<'a> {
@@ -118,6 +384,132 @@ impl<'a> TryFrom<&'a str> for Interface<'a> {
     }
 }
 
+// Whether both iterators yield the same items, regardless of their order.
+fn same_members<'i, T, A, B>(
+    a: impl Fn() -> A,
+    b: impl Fn() -> B,
+    eq: impl Fn(&T, &T) -> bool,
+) -> bool
+where
+    T: 'i,
+    A: Iterator<Item = &'i T>,
+    B: Iterator<Item = &'i T>,
+{
+    a().count() == b().count() && a().all(|a| b().any(|b| eq(a, b)))
+}
+
+fn same_fields<'i, 'a: 'i, A, B>(a: impl Fn() -> A, b: impl Fn() -> B) -> bool
+where
+    A: Iterator<Item = &'i super::Field<'a>>,
+    B: Iterator<Item = &'i super::Field<'a>>,
+{
+    same_members(a, b, |a, b| {
+        a.name() == b.name() && same_type(a.ty(), b.ty())
+    })
+}
+
+fn same_type(a: &super::Type<'_>, b: &super::Type<'_>) -> bool {
+    use super::Type;
+
+    match (a, b) {
+        (Type::Optional(a), Type::Optional(b))
+        | (Type::Array(a), Type::Array(b))
+        | (Type::Map(a), Type::Map(b)) => same_type(a.inner(), b.inner()),
+        (Type::Enum(a), Type::Enum(b)) => {
+            same_members(|| a.iter(), || b.iter(), |a, b| a.name() == b.name())
+        }
+        (Type::Object(a), Type::Object(b)) => same_fields(|| a.iter(), || b.iter()),
+        _ => a == b,
+    }
+}
+
+/// The 64-bit FNV-1a hash function, used for [`Interface::fingerprint`].
+///
+/// Unlike the hashers of `std`, its output is fully specified and hence stable.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+fn bytes_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = Fnv1a::new();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+// Combine hashes regardless of their order.
+fn unordered_hash(hashes: impl Iterator<Item = u64>) -> u64 {
+    hashes.fold(0, u64::wrapping_add)
+}
+
+fn fields_hash<'i, 'a: 'i>(fields: impl Iterator<Item = &'i super::Field<'a>>) -> u64 {
+    unordered_hash(fields.map(|field| {
+        let mut hasher = Fnv1a::new();
+        hasher.write(field.name().as_bytes());
+        hasher.write_u64(type_hash(field.ty()));
+        hasher.finish()
+    }))
+}
+
+fn type_hash(ty: &super::Type<'_>) -> u64 {
+    use super::Type;
+
+    let mut hasher = Fnv1a::new();
+    match ty {
+        Type::Bool => hasher.write(b"bool"),
+        Type::Int => hasher.write(b"int"),
+        Type::Float => hasher.write(b"float"),
+        Type::String => hasher.write(b"string"),
+        Type::ForeignObject => hasher.write(b"object"),
+        Type::Optional(inner) => {
+            hasher.write(b"?");
+            hasher.write_u64(type_hash(inner.inner()));
+        }
+        Type::Array(inner) => {
+            hasher.write(b"[]");
+            hasher.write_u64(type_hash(inner.inner()));
+        }
+        Type::Map(inner) => {
+            hasher.write(b"[string]");
+            hasher.write_u64(type_hash(inner.inner()));
+        }
+        Type::Custom(name) => {
+            hasher.write(b"custom ");
+            hasher.write(name.as_bytes());
+        }
+        Type::Enum(variants) => {
+            hasher.write(b"enum");
+            hasher.write_u64(unordered_hash(
+                variants.iter().map(|v| bytes_hash(v.name().as_bytes())),
+            ));
+        }
+        Type::Object(fields) => {
+            hasher.write(b"object ");
+            hasher.write_u64(fields_hash(fields.iter()));
+        }
+    }
+
+    hasher.finish()
+}
+
 impl PartialEq for Interface<'_> {
     fn eq(&self, other: &Self) -> bool {
         self.name == other.name
@@ -246,15 +638,206 @@ error ExpectedMore ()
         }
     }
 
+    #[cfg(feature = "idl-parse")]
+    #[test]
+    fn filtered_members() {
+        let interface = Interface::try_from(
+            r#"interface org.example.filters
+
+method Get() -> (value: int)
+method Set(value: int) -> ()
+method MonitorValue() -> (value: int)
+# Replies multiple times if called with the `more` flag.
+method List() -> (values: []int)
+
+error NotFound ()
+error Invalid (reason: string)"#,
+        )
+        .unwrap();
+
+        let methods: Vec<_> = interface.methods_with_outputs().map(|m| m.name()).collect();
+        assert_eq!(methods, ["Get", "MonitorValue", "List"]);
+        let methods: Vec<_> = interface.streaming_candidates().map(|m| m.name()).collect();
+        assert_eq!(methods, ["MonitorValue", "List"]);
+        let errors: Vec<_> = interface.errors_with_fields().map(|e| e.name()).collect();
+        assert_eq!(errors, ["Invalid"]);
+    }
+
+    #[cfg(feature = "idl-parse")]
+    #[test]
+    fn structural_equality() {
+        let interface = Interface::try_from(
+            r#"# The first description.
+interface org.example.contract
+
+type Point (x: int, y: int)
+
+type Color (red, green, blue)
+
+# Get a point.
+method Get(id: int, mode: (fast, slow)) -> (point: Point)
+
+method Set(point: Point) -> ()
+
+error NotFound (id: int)"#,
+        )
+        .unwrap();
+        let reordered = Interface::try_from(
+            r#"interface org.example.contract
+
+error NotFound (id: int)
+
+# Set a point.
+method Set(point: Point) -> ()
+
+method Get(mode: (slow, fast), id: int) -> (point: Point)
+
+type Color (
+    # The best one.
+    blue,
+    red,
+    green
+)
+
+# A point.
+type Point (y: int, x: int)"#,
+        )
+        .unwrap();
+        assert!(interface.structurally_eq(&reordered));
+        assert!(reordered.structurally_eq(&interface));
+        assert_ne!(interface, reordered);
+        assert_eq!(interface.fingerprint(), reordered.fingerprint());
+
+        let changed = Interface::try_from(
+            r#"interface org.example.contract
+
+type Point (x: int, y: float)
+
+type Color (red, green, blue)
+
+method Get(id: int, mode: (fast, slow)) -> (point: Point)
+
+method Set(point: Point) -> ()
+
+error NotFound (id: int)"#,
+        )
+        .unwrap();
+        assert!(!interface.structurally_eq(&changed));
+        assert_ne!(interface.fingerprint(), changed.fingerprint());
+
+        let missing = Interface::try_from(
+            r#"interface org.example.contract
+
+type Point (x: int, y: int)
+
+type Color (red, green, blue)
+
+method Get(id: int, mode: (fast, slow)) -> (point: Point)
+
+error NotFound (id: int)"#,
+        )
+        .unwrap();
+        assert!(!interface.structurally_eq(&missing));
+        assert!(!missing.structurally_eq(&interface));
+        assert_ne!(interface.fingerprint(), missing.fingerprint());
+    }
+
+    #[test]
+    fn stable_fingerprint() {
+        let ping_inputs = [&Parameter::new("ping", &Type::String, &[])];
+        let ping_outputs = [&Parameter::new("pong", &Type::String, &[])];
+        let ping = Method::new("Ping", &ping_inputs, &ping_outputs, &[]);
+        let interface = Interface::new("org.example.ping", &[&ping], &[], &[], &[]);
+
+        // The fingerprint must never change, as it may be stored or exchanged with peers.
+        assert_eq!(interface.fingerprint(), 0x0276_b8ed_1286_90fb);
+    }
+
     #[test]
     fn empty_interface() {
         let interface = Interface::new("com.example.empty", &[], &[], &[], &[]);
         assert!(interface.is_empty());
+        assert_eq!(interface.method_count(), 0);
         assert_eq!(interface.methods().count(), 0);
         assert_eq!(interface.errors().count(), 0);
         assert_eq!(interface.custom_types().count(), 0);
     }
 
+    #[cfg(feature = "idl-parse")]
+    #[test]
+    fn add_and_remove_members() {
+        let mut interface = Interface::try_from(
+            r#"interface org.example.mutation
+
+type Point (x: int, y: int)
+
+method Get() -> (point: Point)
+
+# Deprecated: use Get instead.
+method GetLegacy() -> (x: int, y: int)
+
+error NotFound ()"#,
+        )
+        .unwrap();
+
+        assert!(interface.remove_method_by_name("GetLegacy"));
+        assert!(!interface.remove_method_by_name("GetLegacy"));
+        assert!(!interface.remove_custom_type_by_name("Unknown"));
+        assert!(interface.remove_error_by_name("NotFound"));
+
+        let set = Method::new_owned(
+            "Set",
+            vec![Parameter::new_owned("point", Type::Custom("Point"), vec![])],
+            vec![],
+            vec![],
+        );
+        assert!(interface.add_method(set.clone()));
+        assert!(!interface.add_method(set));
+        assert!(interface.add_error(Error::new_owned("Invalid", vec![], vec![])));
+
+        assert_eq!(
+            interface.to_string(),
+            "interface org.example.mutation
+
+type Point (x: int, y: int)
+
+method Get() -> (point: Point)
+
+method Set(point: Point) -> ()
+
+error Invalid ()"
+        );
+
+        // Borrowed interfaces are converted to owned ones on mutation.
+        let ping = Method::new("Ping", &[], &[], &[]);
+        let methods = [&ping];
+        let mut interface = Interface::new("org.example.borrowed", &methods, &[], &[], &[]);
+        assert!(interface.remove_method_by_name("Ping"));
+        assert_eq!(interface.method_count(), 0);
+    }
+
+    #[cfg(feature = "idl-parse")]
+    #[test]
+    fn member_counts() {
+        let interface = Interface::try_from(
+            r#"interface org.example.counts
+
+type Point (x: int, y: int)
+
+method Get() -> (point: Point)
+method Set(point: Point) -> ()
+method Reset() -> ()
+
+error NotFound()
+error Invalid(reason: string)
+"#,
+        )
+        .unwrap();
+        assert_eq!(interface.method_count(), 3);
+        assert_eq!(interface.custom_type_count(), 1);
+        assert_eq!(interface.error_count(), 2);
+    }
+
     #[cfg(feature = "idl-parse")]
     #[test]
     fn systemd_resolved_interface_parsing() {
@@ -0,0 +1,188 @@
+//! JSON Schema generation for Varlink interfaces.
+
+use serde_json::{json, Map, Value};
+
+use super::{comments_to_doc, Comment, CustomType, Field, Interface, Type};
+
+impl Interface<'_> {
+    /// Render the interface as a JSON Schema document.
+    ///
+    /// Each custom type, error and method of the interface is rendered as a definition under
+    /// `$defs`. Methods are split into two definitions, `<Method>Input` and `<Method>Output`, for
+    /// their input and output parameters. Varlink types are mapped to JSON Schema as follows:
+    ///
+    /// * `bool`: `boolean`
+    /// * `int`: `integer`
+    /// * `float`: `number`
+    /// * `string`: `string`
+    /// * `object`: `object`
+    /// * optional types: the inner type or `null`
+    /// * arrays: `array` with the element type as `items`
+    /// * maps: `object` with the value type as `additionalProperties`
+    /// * custom types: a `$ref` to their definition
+    /// * enums: `string` with the variants as `enum`
+    ///
+    /// Comments are rendered as descriptions.
+    pub fn to_json_schema(&self) -> Value {
+        let mut defs = Map::new();
+        for custom_type in self.custom_types() {
+            let (name, schema) = match custom_type {
+                CustomType::Object(object) => (
+                    object.name(),
+                    object_schema(object.fields(), object.comments()),
+                ),
+                CustomType::Enum(enm) => (
+                    enm.name(),
+                    with_description(
+                        enum_schema(enm.variants().map(|v| v.name())),
+                        enm.comments(),
+                    ),
+                ),
+            };
+            defs.insert(name.into(), schema);
+        }
+        for error in self.errors() {
+            defs.insert(
+                error.name().into(),
+                object_schema(error.fields(), error.comments()),
+            );
+        }
+        for method in self.methods() {
+            defs.insert(
+                format!("{}Input", method.name()),
+                object_schema(method.inputs(), method.comments()),
+            );
+            defs.insert(
+                format!("{}Output", method.name()),
+                object_schema(method.outputs(), core::iter::empty()),
+            );
+        }
+
+        with_description(
+            json!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": self.name(),
+                "$defs": defs,
+            }),
+            self.comments(),
+        )
+    }
+}
+
+fn type_schema(ty: &Type<'_>) -> Value {
+    match ty {
+        Type::Bool => json!({ "type": "boolean" }),
+        Type::Int => json!({ "type": "integer" }),
+        Type::Float => json!({ "type": "number" }),
+        Type::String => json!({ "type": "string" }),
+        Type::ForeignObject => json!({ "type": "object" }),
+        Type::Optional(inner) => json!({ "anyOf": [type_schema(inner), { "type": "null" }] }),
+        Type::Array(element) => json!({ "type": "array", "items": type_schema(element) }),
+        Type::Map(value) => json!({
+            "type": "object",
+            "additionalProperties": type_schema(value),
+        }),
+        Type::Custom(name) => json!({ "$ref": format!("#/$defs/{name}") }),
+        Type::Enum(variants) => enum_schema(variants.iter().map(|v| v.name())),
+        Type::Object(fields) => object_schema(fields.iter(), core::iter::empty()),
+    }
+}
+
+fn enum_schema<'a>(variants: impl Iterator<Item = &'a str>) -> Value {
+    json!({ "type": "string", "enum": variants.collect::<Vec<_>>() })
+}
+
+fn object_schema<'c, 'a: 'c>(
+    fields: impl Iterator<Item = &'c Field<'a>>,
+    comments: impl IntoIterator<Item = &'c Comment<'a>>,
+) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for field in fields {
+        // Optional fields can be omitted.
+        if !matches!(field.ty(), Type::Optional(_)) {
+            required.push(field.name());
+        }
+        let schema = with_description(type_schema(field.ty()), field.comments());
+        properties.insert(field.name().into(), schema);
+    }
+
+    with_description(
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+        }),
+        comments,
+    )
+}
+
+fn with_description<'c, 'a: 'c>(
+    mut schema: Value,
+    comments: impl IntoIterator<Item = &'c Comment<'a>>,
+) -> Value {
+    let description = comments_to_doc(comments);
+    if !description.is_empty() {
+        schema["description"] = Value::String(description);
+    }
+
+    schema
+}
+
+#[cfg(all(test, feature = "idl-parse"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn method_schema() {
+        let interface = Interface::try_from(
+            r#"
+# A service managing persons.
+interface org.example.persons
+
+type Person (name: string, age: ?int)
+
+type Role (admin, user)
+
+# Add persons.
+method Add(persons: []Person, roles: [string]Role) -> (ids: []int)
+
+error Invalid (reason: string)
+"#,
+        )
+        .unwrap();
+        let schema = interface.to_json_schema();
+
+        assert_eq!(schema["title"], "org.example.persons");
+        assert_eq!(schema["description"], "A service managing persons.");
+        assert_eq!(
+            schema["$defs"]["AddInput"],
+            json!({
+                "type": "object",
+                "description": "Add persons.",
+                "properties": {
+                    "persons": { "type": "array", "items": { "$ref": "#/$defs/Person" } },
+                    "roles": {
+                        "type": "object",
+                        "additionalProperties": { "$ref": "#/$defs/Role" },
+                    },
+                },
+                "required": ["persons", "roles"],
+            })
+        );
+        assert_eq!(
+            schema["$defs"]["AddOutput"]["properties"]["ids"],
+            json!({ "type": "array", "items": { "type": "integer" } })
+        );
+        assert_eq!(
+            schema["$defs"]["Person"]["properties"]["age"],
+            json!({ "anyOf": [{ "type": "integer" }, { "type": "null" }] })
+        );
+        assert_eq!(schema["$defs"]["Person"]["required"], json!(["name"]));
+        assert_eq!(
+            schema["$defs"]["Role"],
+            json!({ "type": "string", "enum": ["admin", "user"] })
+        );
+        assert_eq!(schema["$defs"]["Invalid"]["required"], json!(["reason"]));
+    }
+}
@@ -57,6 +57,22 @@ impl<'a, T> List<'a, T> {
             List::Owned(vec) => Some(vec),
         }
     }
+
+    /// The mutable owned vector of values, converting a borrowed list into an owned one first.
+    #[cfg(feature = "std")]
+    pub fn to_mut(&mut self) -> &mut Vec<T>
+    where
+        T: Clone,
+    {
+        if let List::Borrowed(slice) = self {
+            *self = List::Owned(slice.iter().map(|&item| item.clone()).collect());
+        }
+
+        match self {
+            List::Owned(vec) => vec,
+            List::Borrowed(_) => unreachable!(),
+        }
+    }
 }
 
 /// Iterator over list items.
@@ -2,7 +2,7 @@
 
 use core::fmt;
 
-use super::{Comment, List, Parameter};
+use super::{Comment, List, Parameter, Type};
 
 /// A method definition in Varlink IDL.
 #[derive(Debug, Clone, Eq)]
@@ -15,6 +15,12 @@ pub struct Method<'a> {
     outputs: List<'a, Parameter<'a>>,
     /// Comments associated with this method.
     comments: List<'a, Comment<'a>>,
+    /// The comment following this method on the same line.
+    trailing_comment: Option<Comment<'a>>,
+    /// Whether this method was preceded by a blank line in the parsed IDL.
+    preceded_by_blank: bool,
+    /// The byte range of this method in the parsed IDL.
+    span: Option<(usize, usize)>,
 }
 
 impl<'a> Method<'a> {
@@ -30,6 +36,9 @@ impl<'a> Method<'a> {
             inputs: List::Borrowed(inputs),
             outputs: List::Borrowed(outputs),
             comments: List::Borrowed(comments),
+            trailing_comment: None,
+            preceded_by_blank: false,
+            span: None,
         }
     }
 
@@ -46,6 +55,9 @@ impl<'a> Method<'a> {
             inputs: List::from(inputs),
             outputs: List::from(outputs),
             comments: List::from(comments),
+            trailing_comment: None,
+            preceded_by_blank: false,
+            span: None,
         }
     }
 
@@ -54,16 +66,47 @@ impl<'a> Method<'a> {
         self.name
     }
 
+    /// Returns the fully-qualified name of the method in the given interface.
+    ///
+    /// This is the name used on the wire, e.g `org.varlink.service.GetInfo`.
+    #[cfg(feature = "std")]
+    pub fn fully_qualified_name(&self, interface: &str) -> String {
+        format!("{interface}.{}", self.name)
+    }
+
     /// Returns an iterator over the input parameters.
     pub fn inputs(&self) -> impl Iterator<Item = &Parameter<'a>> {
         self.inputs.iter()
     }
 
+    /// Returns an iterator over the required input parameters, i-e the non-optional ones.
+    pub fn required_inputs(&self) -> impl Iterator<Item = &Parameter<'a>> {
+        self.inputs()
+            .filter(|p| !matches!(p.ty(), Type::Optional(_)))
+    }
+
+    /// Returns an iterator over the optional input parameters.
+    pub fn optional_inputs(&self) -> impl Iterator<Item = &Parameter<'a>> {
+        self.inputs()
+            .filter(|p| matches!(p.ty(), Type::Optional(_)))
+    }
+
     /// Returns an iterator over the output parameters.
     pub fn outputs(&self) -> impl Iterator<Item = &Parameter<'a>> {
         self.outputs.iter()
     }
 
+    /// A measure of how complex this method is, e.g to flag overly complex methods.
+    ///
+    /// This is the number of its parameters plus the sum of the [complexities](Type::complexity)
+    /// of their types.
+    pub fn complexity(&self) -> usize {
+        self.inputs()
+            .chain(self.outputs())
+            .map(|param| 1 + param.ty().complexity())
+            .sum()
+    }
+
     /// Returns true if the method has no input parameters.
     pub fn has_no_inputs(&self) -> bool {
         self.inputs.is_empty()
@@ -78,6 +121,64 @@ impl<'a> Method<'a> {
     pub fn comments(&self) -> impl Iterator<Item = &Comment<'a>> {
         self.comments.iter()
     }
+
+    /// Returns true if this method is marked as deprecated.
+    ///
+    /// See [`Method::deprecation_note`] for details.
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecation_note().is_some()
+    }
+
+    /// Returns the deprecation note if this method is marked as deprecated.
+    ///
+    /// A method is marked as deprecated through a `@deprecated` marker in its comments (see
+    /// [`Comment::deprecation_note`]), e.g `# @deprecated: use Bar instead`.
+    pub fn deprecation_note(&self) -> Option<&'a str> {
+        self.comments
+            .iter()
+            .chain(self.trailing_comment.iter())
+            .find_map(Comment::deprecation_note)
+    }
+
+    /// Returns the comments following this method on the same line.
+    ///
+    /// A method can have at most one trailing comment, e.g `method Foo() -> () # Does foo.`.
+    pub fn trailing_comments(&self) -> impl Iterator<Item = &Comment<'a>> {
+        self.trailing_comment.iter()
+    }
+
+    /// Set the comment following this method on the same line.
+    pub fn set_trailing_comment(mut self, trailing_comment: Option<Comment<'a>>) -> Self {
+        self.trailing_comment = trailing_comment;
+        self
+    }
+
+    /// Returns true if this method was preceded by a blank line in the parsed IDL.
+    ///
+    /// This is used by [`super::Interface::format`] to preserve the grouping of members.
+    pub fn preceded_by_blank(&self) -> bool {
+        self.preceded_by_blank
+    }
+
+    /// Set whether this method is preceded by a blank line.
+    pub fn set_preceded_by_blank(mut self, preceded_by_blank: bool) -> Self {
+        self.preceded_by_blank = preceded_by_blank;
+        self
+    }
+
+    /// Returns the `(start, end)` byte offsets of this method in the IDL it was parsed from.
+    ///
+    /// The range includes the preceding comments. Returns `None` if the method wasn't parsed.
+    pub fn span(&self) -> Option<(usize, usize)> {
+        self.span
+    }
+
+    /// Set the byte offsets of this method in the parsed IDL.
+    #[cfg(feature = "idl-parse")]
+    pub(crate) fn set_span(mut self, span: (usize, usize)) -> Self {
+        self.span = Some(span);
+        self
+    }
 }
 
 impl<'a> fmt::Display for Method<'a> {
@@ -86,27 +187,14 @@ impl<'a> fmt::Display for Method<'a> {
         for comment in self.comments.iter() {
             writeln!(f, "{comment}")?;
         }
-        write!(f, "method {}(", self.name)?;
-        let mut first = true;
-        for param in self.inputs.iter() {
-            if !first {
-                write!(f, ", ")?;
-            }
-            first = false;
-            write!(f, "{param}")?;
-        }
-        write!(f, ")")?;
-
-        write!(f, " -> (")?;
-        let mut first = true;
-        for param in self.outputs.iter() {
-            if !first {
-                write!(f, ", ")?;
-            }
-            first = false;
-            write!(f, "{param}")?;
+        write!(f, "method {}", self.name)?;
+        super::field::write_fields(f, &self.inputs)?;
+        write!(f, " -> ")?;
+        super::field::write_fields(f, &self.outputs)?;
+
+        if let Some(comment) = &self.trailing_comment {
+            write!(f, " {comment}")?;
         }
-        write!(f, ")")?;
 
         Ok(())
     }
@@ -147,6 +235,32 @@ mod tests {
         assert_eq!(outputs_vec[0].ty(), &Type::String);
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn fully_qualified_name() {
+        let method = Method::new("GetInfo", &[], &[], &[]);
+        assert_eq!(
+            method.fully_qualified_name("org.varlink.service"),
+            "org.varlink.service.GetInfo"
+        );
+    }
+
+    #[test]
+    fn required_and_optional_inputs() {
+        let name = Parameter::new("name", &Type::String, &[]);
+        let optional_int = Type::Optional(crate::idl::TypeRef::new(&Type::Int));
+        let age = Parameter::new("age", &optional_int, &[]);
+        let id = Parameter::new("id", &Type::Int, &[]);
+        let inputs = [&name, &age, &id];
+
+        let method = Method::new("Add", &inputs, &[], &[]);
+        let required: mayheap::Vec<_, 8> = method.required_inputs().map(|p| p.name()).collect();
+        assert_eq!(required.as_slice(), &["name", "id"]);
+        let optional: mayheap::Vec<_, 8> = method.optional_inputs().map(|p| p.name()).collect();
+        assert_eq!(optional.as_slice(), &["age"]);
+        assert_eq!(method.complexity(), 7);
+    }
+
     #[test]
     fn method_no_params() {
         let method = Method::new("Ping", &[], &[], &[]);
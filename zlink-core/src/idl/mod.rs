@@ -8,7 +8,7 @@ mod list;
 pub use list::List;
 
 mod r#type;
-pub use r#type::{Type, TypeRef};
+pub use r#type::{Type, TypeRef, TypeVisitor};
 
 mod custom_object;
 pub use custom_object::CustomObject;
@@ -32,10 +32,37 @@ mod error;
 pub use error::Error;
 
 mod comment;
+#[cfg(feature = "std")]
+pub use comment::comments_to_doc;
 pub use comment::Comment;
 
 mod interface;
 pub use interface::Interface;
 
+#[cfg(feature = "std")]
+mod builder;
+#[cfg(feature = "std")]
+pub use builder::{InterfaceBuilder, MethodBuilder};
+
+#[cfg(feature = "std")]
+mod default_json;
+#[cfg(feature = "std")]
+pub use default_json::default_json;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_json;
+#[cfg(feature = "arbitrary")]
+pub use arbitrary_json::arbitrary_json;
+
+#[cfg(feature = "std")]
+mod json_schema;
+
+#[cfg(feature = "std")]
+mod validate;
+#[cfg(feature = "std")]
+pub use validate::{validate_parameters, validate_value};
+
 #[cfg(feature = "idl-parse")]
 mod parse;
+#[cfg(feature = "idl-parse")]
+pub use parse::CommentPolicy;
@@ -7,6 +7,7 @@ use winnow::{
     ascii::multispace0,
     combinator::{alt, separated},
     error::{ErrMode, InputError, ParserError},
+    stream::Offset,
     token::{literal, take_while},
     ModalResult, Parser,
 };
@@ -19,6 +20,20 @@ use super::{
 #[cfg(feature = "std")]
 use std::vec::Vec;
 
+/// How comments separated from the following definition by a blank line are treated.
+///
+/// See [`Interface::parse_with_comment_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommentPolicy {
+    /// The comments belong to the following definition, as if there was no blank line.
+    #[default]
+    Attach,
+    /// The comments are free-floating, i-e not associated with any definition, and are dropped.
+    ///
+    /// This matches the convention of most documentation tools.
+    Detach,
+}
+
 /// Parse whitespace and comments according to Varlink grammar.
 /// The `_` production in Varlink grammar: whitespace / comment / eol_r
 fn ws<'a>(input: &mut &'a [u8]) -> ModalResult<(), InputError<&'a [u8]>> {
@@ -72,11 +87,14 @@ fn whitespace_only<'a>(input: &mut &'a [u8]) -> ModalResult<(), InputError<&'a [
 
 /// Convert bytes to str with input lifetime.
 fn bytes_to_str(bytes: &[u8]) -> &str {
-    // SAFETY: We only accept ASCII characters in our parsers
+    // SAFETY: Names are restricted to ASCII by the Varlink grammar, so that's all our parsers
+    // accept.
     core::str::from_utf8(bytes).unwrap()
 }
 
 /// Parse a field name: starts with letter, continues with alphanumeric and underscores.
+///
+/// Only ASCII is allowed, as per the Varlink grammar (`[A-Za-z](_?[A-Za-z0-9])*`).
 fn field_name<'a>(input: &mut &'a [u8]) -> ModalResult<&'a str, InputError<&'a [u8]>> {
     let start = *input;
     let mut pos = 0;
@@ -98,6 +116,8 @@ fn field_name<'a>(input: &mut &'a [u8]) -> ModalResult<&'a str, InputError<&'a [
 }
 
 /// Parse a type name: starts with uppercase letter, continues with alphanumeric.
+///
+/// Only ASCII is allowed, as per the Varlink grammar (`[A-Z][A-Za-z0-9]*`).
 fn type_name<'a>(input: &mut &'a [u8]) -> ModalResult<&'a str, InputError<&'a [u8]>> {
     let start = *input;
     if input.is_empty() || !input[0].is_ascii_uppercase() {
@@ -128,7 +148,7 @@ fn primitive_type<'a>(input: &mut &'a [u8]) -> ModalResult<Type<'a>, InputError<
 
 /// Parse a field in a struct or parameter list.
 fn field<'a>(input: &mut &'a [u8]) -> ModalResult<Field<'a>, InputError<&'a [u8]>> {
-    let comments = parse_preceding_comments(input)?;
+    let comments = parse_preceding_comments(input, CommentPolicy::Attach)?;
 
     let name = field_name(input)?;
     ws(input)?;
@@ -165,12 +185,13 @@ fn enum_type<'a>(input: &mut &'a [u8]) -> ModalResult<Type<'a>, InputError<&'a [
 }
 
 /// Parse an inline type (struct or enum).
-/// Determines if it's a struct by looking for ':' character.
+/// Determines if it's a struct by looking for ':' character. An empty `()` is a struct since enums
+/// need at least one variant.
 fn inline_type<'a>(input: &mut &'a [u8]) -> ModalResult<Type<'a>, InputError<&'a [u8]>> {
     // Look ahead to see if this contains a colon (indicating struct)
     if let Some(pos) = input.iter().position(|&b| b == b')') {
         let content = &input[1..pos]; // Skip opening paren
-        if content.contains(&b':') {
+        if content.contains(&b':') || content.iter().all(u8::is_ascii_whitespace) {
             struct_type(input)
         } else {
             enum_type(input)
@@ -237,9 +258,11 @@ fn interface_name<'a>(input: &mut &'a [u8]) -> ModalResult<&'a str, InputError<&
         found_dot = true;
         pos += 1; // skip dot
 
-        // Must have at least one alphanumeric after dot
+        // Must have at least one alphanumeric after dot. Empty segments and trailing dots are
+        // rejected outright, rather than leaving the rest of the name to the next parser.
         if pos >= input.len() || !input[pos].is_ascii_alphanumeric() {
-            break;
+            *input = &start[pos - 1..];
+            return Err(ErrMode::Cut(ParserError::from_input(input)));
         }
         pos += 1;
 
@@ -260,7 +283,10 @@ fn interface_name<'a>(input: &mut &'a [u8]) -> ModalResult<&'a str, InputError<&
 }
 
 /// Parse a parameter list: (param1: type1, param2: type2).
+///
+/// The spans of the parameters are recorded as offsets into `source`.
 fn parameter_list<'a>(
+    source: &'a [u8],
     input: &mut &'a [u8],
 ) -> ModalResult<Vec<Parameter<'a>>, InputError<&'a [u8]>> {
     literal("(").parse_next(input)?;
@@ -278,8 +304,10 @@ fn parameter_list<'a>(
 
     // Parse first parameter with any preceding comments
     loop {
+        let start = input.offset_from(&source);
+
         // Parse any preceding comments for this parameter
-        let comments = parse_preceding_comments(input)?;
+        let comments = parse_preceding_comments(input, CommentPolicy::Attach)?;
 
         // Parse the parameter itself (field name and type)
         let name = field_name(input)?;
@@ -288,7 +316,8 @@ fn parameter_list<'a>(
         ws(input)?;
         let ty = varlink_type(input)?;
 
-        params.push(Parameter::new_owned(name, ty, comments));
+        let end = input.offset_from(&source);
+        params.push(Parameter::new_owned(name, ty, comments).set_span((start, end)));
 
         whitespace_only(input)?;
 
@@ -298,6 +327,14 @@ fn parameter_list<'a>(
             .is_ok()
         {
             whitespace_only(input)?;
+
+            // Allow a trailing comma.
+            if literal::<_, _, InputError<&'a [u8]>>(")")
+                .parse_next(input)
+                .is_ok()
+            {
+                break;
+            }
             // Continue to next parameter
         } else if literal::<_, _, InputError<&'a [u8]>>(")")
             .parse_next(input)
@@ -314,43 +351,54 @@ fn parameter_list<'a>(
 }
 
 /// Parse a method definition: method Name(inputs) -> (outputs).
-fn method_def<'a>(input: &mut &'a [u8]) -> ModalResult<Method<'a>, InputError<&'a [u8]>> {
-    let comments = parse_preceding_comments(input)?;
+fn method_def<'a>(
+    source: &'a [u8],
+    comment_policy: CommentPolicy,
+    input: &mut &'a [u8],
+) -> ModalResult<Method<'a>, InputError<&'a [u8]>> {
+    let comments = parse_preceding_comments(input, comment_policy)?;
 
     literal("method").parse_next(input)?;
     take_while(1.., |c: u8| c.is_ascii_whitespace()).parse_next(input)?;
     let name = type_name(input)?;
     ws(input)?;
-    let input_params = parameter_list(input)?;
+    let input_params = parameter_list(source, input)?;
     ws(input)?;
     literal("->").parse_next(input)?;
     ws(input)?;
-    let output_params = parameter_list(input)?;
+    let output_params = parameter_list(source, input)?;
+    let trailing_comment = parse_trailing_comment(input)?;
 
-    Ok(Method::new_owned(
-        name,
-        input_params,
-        output_params,
-        comments,
-    ))
+    Ok(
+        Method::new_owned(name, input_params, output_params, comments)
+            .set_trailing_comment(trailing_comment),
+    )
 }
 
 /// Parse an error definition: error Name (fields).
-fn error_def<'a>(input: &mut &'a [u8]) -> ModalResult<Error<'a>, InputError<&'a [u8]>> {
-    let comments = parse_preceding_comments(input)?;
+fn error_def<'a>(
+    source: &'a [u8],
+    comment_policy: CommentPolicy,
+    input: &mut &'a [u8],
+) -> ModalResult<Error<'a>, InputError<&'a [u8]>> {
+    let comments = parse_preceding_comments(input, comment_policy)?;
 
     literal("error").parse_next(input)?;
     take_while(1.., |c: u8| c.is_ascii_whitespace()).parse_next(input)?;
     let name = type_name(input)?;
     ws(input)?;
-    let params = parameter_list(input)?;
+    let params = parameter_list(source, input)?;
 
     Ok(Error::new_owned(name, params, comments))
 }
 
 /// Parse a type definition: type Name <definition>.
-fn type_def<'a>(input: &mut &'a [u8]) -> ModalResult<CustomType<'a>, InputError<&'a [u8]>> {
-    let comments = parse_preceding_comments(input)?;
+fn type_def<'a>(
+    source: &'a [u8],
+    comment_policy: CommentPolicy,
+    input: &mut &'a [u8],
+) -> ModalResult<CustomType<'a>, InputError<&'a [u8]>> {
+    let comments = parse_preceding_comments(input, comment_policy)?;
 
     literal("type").parse_next(input)?;
     take_while(1.., |c: u8| c.is_ascii_whitespace()).parse_next(input)?;
@@ -376,8 +424,10 @@ fn type_def<'a>(input: &mut &'a [u8]) -> ModalResult<CustomType<'a>, InputError<
 
     // Parse fields with any preceding comments
     loop {
+        let start = input.offset_from(&source);
+
         // Parse any preceding comments for this field
-        let field_comments = parse_preceding_comments(input)?;
+        let field_comments = parse_preceding_comments(input, CommentPolicy::Attach)?;
 
         // Parse the field itself
         let field_name = field_name(input)?;
@@ -390,7 +440,8 @@ fn type_def<'a>(input: &mut &'a [u8]) -> ModalResult<CustomType<'a>, InputError<
         {
             whitespace_only(input)?;
             let ty = varlink_type(input)?;
-            fields.push(Field::new_owned(field_name, ty, field_comments));
+            let end = input.offset_from(&source);
+            fields.push(Field::new_owned(field_name, ty, field_comments).set_span((start, end)));
             has_typed_fields = true;
         } else {
             // This is an enum-like field without type - collect as variant with comments
@@ -406,6 +457,14 @@ fn type_def<'a>(input: &mut &'a [u8]) -> ModalResult<CustomType<'a>, InputError<
             .is_ok()
         {
             whitespace_only(input)?;
+
+            // Allow a trailing comma.
+            if literal::<_, _, InputError<&'a [u8]>>(")")
+                .parse_next(input)
+                .is_ok()
+            {
+                break;
+            }
             // Continue to next field
         } else if literal::<_, _, InputError<&'a [u8]>>(")")
             .parse_next(input)
@@ -440,6 +499,7 @@ fn type_def<'a>(input: &mut &'a [u8]) -> ModalResult<CustomType<'a>, InputError<
 /// Helper function to parse any preceding comments.
 fn parse_preceding_comments<'a>(
     input: &mut &'a [u8],
+    policy: CommentPolicy,
 ) -> ModalResult<Vec<Comment<'a>>, InputError<&'a [u8]>> {
     let mut comments = Vec::new();
     while !input.is_empty() {
@@ -452,7 +512,15 @@ fn parse_preceding_comments<'a>(
 
         if let Ok(comment) = comment_def(input) {
             comments.push(comment);
+            let before_ws = *input;
             whitespace_only(input)?;
+            let consumed = &before_ws[..before_ws.len() - input.len()];
+            if policy == CommentPolicy::Detach
+                && consumed.iter().filter(|&&c| c == b'\n').count() >= 2
+            {
+                // A blank line separates the comments so far from what follows.
+                comments.clear();
+            }
         } else {
             // Not a comment, restore position
             *input = checkpoint;
@@ -462,6 +530,23 @@ fn parse_preceding_comments<'a>(
     Ok(comments)
 }
 
+/// Helper function to parse a comment following a member on the same line.
+fn parse_trailing_comment<'a>(
+    input: &mut &'a [u8],
+) -> ModalResult<Option<Comment<'a>>, InputError<&'a [u8]>> {
+    let checkpoint = *input;
+    take_while(0.., |c: u8| c == b' ' || c == b'\t').parse_next(input)?;
+
+    match comment_def(input) {
+        Ok(comment) => Ok(Some(comment)),
+        Err(_) => {
+            // Not a comment, restore position
+            *input = checkpoint;
+            Ok(None)
+        }
+    }
+}
+
 fn comment_def<'a>(input: &mut &'a [u8]) -> ModalResult<Comment<'a>, InputError<&'a [u8]>> {
     literal("#").parse_next(input)?;
 
@@ -478,13 +563,18 @@ fn comment_def<'a>(input: &mut &'a [u8]) -> ModalResult<Comment<'a>, InputError<
 }
 
 /// Parse an interface definition.
-fn interface_def<'a>(input: &mut &'a [u8]) -> ModalResult<Interface<'a>, InputError<&'a [u8]>> {
-    let comments = parse_preceding_comments(input)?;
+///
+/// The spans of the members are recorded as offsets into `source`, which `input` is a part of.
+fn interface_def<'a>(
+    source: &'a [u8],
+    comment_policy: CommentPolicy,
+    input: &mut &'a [u8],
+) -> ModalResult<Interface<'a>, InputError<&'a [u8]>> {
+    let comments = parse_preceding_comments(input, comment_policy)?;
 
     literal("interface").parse_next(input)?;
     take_while(1.., |c: u8| c.is_ascii_whitespace()).parse_next(input)?;
     let name = interface_name(input)?;
-    whitespace_only(input)?;
 
     // Parse members separated by whitespace/newlines
     let mut methods = Vec::new();
@@ -492,7 +582,11 @@ fn interface_def<'a>(input: &mut &'a [u8]) -> ModalResult<Interface<'a>, InputEr
     let mut errors = Vec::new();
 
     while !input.is_empty() {
+        // Keep track of blank lines before members, so the grouping can be preserved.
+        let before_ws = *input;
         whitespace_only(input)?;
+        let consumed = &before_ws[..before_ws.len() - input.len()];
+        let preceded_by_blank = consumed.iter().filter(|&&c| c == b'\n').count() >= 2;
 
         if input.is_empty() {
             break;
@@ -504,17 +598,34 @@ fn interface_def<'a>(input: &mut &'a [u8]) -> ModalResult<Interface<'a>, InputEr
             Error(Error<'a>),
         }
 
+        let start = input.offset_from(&source);
         let result = alt((
-            type_def.map(ParsedMember::Custom),
-            method_def.map(ParsedMember::Method),
-            error_def.map(ParsedMember::Error),
+            (|input: &mut &'a [u8]| type_def(source, comment_policy, input))
+                .map(ParsedMember::Custom),
+            (|input: &mut &'a [u8]| method_def(source, comment_policy, input))
+                .map(ParsedMember::Method),
+            (|input: &mut &'a [u8]| error_def(source, comment_policy, input))
+                .map(ParsedMember::Error),
         ))
         .parse_next(input);
+        let span = (start, input.offset_from(&source));
 
         match result {
-            Ok(ParsedMember::Custom(custom_type)) => custom_types.push(custom_type),
-            Ok(ParsedMember::Method(method)) => methods.push(method),
-            Ok(ParsedMember::Error(error)) => errors.push(error),
+            Ok(ParsedMember::Custom(custom_type)) => custom_types.push(
+                custom_type
+                    .set_preceded_by_blank(preceded_by_blank)
+                    .set_span(span),
+            ),
+            Ok(ParsedMember::Method(method)) => methods.push(
+                method
+                    .set_preceded_by_blank(preceded_by_blank)
+                    .set_span(span),
+            ),
+            Ok(ParsedMember::Error(error)) => errors.push(
+                error
+                    .set_preceded_by_blank(preceded_by_blank)
+                    .set_span(span),
+            ),
             Err(_) => break,
         }
     }
@@ -530,7 +641,46 @@ fn interface_def<'a>(input: &mut &'a [u8]) -> ModalResult<Interface<'a>, InputEr
 
 /// Parse an interface from a string.
 pub(super) fn parse_interface(input: &str) -> Result<Interface<'_>, crate::Error> {
-    parse_from_str(input, interface_def)
+    parse_interface_with_comment_policy(input, CommentPolicy::Attach)
+}
+
+/// Parse an interface from a string, treating detached comments according to `comment_policy`.
+pub(super) fn parse_interface_with_comment_policy(
+    input: &str,
+    comment_policy: CommentPolicy,
+) -> Result<Interface<'_>, crate::Error> {
+    let interface = parse_from_str(input, |i| {
+        interface_def(input.as_bytes(), comment_policy, i)
+    })?;
+    check_duplicate_members(&interface)?;
+
+    Ok(interface)
+}
+
+/// Ensure that no two members of `interface` have the same name.
+///
+/// Types, methods and errors all share the same namespace. The error reports the offset of the
+/// second definition.
+fn check_duplicate_members(interface: &Interface<'_>) -> Result<(), crate::Error> {
+    let mut members: Vec<_> = interface
+        .custom_types()
+        .map(|t| (t.name(), t.span()))
+        .chain(interface.methods().map(|m| (m.name(), m.span())))
+        .chain(interface.errors().map(|e| (e.name(), e.span())))
+        .collect();
+    members.sort_by_key(|(_, span)| *span);
+
+    let mut names = std::collections::HashSet::new();
+    for (name, span) in members {
+        if !names.insert(name) {
+            let offset = span.map_or(0, |(start, _)| start);
+            return Err(crate::Error::IdlParse(format!(
+                "Duplicate member name `{name}` at offset {offset}"
+            )));
+        }
+    }
+
+    Ok(())
 }
 
 /// Helper function to parse from string using byte-based parsers.
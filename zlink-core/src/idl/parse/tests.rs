@@ -126,6 +126,28 @@ fn parse_interface_name() {
     // Invalid: starts with number
     let mut input_mut = b"1example.test".as_slice();
     assert!(interface_name(&mut input_mut).is_err());
+
+    // Invalid: trailing dot
+    let mut input_mut = b"org.example.".as_slice();
+    assert!(matches!(
+        interface_name(&mut input_mut),
+        Err(ErrMode::Cut(_))
+    ));
+
+    // Invalid: empty segment
+    let mut input_mut = b"org..example".as_slice();
+    assert!(matches!(
+        interface_name(&mut input_mut),
+        Err(ErrMode::Cut(_))
+    ));
+
+    for name in ["org.example.", "org..example"] {
+        let idl = format!("interface {name}\nmethod Ping() -> ()");
+        assert!(matches!(
+            parse_interface(&idl),
+            Err(crate::Error::IdlParse(_))
+        ));
+    }
 }
 
 #[test]
@@ -257,6 +279,15 @@ fn test_parse_field() {
     assert_eq!(field.ty(), &Type::Array(TypeRef::new(&Type::Int)));
 }
 
+#[test]
+fn parse_non_ascii_names() {
+    // The Varlink grammar only allows ASCII letters and digits in names.
+    assert!(parse_field("naïve: string").is_err());
+    assert!(parse_field("名前: string").is_err());
+    assert!(parse_custom_type("type Café (name: string)").is_err());
+    assert!(parse_method("method Get(straße: string) -> ()").is_err());
+}
+
 #[test]
 fn parse_field_with_comments() {
     let input = r#"# Field comment
@@ -311,6 +342,35 @@ error NotFound(id: int)
     assert_eq!(interface.errors().count(), 1);
 }
 
+#[test]
+fn detached_comments() {
+    let input = r#"
+# The interface.
+interface org.example.test
+
+# A free-floating comment.
+
+# Ping the service.
+method Ping() -> ()
+
+# Another free-floating comment.
+
+method Pong() -> ()
+"#;
+
+    let interface = parse_interface(input).unwrap();
+    let methods: Vec<_> = interface.methods().collect();
+    assert_eq!(methods[0].comments().count(), 2);
+    assert_eq!(methods[1].comments().count(), 1);
+
+    let interface = Interface::parse_with_comment_policy(input, CommentPolicy::Detach).unwrap();
+    assert_eq!(interface.comments().count(), 1);
+    let methods: Vec<_> = interface.methods().collect();
+    let comments: Vec<_> = methods[0].comments().map(|c| c.text()).collect();
+    assert_eq!(comments, ["Ping the service."]);
+    assert_eq!(methods[1].comments().count(), 0);
+}
+
 #[test]
 fn parse_error_messages() {
     // Test with invalid syntax
@@ -776,6 +836,204 @@ method AnotherMethod() -> ()
     assert_eq!(comments[0].text(), "No space after hash");
 }
 
+#[test]
+fn blank_line_grouping_preserved() {
+    let input = "interface org.example.grouping
+
+type Person (name: string, age: int)
+type Pet (name: string)
+
+# Person methods.
+method GetPerson(id: int) -> (person: Person)
+method SetPerson(person: Person) -> ()
+
+method GetPet(id: int) -> (pet: Pet)
+
+error NotFound (id: int)
+error PermissionDenied ()";
+
+    let interface = parse_interface(input).unwrap();
+
+    let methods: Vec<_> = interface.methods().collect();
+    assert!(methods[0].preceded_by_blank());
+    assert!(!methods[1].preceded_by_blank());
+    assert!(methods[2].preceded_by_blank());
+    let types: Vec<_> = interface.custom_types().collect();
+    assert!(types[0].preceded_by_blank());
+    assert!(!types[1].preceded_by_blank());
+
+    // Formatting reproduces the original grouping.
+    let mut formatted = String::new();
+    interface.format(&mut formatted).unwrap();
+    assert_eq!(formatted, input);
+
+    // The grouping is not considered for equality.
+    assert_eq!(parse_interface(&formatted).unwrap(), interface);
+}
+
+#[test]
+fn method_trailing_comment() {
+    let input = "interface org.example.trailing
+
+# Does foo.
+method Foo() -> () # Returns nothing.
+method Bar(x: int) -> (y: int)
+method Baz() -> ()";
+
+    let interface = parse_interface(input).unwrap();
+    let methods: Vec<_> = interface.methods().collect();
+
+    let foo_comments: Vec<_> = methods[0].comments().map(|c| c.text()).collect();
+    assert_eq!(foo_comments, ["Does foo."]);
+    let foo_trailing: Vec<_> = methods[0].trailing_comments().map(|c| c.text()).collect();
+    assert_eq!(foo_trailing, ["Returns nothing."]);
+
+    // The trailing comment is not attached to the next member.
+    assert_eq!(methods[1].comments().count(), 0);
+    assert_eq!(methods[1].trailing_comments().count(), 0);
+
+    // Displaying keeps the comment on the same line.
+    assert_eq!(
+        methods[0].to_string(),
+        "# Does foo.\nmethod Foo() -> () # Returns nothing."
+    );
+}
+
+#[test]
+fn display_members_with_field_comments() {
+    let method = parse_method(
+        "# Get a person.\nmethod Get(\n# The name.\nname: string, age: int) -> (found: bool)",
+    )
+    .unwrap();
+    let displayed = method.to_string();
+    assert_eq!(
+        displayed,
+        "# Get a person.\nmethod Get(\n\t# The name.\n\tname: string,\n\tage: int\n) -> \
+         (found: bool)"
+    );
+    assert_eq!(parse_method(&displayed).unwrap(), method);
+
+    let error = parse_error("error NotFound (\n# The name.\nname: string\n)").unwrap();
+    let displayed = error.to_string();
+    assert_eq!(
+        displayed,
+        "error NotFound (\n\t# The name.\n\tname: string\n)"
+    );
+    assert_eq!(parse_error(&displayed).unwrap(), error);
+
+    let custom_type =
+        parse_custom_type("# A person.\ntype Person (name: string, # Years.\nage: int)").unwrap();
+    let displayed = custom_type.to_string();
+    assert_eq!(
+        displayed,
+        "# A person.\ntype Person (\n\tname: string,\n\t# Years.\n\tage: int\n)"
+    );
+    assert_eq!(parse_custom_type(&displayed).unwrap(), custom_type);
+}
+
+#[test]
+fn trailing_commas() {
+    let method = parse_method("method Foo(a: int,) -> (b: int,)").unwrap();
+    assert_eq!(method.name(), "Foo");
+    assert_eq!(method.inputs().count(), 1);
+    assert_eq!(method.outputs().count(), 1);
+    assert_eq!(method.to_string(), "method Foo(a: int) -> (b: int)");
+
+    let custom = parse_custom_type("type T(x: int,)").unwrap();
+    match custom {
+        CustomType::Object(object) => {
+            assert_eq!(object.name(), "T");
+            assert_eq!(object.fields().count(), 1);
+        }
+        _ => panic!("Expected custom object"),
+    }
+
+    // Only a single trailing comma is allowed.
+    assert!(parse_method("method Foo(a: int,,) -> ()").is_err());
+    assert!(parse_custom_type("type T(x: int,,)").is_err());
+}
+
+#[test]
+fn nested_types_round_trip() {
+    for input in [
+        "[string][]?Foo",
+        "[]?[string]int",
+        "?[][string]?[]bool",
+        "[string][string][]float",
+        "[][]?object",
+        "?[string](a: []?int, b: [string](x, y))",
+        "[](a: ?[](b: [string]Foo), c: ())",
+        "[string]()",
+    ] {
+        let ty = parse_type(input).unwrap();
+        let displayed = ty.to_string();
+        assert_eq!(displayed, input);
+        assert_eq!(parse_type(&displayed).unwrap(), ty);
+    }
+
+    // An empty inline type is an empty struct, not an enum.
+    assert_eq!(parse_type("()").unwrap(), Type::Object(List::from(vec![])));
+}
+
+#[test]
+fn member_spans() {
+    let input = r#"
+interface org.example.spans
+
+# A person.
+type Person (name: string, age: int)
+
+# Get a person.
+method GetPerson(id: int) -> (person: Person)
+
+error NotFound (id: int)
+"#;
+    let interface = parse_interface(input).unwrap();
+    let text = |span: Option<(usize, usize)>| {
+        let (start, end) = span.unwrap();
+        &input[start..end]
+    };
+
+    let method = interface.methods().next().unwrap();
+    assert_eq!(
+        text(method.span()),
+        "# Get a person.\nmethod GetPerson(id: int) -> (person: Person)"
+    );
+    let params: Vec<_> = method.inputs().chain(method.outputs()).collect();
+    assert_eq!(text(params[0].span()), "id: int");
+    assert_eq!(text(params[1].span()), "person: Person");
+
+    let person = interface.custom_types().next().unwrap();
+    assert_eq!(
+        text(person.span()),
+        "# A person.\ntype Person (name: string, age: int)"
+    );
+    let fields: Vec<_> = person.as_object().unwrap().fields().collect();
+    assert_eq!(text(fields[1].span()), "age: int");
+
+    let error = interface.errors().next().unwrap();
+    assert_eq!(text(error.span()), "error NotFound (id: int)");
+
+    // Members that weren't parsed have no span.
+    assert_eq!(Method::new("Ping", &[], &[], &[]).span(), None);
+}
+
+#[test]
+fn duplicate_member_names() {
+    let input = "interface org.example.dup\nmethod Foo() -> ()\nmethod Foo(a: int) -> ()\n";
+    match parse_interface(input).unwrap_err() {
+        crate::Error::IdlParse(msg) => {
+            assert_eq!(msg, "Duplicate member name `Foo` at offset 45");
+            assert!(input[45..].starts_with("method Foo(a: int)"));
+        }
+        other => panic!("Expected IdlParse error, got: {:?}", other),
+    }
+
+    // Types, methods and errors share the same namespace.
+    let input = "interface org.example.dup\ntype Foo (a: int)\nerror Foo ()\n";
+    assert!(parse_interface(input).is_err());
+}
+
 /// Parse a Varlink type from a string.
 fn parse_type(input: &str) -> Result<Type<'_>, crate::Error> {
     parse_from_str(input, varlink_type)
@@ -783,17 +1041,23 @@ fn parse_type(input: &str) -> Result<Type<'_>, crate::Error> {
 
 /// Parse a method from a string.
 fn parse_method(input: &str) -> Result<Method<'_>, crate::Error> {
-    parse_from_str(input, method_def)
+    parse_from_str(input, |i| {
+        method_def(input.as_bytes(), CommentPolicy::Attach, i)
+    })
 }
 
 /// Parse an error from a string.
 fn parse_error(input: &str) -> Result<Error<'_>, crate::Error> {
-    parse_from_str(input, error_def)
+    parse_from_str(input, |i| {
+        error_def(input.as_bytes(), CommentPolicy::Attach, i)
+    })
 }
 
 /// Parse a custom type from a string.
 fn parse_custom_type(input: &str) -> Result<CustomType<'_>, crate::Error> {
-    parse_from_str(input, type_def)
+    parse_from_str(input, |i| {
+        type_def(input.as_bytes(), CommentPolicy::Attach, i)
+    })
 }
 
 /// Parse a field from a string.
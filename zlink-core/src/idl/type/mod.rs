@@ -3,6 +3,9 @@
 mod type_ref;
 pub use type_ref::TypeRef;
 
+mod visitor;
+pub use visitor::TypeVisitor;
+
 use core::fmt;
 
 use super::{EnumVariant, Field, List};
@@ -0,0 +1,228 @@
+//! Generic traversal of IDL types.
+
+use super::Type;
+use crate::idl::{EnumVariant, Field, List};
+
+/// A visitor of [`Type`] trees.
+///
+/// Use [`Type::accept`] to walk a type with a visitor. The visitor is notified about each type in
+/// the tree, parents before their children, while the recursion itself is taken care of by
+/// [`Type::accept`].
+///
+/// All methods have a default implementation that does nothing, so you only need to implement the
+/// ones you're interested in.
+pub trait TypeVisitor<'a> {
+    /// Visit a primitive type, i-e `bool`, `int`, `float`, `string` or `object`.
+    fn visit_primitive(&mut self, _ty: &Type<'a>) {}
+
+    /// Visit an optional type, with the given child type.
+    fn visit_optional(&mut self, _inner: &Type<'a>) {}
+
+    /// Visit an array type, with the given element type.
+    fn visit_array(&mut self, _element: &Type<'a>) {}
+
+    /// Visit a map type, with the given value type.
+    fn visit_map(&mut self, _value: &Type<'a>) {}
+
+    /// Visit a reference to the custom type with the given name.
+    fn visit_custom(&mut self, _name: &'a str) {}
+
+    /// Visit an inline enum type.
+    fn visit_enum(&mut self, _variants: &List<'a, EnumVariant<'a>>) {}
+
+    /// Visit an inline object type.
+    ///
+    /// Each of its fields is then visited through [`TypeVisitor::visit_field`], followed by its
+    /// type.
+    fn visit_object(&mut self, _fields: &List<'a, Field<'a>>) {}
+
+    /// Visit a field of an inline object type.
+    fn visit_field(&mut self, _field: &Field<'a>) {}
+}
+
+impl<'a> Type<'a> {
+    /// The maximum nesting depth of types visited by [`Type::accept`].
+    pub const MAX_DEPTH: usize = 64;
+
+    /// Walk this type and all the types it's composed of, with the given visitor.
+    ///
+    /// Custom type references are not resolved. Returns `false` if the type is nested deeper than
+    /// [`Type::MAX_DEPTH`], in which case the types beyond that depth are not visited.
+    pub fn accept<V>(&self, visitor: &mut V) -> bool
+    where
+        V: TypeVisitor<'a> + ?Sized,
+    {
+        self.accept_at(visitor, 0)
+    }
+
+    /// A measure of how complex this type is, e.g to sort types or flag overly complex ones.
+    ///
+    /// This is the nesting depth of the type plus the number of fields of all the inline objects
+    /// it's composed of. Primitive types and custom type references have a complexity of 1, since
+    /// custom type references are not resolved.
+    pub fn complexity(&self) -> usize {
+        struct FieldCounter(usize);
+
+        impl<'a> TypeVisitor<'a> for FieldCounter {
+            fn visit_field(&mut self, _field: &Field<'a>) {
+                self.0 += 1;
+            }
+        }
+
+        let mut counter = FieldCounter(0);
+        self.accept(&mut counter);
+
+        self.depth_at(0) + counter.0
+    }
+
+    fn depth_at(&self, depth: usize) -> usize {
+        if depth >= Self::MAX_DEPTH {
+            return 0;
+        }
+
+        match self {
+            Type::Optional(inner) | Type::Array(inner) | Type::Map(inner) => {
+                1 + inner.inner().depth_at(depth + 1)
+            }
+            Type::Object(fields) => {
+                1 + fields
+                    .iter()
+                    .map(|field| field.ty().depth_at(depth + 1))
+                    .max()
+                    .unwrap_or(0)
+            }
+            _ => 1,
+        }
+    }
+
+    fn accept_at<V>(&self, visitor: &mut V, depth: usize) -> bool
+    where
+        V: TypeVisitor<'a> + ?Sized,
+    {
+        if depth >= Self::MAX_DEPTH {
+            return false;
+        }
+
+        match self {
+            Type::Bool | Type::Int | Type::Float | Type::String | Type::ForeignObject => {
+                visitor.visit_primitive(self);
+
+                true
+            }
+            Type::Optional(inner) => {
+                visitor.visit_optional(inner.inner());
+
+                inner.inner().accept_at(visitor, depth + 1)
+            }
+            Type::Array(element) => {
+                visitor.visit_array(element.inner());
+
+                element.inner().accept_at(visitor, depth + 1)
+            }
+            Type::Map(value) => {
+                visitor.visit_map(value.inner());
+
+                value.inner().accept_at(visitor, depth + 1)
+            }
+            Type::Custom(name) => {
+                visitor.visit_custom(*name);
+
+                true
+            }
+            Type::Enum(variants) => {
+                visitor.visit_enum(variants);
+
+                true
+            }
+            Type::Object(fields) => {
+                visitor.visit_object(fields);
+
+                fields.iter().fold(true, |complete, field| {
+                    visitor.visit_field(field);
+
+                    field.ty().accept_at(visitor, depth + 1) && complete
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl::TypeRef;
+
+    #[derive(Default)]
+    struct CustomCounter {
+        count: usize,
+        fields: usize,
+    }
+
+    impl<'a> TypeVisitor<'a> for CustomCounter {
+        fn visit_custom(&mut self, _name: &'a str) {
+            self.count += 1;
+        }
+
+        fn visit_field(&mut self, _field: &Field<'a>) {
+            self.fields += 1;
+        }
+    }
+
+    #[test]
+    fn count_custom_references() {
+        let person = Type::Custom("Person");
+        let people = Type::Array(TypeRef::new(&person));
+        let address = Type::Custom("Address");
+        let optional_address = Type::Optional(TypeRef::new(&address));
+        let fields = [
+            &Field::new("people", &people, &[]),
+            &Field::new("address", &optional_address, &[]),
+            &Field::new("name", &Type::String, &[]),
+        ];
+        let object = Type::Object(List::from(&fields[..]));
+        let ty = Type::Map(TypeRef::new(&object));
+
+        let mut counter = CustomCounter::default();
+        assert!(ty.accept(&mut counter));
+        assert_eq!(counter.count, 2);
+        assert_eq!(counter.fields, 3);
+
+        let mut counter = CustomCounter::default();
+        assert!(Type::Int.accept(&mut counter));
+        assert_eq!(counter.count, 0);
+    }
+
+    #[test]
+    fn complexity() {
+        let fields = [
+            &Field::new("name", &Type::String, &[]),
+            &Field::new("age", &Type::Int, &[]),
+        ];
+        let flat = Type::Object(List::from(&fields[..]));
+        assert_eq!(Type::Int.complexity(), 1);
+        assert_eq!(flat.complexity(), 4);
+
+        let names = Type::Array(TypeRef::new(&Type::String));
+        let optional_names = Type::Optional(TypeRef::new(&names));
+        let inner_fields = [&Field::new("names", &optional_names, &[])];
+        let inner = Type::Object(List::from(&inner_fields[..]));
+        let outer_fields = [&Field::new("inner", &inner, &[])];
+        let outer = Type::Object(List::from(&outer_fields[..]));
+        let nested = Type::Map(TypeRef::new(&outer));
+        assert_eq!(nested.complexity(), 8);
+        assert!(nested.complexity() > flat.complexity());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn depth_limit() {
+        let mut ty = Type::Custom("Person");
+        for _ in 0..Type::MAX_DEPTH {
+            ty = Type::Array(TypeRef::new_owned(ty));
+        }
+
+        let mut counter = CustomCounter::default();
+        assert!(!ty.accept(&mut counter));
+        assert_eq!(counter.count, 0);
+    }
+}
@@ -0,0 +1,222 @@
+//! Validation of JSON values against Varlink IDL types.
+
+use serde_json::Value;
+
+use super::{CustomType, Field, Interface, Parameter, Type};
+
+/// Validates the `parameters` of a call or reply against the given parameter list.
+///
+/// Custom type references are resolved through `interface`. Absent `parameters` are treated as
+/// an empty object, and absent members as `null`, so that they're valid for optional parameters.
+///
+/// On mismatch, a description of the first mismatch found is returned.
+pub fn validate_parameters<'p>(
+    parameters: impl IntoIterator<Item = &'p Parameter<'p>>,
+    value: Option<&Value>,
+    interface: &Interface<'_>,
+) -> Result<(), String> {
+    let empty = serde_json::Map::new();
+    let object = match value {
+        None | Some(Value::Null) => &empty,
+        Some(Value::Object(object)) => object,
+        Some(_) => return Err("parameters: expected an object".into()),
+    };
+
+    validate_fields(parameters, object, interface, "")
+}
+
+/// Validates `value` against the given type.
+///
+/// Custom type references are resolved through `interface`. References to types not defined in
+/// `interface` are accepted as is.
+///
+/// On mismatch, a description of the first mismatch found is returned.
+pub fn validate_value(
+    ty: &Type<'_>,
+    value: &Value,
+    interface: &Interface<'_>,
+) -> Result<(), String> {
+    validate_at(ty, value, interface, "")
+}
+
+fn validate_at(
+    ty: &Type<'_>,
+    value: &Value,
+    interface: &Interface<'_>,
+    path: &str,
+) -> Result<(), String> {
+    let valid = match ty {
+        Type::Bool => value.is_boolean(),
+        Type::Int => value.is_i64() || value.is_u64(),
+        Type::Float => value.is_number(),
+        Type::String => value.is_string(),
+        Type::ForeignObject => value.is_object(),
+        Type::Optional(inner) => {
+            return match value {
+                Value::Null => Ok(()),
+                value => validate_at(inner, value, interface, path),
+            }
+        }
+        Type::Array(inner) => {
+            let Value::Array(elements) = value else {
+                return Err(mismatch(path, ty));
+            };
+            return elements.iter().enumerate().try_for_each(|(i, element)| {
+                validate_at(inner, element, interface, &format!("{path}[{i}]"))
+            });
+        }
+        Type::Map(inner) => {
+            let Value::Object(entries) = value else {
+                return Err(mismatch(path, ty));
+            };
+            return entries.iter().try_for_each(|(key, entry)| {
+                validate_at(inner, entry, interface, &format!("{path}[{key:?}]"))
+            });
+        }
+        Type::Custom(name) => {
+            let Some(custom) = interface.custom_types().find(|t| t.name() == *name) else {
+                return Ok(());
+            };
+            return match custom {
+                CustomType::Object(object) => {
+                    let Value::Object(members) = value else {
+                        return Err(mismatch(path, ty));
+                    };
+                    validate_fields(object.fields(), members, interface, path)
+                }
+                CustomType::Enum(custom_enum) => {
+                    let valid = value
+                        .as_str()
+                        .is_some_and(|variant| custom_enum.variants().any(|v| v.name() == variant));
+                    if valid {
+                        Ok(())
+                    } else {
+                        Err(mismatch(path, ty))
+                    }
+                }
+            };
+        }
+        Type::Enum(variants) => value
+            .as_str()
+            .is_some_and(|variant| variants.iter().any(|v| v.name() == variant)),
+        Type::Object(fields) => {
+            let Value::Object(members) = value else {
+                return Err(mismatch(path, ty));
+            };
+            return validate_fields(fields.iter(), members, interface, path);
+        }
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(mismatch(path, ty))
+    }
+}
+
+fn validate_fields<'f>(
+    fields: impl IntoIterator<Item = &'f Field<'f>>,
+    members: &serde_json::Map<String, Value>,
+    interface: &Interface<'_>,
+    path: &str,
+) -> Result<(), String> {
+    let mut known = 0;
+    for field in fields {
+        let field_path = if path.is_empty() {
+            field.name().to_string()
+        } else {
+            format!("{path}.{}", field.name())
+        };
+        let value = members.get(field.name());
+        if value.is_some() {
+            known += 1;
+        }
+        validate_at(
+            field.ty(),
+            value.unwrap_or(&Value::Null),
+            interface,
+            &field_path,
+        )?;
+    }
+
+    if known != members.len() {
+        let path = if path.is_empty() { "parameters" } else { path };
+        return Err(format!("{path}: unexpected members"));
+    }
+
+    Ok(())
+}
+
+fn mismatch(path: &str, ty: &Type<'_>) -> String {
+    let path = if path.is_empty() { "value" } else { path };
+
+    format!("{path}: expected `{ty}`")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::idl::{CustomObject, Method, TypeRef};
+    use serde_json::json;
+
+    #[test]
+    fn primitives() {
+        let interface = Interface::new("org.example.test", &[], &[], &[], &[]);
+
+        assert!(validate_value(&Type::Bool, &json!(true), &interface).is_ok());
+        assert!(validate_value(&Type::Int, &json!(42), &interface).is_ok());
+        assert!(validate_value(&Type::Int, &json!(4.2), &interface).is_err());
+        assert!(validate_value(&Type::Float, &json!(42), &interface).is_ok());
+        assert!(validate_value(&Type::String, &json!(42), &interface).is_err());
+
+        let int = Type::Int;
+        let optional = Type::Optional(TypeRef::new(&int));
+        assert!(validate_value(&optional, &json!(null), &interface).is_ok());
+        let array = Type::Array(TypeRef::new(&int));
+        assert_eq!(
+            validate_value(&array, &json!([1, "2"]), &interface),
+            Err("[1]: expected `int`".into())
+        );
+    }
+
+    #[test]
+    fn method_parameters() {
+        let string = Type::String;
+        let optional_string = Type::Optional(TypeRef::new(&string));
+        let person_fields = [
+            &Field::new("name", &Type::String, &[]),
+            &Field::new("email", &optional_string, &[]),
+        ];
+        let person = CustomType::from(CustomObject::new("Person", &person_fields, &[]));
+        let person_ty = Type::Custom("Person");
+        let inputs = [
+            &Parameter::new("id", &Type::Int, &[]),
+            &Parameter::new("person", &person_ty, &[]),
+        ];
+        let method = Method::new("SetPerson", &inputs, &[], &[]);
+        let methods = [&method];
+        let custom_types = [&person];
+        let interface = Interface::new("org.example.test", &methods, &custom_types, &[], &[]);
+
+        let valid = json!({ "id": 1, "person": { "name": "Jane" } });
+        assert!(validate_parameters(method.inputs(), Some(&valid), &interface).is_ok());
+
+        let wrong_type = json!({ "id": "1", "person": { "name": "Jane" } });
+        assert_eq!(
+            validate_parameters(method.inputs(), Some(&wrong_type), &interface),
+            Err("id: expected `int`".into())
+        );
+
+        let nested = json!({ "id": 1, "person": { "name": 42 } });
+        assert_eq!(
+            validate_parameters(method.inputs(), Some(&nested), &interface),
+            Err("person.name: expected `string`".into())
+        );
+
+        let missing = json!({ "person": { "name": "Jane" } });
+        assert!(validate_parameters(method.inputs(), Some(&missing), &interface).is_err());
+
+        let extra = json!({ "id": 1, "person": { "name": "Jane" }, "age": 42 });
+        assert!(validate_parameters(method.inputs(), Some(&extra), &interface).is_err());
+    }
+}
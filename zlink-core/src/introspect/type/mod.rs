@@ -36,6 +36,14 @@ use super::super::idl;
 ///
 /// The derive macro automatically generates the appropriate `Type` implementation based on
 /// your struct's fields.
+///
+/// # Lossy mapping
+///
+/// The Varlink type system is much simpler than Rust's, so the mapping is lossy. Most notably,
+/// Varlink has a single `int` type, so all the integer types (signed or unsigned, of any size) map
+/// to [`idl::Type::Int`], just like both `f32` and `f64` map to [`idl::Type::Float`]. Hence, the
+/// original Rust type can't be recovered from the IDL, and code generated from it will use `i64`
+/// and `f64`.
 pub trait Type {
     /// The type information.
     const TYPE: &'static idl::Type<'static>;
@@ -17,7 +17,8 @@ macro_rules! impl_type {
 // Boolean type.
 impl_type!(bool => idl::Type::Bool);
 
-// Integer types - all map to 64-bit signed integer in Varlink.
+// Integer types - all map to 64-bit signed integer in Varlink. Note that this means the
+// signedness and the size of the original type are lost.
 impl_type!(i8, i16, i32, i64 => idl::Type::Int);
 impl_type!(u8, u16, u32, u64 => idl::Type::Int);
 impl_type!(isize, usize => idl::Type::Int);
@@ -112,6 +112,14 @@ fn additional_numeric_types() {
     assert_eq!(*usize::TYPE, idl::Type::Int);
 }
 
+#[test]
+fn integer_signedness_is_lost() {
+    // Varlink only has one integer type.
+    assert_eq!(u32::TYPE, i64::TYPE);
+    assert_eq!(*u32::TYPE, idl::Type::Int);
+    assert_eq!(*i64::TYPE, idl::Type::Int);
+}
+
 #[test]
 fn char_type() {
     assert_eq!(*char::TYPE, idl::Type::String);
@@ -20,16 +20,26 @@ pub mod log;
 
 pub mod connection;
 pub use connection::Connection;
+#[cfg(feature = "embedded-io-async")]
+mod embedded_io;
+#[cfg(feature = "embedded-io-async")]
+pub use embedded_io::{EmbeddedIoReadHalf, EmbeddedIoSocket, EmbeddedIoWriteHalf};
 mod error;
 pub use error::{Error, Result};
 mod server;
+#[cfg(feature = "std")]
+pub use server::listener::MultiListener;
 pub use server::{
-    listener::Listener,
+    interceptor::{self, Interceptor},
+    listener::{Listener, LocalAddr},
+    serve_connection,
     service::{self, Service},
-    Server,
+    Scheduling, Server,
 };
 mod call;
 pub use call::Call;
+#[cfg(feature = "std")]
+pub use call::RawMethod;
 pub mod reply;
 pub use reply::Reply;
 #[cfg(feature = "idl")]
@@ -45,3 +55,5 @@ pub use zlink_macros::ReplyError;
 
 #[doc(hidden)]
 pub mod test_utils;
+#[cfg(any(feature = "testing", all(test, feature = "std")))]
+pub mod testing;
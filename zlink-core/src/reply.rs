@@ -3,12 +3,16 @@
 use serde::{Deserialize, Serialize};
 
 /// A successful method call reply.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Reply<Params> {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) parameters: Option<Params>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(super) continues: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) id: Option<u64>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub(super) upgrade: bool,
 }
 
 impl<Params> Reply<Params> {
@@ -17,15 +21,43 @@ impl<Params> Reply<Params> {
         Self {
             parameters,
             continues: None,
+            id: None,
+            upgrade: false,
         }
     }
 
+    /// Create a new reply without any parameters.
+    ///
+    /// This is the reply to send for methods that don't return anything. It's serialized without
+    /// the `parameters` field, i-e as `{}` (besides the flags that are set).
+    pub fn empty() -> Self {
+        Self::new(None)
+    }
+
     /// Set the continues flag.
     pub fn set_continues(mut self, continues: Option<bool>) -> Self {
         self.continues = continues;
         self
     }
 
+    /// Set the correlation ID.
+    ///
+    /// This should be the ID of the method call this is a reply to. **Note**: This is a
+    /// non-standard extension to the Varlink protocol.
+    pub fn set_id(mut self, id: Option<u64>) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Set the upgrade flag.
+    ///
+    /// Services set this in the reply to a call with the upgrade flag set, to acknowledge that the
+    /// connection is switching to a different protocol after this reply.
+    pub fn set_upgrade(mut self, upgrade: bool) -> Self {
+        self.upgrade = upgrade;
+        self
+    }
+
     /// The parameters of the reply.
     pub fn parameters(&self) -> Option<&Params> {
         self.parameters.as_ref()
@@ -40,6 +72,20 @@ impl<Params> Reply<Params> {
     pub fn continues(&self) -> Option<bool> {
         self.continues
     }
+
+    /// The correlation ID of the reply, if any.
+    pub fn id(&self) -> Option<u64> {
+        self.id
+    }
+
+    /// If the service accepted the upgrade of the connection.
+    pub fn upgrade(&self) -> bool {
+        self.upgrade
+    }
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
 }
 
 impl<Params> From<Params> for Reply<Params> {
@@ -50,3 +96,129 @@ impl<Params> From<Params> for Reply<Params> {
 
 /// A reply result.
 pub type Result<Params, Error> = core::result::Result<Reply<Params>, Error>;
+
+/// An error reply, built from the error name and its parameters.
+///
+/// This serializes to the `{"error": ..., "parameters": ...}` object defined by the Varlink
+/// specification, so it can be passed to [`crate::Connection::send_error`]. It's useful for
+/// dynamic services and hand-written handlers, where deriving [`crate::ReplyError`] is not an
+/// option.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Error<'e, Params> {
+    error: &'e str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parameters: Option<Params>,
+}
+
+impl<'e, Params> Error<'e, Params> {
+    /// Create a new error reply.
+    ///
+    /// `name` is the fully-qualified name of the error, e.g. `org.example.NotFound`.
+    pub fn new(name: &'e str, parameters: Params) -> Self {
+        Self {
+            error: name,
+            parameters: Some(parameters),
+        }
+    }
+
+    /// Create a new error reply without any parameters.
+    pub fn empty(name: &'e str) -> Self {
+        Self {
+            error: name,
+            parameters: None,
+        }
+    }
+
+    /// The fully-qualified name of the error.
+    pub fn name(&self) -> &'e str {
+        self.error
+    }
+
+    /// The parameters of the error.
+    pub fn parameters(&self) -> Option<&Params> {
+        self.parameters.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn clone_reply() {
+        use super::Reply;
+
+        #[derive(Debug, Clone, PartialEq)]
+        struct Params {
+            value: u32,
+        }
+
+        let reply = Reply::new(Some(Params { value: 42 }))
+            .set_continues(Some(true))
+            .set_id(Some(1));
+        let cloned = reply.clone();
+        assert_eq!(cloned, reply);
+        assert_eq!(cloned.parameters().unwrap().value, 42);
+        assert_eq!(cloned.continues(), Some(true));
+        assert_eq!(cloned.id(), Some(1));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn empty_reply() {
+        use super::Reply;
+
+        let reply = Reply::<()>::empty();
+        assert_eq!(serde_json::to_string(&reply).unwrap(), "{}");
+
+        let reply = Reply::<()>::empty().set_continues(Some(false));
+        assert_eq!(
+            serde_json::to_string(&reply).unwrap(),
+            r#"{"continues":false}"#
+        );
+
+        let reply: Reply<()> = serde_json::from_str("{}").unwrap();
+        assert!(reply.parameters().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn upgrade_reply() {
+        use super::Reply;
+
+        let reply = Reply::<()>::empty().set_upgrade(true);
+        assert_eq!(
+            serde_json::to_string(&reply).unwrap(),
+            r#"{"upgrade":true}"#
+        );
+
+        let reply: Reply<()> = serde_json::from_str(r#"{"upgrade":true}"#).unwrap();
+        assert!(reply.upgrade());
+        let reply: Reply<()> = serde_json::from_str("{}").unwrap();
+        assert!(!reply.upgrade());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn error_reply() {
+        use super::Error;
+        use serde::Serialize;
+
+        #[derive(Debug, Serialize)]
+        struct NotFound {
+            id: u32,
+        }
+
+        let error = Error::new("org.example.NotFound", NotFound { id: 42 });
+        assert_eq!(error.name(), "org.example.NotFound");
+        assert_eq!(error.parameters().unwrap().id, 42);
+        assert_eq!(
+            serde_json::to_string(&error).unwrap(),
+            r#"{"error":"org.example.NotFound","parameters":{"id":42}}"#
+        );
+
+        let error = Error::<()>::empty("org.example.Busy");
+        assert_eq!(
+            serde_json::to_string(&error).unwrap(),
+            r#"{"error":"org.example.Busy"}"#
+        );
+    }
+}
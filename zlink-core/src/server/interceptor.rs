@@ -0,0 +1,41 @@
+//! Reply interception API.
+
+use crate::{varlink_service, Reply};
+
+use super::service::Service;
+
+/// Intercepts all replies sent by a [`crate::Server`].
+///
+/// An interceptor gets to inspect and modify each reply right before it is sent to the client,
+/// e.g to set a correlation ID through [`Reply::set_id`] or to gather metrics, without touching the
+/// service itself. Use [`crate::Server::with_interceptor`] to set one.
+///
+/// All methods have a default implementation that does nothing, so you only need to implement the
+/// ones you're interested in. The `()` type implements this trait, as an interceptor that does
+/// nothing.
+pub trait Interceptor<Svc: Service> {
+    /// Intercept a single reply, i-e a reply to a method call without the `more` flag.
+    fn reply<'ser>(&mut self, _reply: &mut Reply<Svc::ReplyParams<'ser>>, _connection_id: usize)
+    where
+        Svc: 'ser,
+    {
+    }
+
+    /// Intercept a reply from a multi-reply stream.
+    fn stream_reply(&mut self, _reply: &mut Reply<Svc::ReplyStreamParams>, _connection_id: usize) {}
+
+    /// Intercept an error reply.
+    fn error<'ser>(&mut self, _error: &mut Svc::ReplyError<'ser>, _connection_id: usize)
+    where
+        Svc: 'ser,
+    {
+    }
+
+    /// Intercept an error reply from the server itself, rather than from the service.
+    ///
+    /// This is the [`varlink_service::Error::MethodNotFound`] error sent for calls to methods not
+    /// in [`Service::INTERFACE`], which never reach the service.
+    fn server_error(&mut self, _error: &mut varlink_service::Error, _connection_id: usize) {}
+}
+
+impl<Svc: Service> Interceptor<Svc> for () {}
@@ -1,4 +1,6 @@
 use core::future::Future;
+#[cfg(feature = "std")]
+use core::{pin::Pin, task::Poll};
 
 use crate::{connection::Socket, Connection, Result};
 
@@ -10,3 +12,90 @@ pub trait Listener: core::fmt::Debug {
     /// Accept a new connection.
     fn accept(&mut self) -> impl Future<Output = Result<Connection<Self::Socket>>>;
 }
+
+/// A [`Listener`] that is bound to a local address.
+pub trait LocalAddr: Listener {
+    /// The type of the address the listener is bound to.
+    type Address: core::fmt::Debug;
+
+    /// The local address this listener is bound to.
+    ///
+    /// This is useful to find out the actual address after binding to an address that is chosen
+    /// by the system, e.g. port 0 for TCP.
+    fn local_addr(&self) -> Result<Self::Address>;
+}
+
+/// A listener that accepts connections from multiple listeners.
+///
+/// This allows a single [`crate::Server`] to serve connections from multiple listeners, e.g. to
+/// expose a service on multiple addresses. The listeners are polled in a round-robin fashion, so
+/// that a busy listener can't starve the others.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct MultiListener<L> {
+    listeners: std::vec::Vec<L>,
+    // The index of the listener to poll first on the next accept.
+    next: usize,
+}
+
+#[cfg(feature = "std")]
+impl<L: Listener> MultiListener<L> {
+    /// Create a new listener, accepting connections from all of `listeners`.
+    pub fn new(listeners: impl IntoIterator<Item = L>) -> Self {
+        Self {
+            listeners: listeners.into_iter().collect(),
+            next: 0,
+        }
+    }
+
+    /// Add a listener to accept connections from.
+    pub fn add_listener(&mut self, listener: L) {
+        self.listeners.push(listener);
+    }
+
+    /// The listeners.
+    pub fn listeners(&self) -> &[L] {
+        &self.listeners
+    }
+}
+
+#[cfg(feature = "std")]
+impl<L: Listener> Listener for MultiListener<L> {
+    type Socket = L::Socket;
+
+    async fn accept(&mut self) -> Result<Connection<Self::Socket>> {
+        let start = self.next;
+        let mut accepts: std::vec::Vec<Pin<std::boxed::Box<_>>> = self
+            .listeners
+            .iter_mut()
+            .map(|listener| std::boxed::Box::pin(listener.accept()))
+            .collect();
+        let num_listeners = accepts.len();
+
+        // With no listeners, this never resolves.
+        let (idx, conn) = core::future::poll_fn(|cx| {
+            for i in 0..num_listeners {
+                let idx = (start + i) % num_listeners;
+                if let Poll::Ready(conn) = accepts[idx].as_mut().poll(cx) {
+                    return Poll::Ready((idx, conn));
+                }
+            }
+
+            Poll::Pending
+        })
+        .await;
+        self.next = idx + 1;
+
+        conn
+    }
+}
+
+#[cfg(feature = "std")]
+impl<L: LocalAddr> LocalAddr for MultiListener<L> {
+    type Address = std::vec::Vec<L::Address>;
+
+    /// The local addresses of all the listeners, in the order they were added.
+    fn local_addr(&self) -> Result<Self::Address> {
+        self.listeners.iter().map(LocalAddr::local_addr).collect()
+    }
+}
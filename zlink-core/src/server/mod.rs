@@ -1,24 +1,47 @@
+pub mod interceptor;
 pub(crate) mod listener;
 mod select_all;
 pub mod service;
 
-use futures_util::{FutureExt, StreamExt};
+use core::str::FromStr;
+
+use futures_util::{pin_mut, FutureExt, StreamExt};
 use mayheap::Vec;
 use select_all::SelectAll;
 use service::MethodReply;
 
 use crate::{
-    connection::{ReadConnection, Socket, WriteConnection},
-    Call, Connection, Reply,
+    connection::{socket::WriteHalf, ReadConnection, Socket, WriteConnection},
+    varlink_service, Call, Connection, Reply,
 };
 
 /// A server.
 ///
 /// The server listens for incoming connections and handles method calls using a service.
 #[derive(Debug)]
-pub struct Server<Listener, Service> {
+pub struct Server<Listener, Service, Interceptor = ()> {
     listener: Option<Listener>,
     service: Service,
+    interceptor: Interceptor,
+    scheduling: Scheduling,
+}
+
+/// The policy [`Server::run`] uses to choose between the events that are ready at the same time.
+///
+/// In both cases, new connections are accepted first and the connections of the same kind are
+/// served in a round-robin fashion, so that a busy connection can't starve the others of its kind.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Scheduling {
+    /// Method calls and replies from the reply streams take turns.
+    ///
+    /// This maximizes the reply throughput, since the reply streams keep making progress even
+    /// when the clients are sending method calls at a high rate.
+    RoundRobin,
+    /// Method calls are always handled before the replies from the reply streams.
+    ///
+    /// This keeps the latency of method calls low, at the expense of the reply streams.
+    #[default]
+    Priority,
 }
 
 impl<Listener, Service> Server<Listener, Service>
@@ -31,8 +54,41 @@ where
         Self {
             listener: Some(listener),
             service,
+            interceptor: (),
+            scheduling: Scheduling::default(),
         }
     }
+}
+
+impl<Listener, Service, Interceptor> Server<Listener, Service, Interceptor>
+where
+    Listener: listener::Listener,
+    Service: service::Service,
+    Interceptor: interceptor::Interceptor<Service>,
+{
+    /// Set the interceptor for all the replies sent by the server.
+    ///
+    /// See [`interceptor::Interceptor`] for details.
+    pub fn with_interceptor<I>(self, interceptor: I) -> Server<Listener, Service, I>
+    where
+        I: interceptor::Interceptor<Service>,
+    {
+        Server {
+            listener: self.listener,
+            service: self.service,
+            interceptor,
+            scheduling: self.scheduling,
+        }
+    }
+
+    /// Set the policy for choosing between the events that are ready at the same time.
+    ///
+    /// See [`Scheduling`] for details. The default is [`Scheduling::Priority`].
+    pub fn with_scheduling(mut self, scheduling: Scheduling) -> Self {
+        self.scheduling = scheduling;
+
+        self
+    }
 
     /// Run the server.
     ///
@@ -65,21 +121,58 @@ where
             Vec::<ReplyStream<Service::ReplyStream, Listener::Socket>, MAX_CONNECTIONS>::new();
         let mut last_reply_stream_winner = None;
         let mut last_method_call_winner = None;
+        // Whether a method call was handled last, so reply streams get their turn next.
+        let mut handled_call_last = false;
+
+        enum Event<Conn, Call, StreamReply> {
+            Connection(Conn),
+            Call(Call),
+            StreamReply(StreamReply),
+        }
 
         loop {
             let mut reply_stream_futures: Vec<_, MAX_CONNECTIONS> =
                 reply_streams.iter_mut().map(|s| s.stream.next()).collect();
-            let start_index = last_reply_stream_winner.map(|idx| idx + 1);
-            let mut reply_stream_select_all = SelectAll::new(start_index);
-            for future in reply_stream_futures.iter_mut() {
-                reply_stream_select_all
-                    .push(future)
-                    .map_err(|_| crate::Error::BufferOverflow)?;
-            }
+            // New connections are always accepted first.
+            let streams_first = self.scheduling == Scheduling::RoundRobin && handled_call_last;
+            let event = {
+                let start_index = last_reply_stream_winner.map(|idx| idx + 1);
+                let mut reply_stream_select_all = SelectAll::new(start_index);
+                for future in reply_stream_futures.iter_mut() {
+                    reply_stream_select_all
+                        .push(future)
+                        .map_err(|_| crate::Error::BufferOverflow)?;
+                }
+                let mut reply_stream_select_all = reply_stream_select_all.fuse();
+                let accept = listener.accept().fuse();
+                let next_call = self
+                    .get_next_call(
+                        // SAFETY: `readers` is not invalidated or dropped until the output of
+                        // this future is dropped.
+                        unsafe { &mut *(&mut readers as *mut _) },
+                        last_method_call_winner.map(|idx| idx + 1),
+                    )
+                    .fuse();
+                pin_mut!(accept, next_call);
 
-            futures_util::select_biased! {
+                if streams_first {
+                    futures_util::select_biased! {
+                        conn = accept => Event::Connection(conn),
+                        reply = reply_stream_select_all => Event::StreamReply(reply),
+                        res = next_call => Event::Call(res),
+                    }
+                } else {
+                    futures_util::select_biased! {
+                        conn = accept => Event::Connection(conn),
+                        res = next_call => Event::Call(res),
+                        reply = reply_stream_select_all => Event::StreamReply(reply),
+                    }
+                }
+            };
+
+            match event {
                 // 1. Accept a new connection.
-                conn = listener.accept().fuse() => {
+                Event::Connection(conn) => {
                     let conn = conn?;
                     let (read, write) = conn.split();
                     readers
@@ -89,50 +182,66 @@ where
                         .push(write)
                         .map_err(|_| crate::Error::BufferOverflow)?;
                 }
-                // 2. Read method calls from the existing connections and handle them.
-                res = self.get_next_call(
-                    // SAFETY: `readers` is not invalidated or dropped until the output of this
-                    // future is dropped.
-                    unsafe { &mut *(&mut readers as *mut _) },
-                    last_method_call_winner.map(|idx| idx + 1),
-                ).fuse() => {
-                        let (idx, call) = res?;
-                        last_method_call_winner = Some(idx);
-
-                        let mut stream = None;
-                        let mut remove = true;
-                        match call {
-                            Ok(call) => match self.handle_call(call, &mut writers[idx]).await {
-                                Ok(None) => remove = false,
-                                Ok(Some(s)) => stream = Some(s),
+                // 2. Handle a method call read from one of the existing connections.
+                Event::Call(res) => {
+                    let (idx, call) = res?;
+                    last_method_call_winner = Some(idx);
+                    handled_call_last = true;
+
+                    let mut stream = None;
+                    let mut remove = true;
+                    match call {
+                        Ok(Ok(call)) => match handle_call(
+                            &mut self.service,
+                            &mut self.interceptor,
+                            call,
+                            &mut writers[idx],
+                        )
+                        .await
+                        {
+                            Ok(None) => remove = false,
+                            Ok(Some(s)) => stream = Some(s),
+                            Err(e) => warn!("Error writing to connection: {:?}", e),
+                        },
+                        Ok(Err(method)) => {
+                            match handle_unknown_method::<Service, _, _>(
+                                method,
+                                &mut self.interceptor,
+                                &mut writers[idx],
+                            )
+                            .await
+                            {
+                                Ok(()) => remove = false,
                                 Err(e) => warn!("Error writing to connection: {:?}", e),
-                            },
-                            Err(e) => warn!("Error reading from socket: {:?}", e),
+                            }
                         }
+                        Err(e) => warn!("Error reading from socket: {:?}", e),
+                    }
 
-                        if stream.is_some() || remove {
-                            let reader = readers.remove(idx);
-                            let writer = writers.remove(idx);
+                    if stream.is_some() || remove {
+                        let reader = readers.remove(idx);
+                        let writer = writers.remove(idx);
 
-                            #[cfg(not(feature = "std"))]
-                            drop(reply_stream_futures);
-                            if let Some(stream) = stream.map(|s| ReplyStream::new(s, reader, writer)) {
-                                reply_streams
-                                    .push(stream)
-                                    .map_err(|_| crate::Error::BufferOverflow)?;
-                            }
+                        #[cfg(not(feature = "std"))]
+                        drop(reply_stream_futures);
+                        if let Some(stream) = stream.map(|s| ReplyStream::new(s, reader, writer)) {
+                            reply_streams
+                                .push(stream)
+                                .map_err(|_| crate::Error::BufferOverflow)?;
                         }
+                    }
                 }
-                // 3. Read replies from the reply streams and send them off.
-                reply = reply_stream_select_all.fuse() => {
+                // 3. Send off a reply read from one of the reply streams.
+                Event::StreamReply((idx, reply)) => {
                     #[cfg(not(feature = "std"))]
                     drop(reply_stream_futures);
-                    let (idx, reply) = reply;
                     last_reply_stream_winner = Some(idx);
+                    handled_call_last = false;
                     let id = reply_streams.get(idx).unwrap().conn.id();
 
                     match reply {
-                        Some(reply) => {
+                        Some(mut reply) => {
+                            self.interceptor.stream_reply(&mut reply, id);
                             if let Err(e) = reply_streams
                                 .get_mut(idx)
                                 .unwrap()
@@ -170,7 +279,8 @@ where
     /// On success, this method returns a tuple containing:
     ///
     /// * The index of the reader that yielded a call.
-    /// * A Result, containing a method call if reading was successful.
+    /// * A Result, containing a method call if reading was successful. If the method call was not
+    ///   for the interface of the service, the method name is returned instead.
     async fn get_next_call<'r>(
         &mut self,
         readers: &'r mut Vec<
@@ -178,8 +288,14 @@ where
             16,
         >,
         start_index: Option<usize>,
-    ) -> crate::Result<(usize, crate::Result<Call<Service::MethodCall<'r>>>)> {
-        let mut read_futures: Vec<_, 16> = readers.iter_mut().map(|r| r.receive_call()).collect();
+    ) -> crate::Result<(
+        usize,
+        crate::Result<Result<Call<Service::MethodCall<'r>>, &'r str>>,
+    )> {
+        let mut read_futures: Vec<_, 16> = readers
+            .iter_mut()
+            .map(|r| r.receive_call_for(Service::INTERFACE))
+            .collect();
         let mut select_all = SelectAll::new(start_index);
         for future in &mut read_futures {
             // Safety: `future` is in fact `Unpin` but the compiler doesn't know that.
@@ -192,27 +308,126 @@ where
 
         Ok(select_all.await)
     }
+}
 
-    async fn handle_call(
-        &mut self,
-        call: Call<Service::MethodCall<'_>>,
-        writer: &mut WriteConnection<<Listener::Socket as Socket>::WriteHalf>,
-    ) -> crate::Result<Option<Service::ReplyStream>> {
-        let mut stream = None;
-        match self.service.handle(call).await {
-            MethodReply::Single(params) => {
-                let reply = Reply::new(params).set_continues(Some(false));
-                writer.send_reply(&reply).await?
+/// Serve the method calls received over a single connection, until it's closed.
+///
+/// Unlike [`Server::run`], this doesn't listen for connections. It's meant as a building block for
+/// servers that serve each connection in a separate task, so that a slow method call on one
+/// connection doesn't hold up the others.
+///
+/// Errors are logged and end the serving of the connection.
+pub async fn serve_connection<Sock, Svc, Interceptor>(
+    conn: Connection<Sock>,
+    service: &mut Svc,
+    interceptor: &mut Interceptor,
+) where
+    Sock: Socket,
+    Svc: service::Service,
+    Interceptor: interceptor::Interceptor<Svc>,
+{
+    let (mut reader, mut writer) = conn.split();
+
+    loop {
+        let res = match reader.receive_call_for(Svc::INTERFACE).await {
+            Ok(Ok(call)) => match handle_call(service, interceptor, call, &mut writer).await {
+                Ok(Some(stream)) => {
+                    send_stream_replies::<Svc, _, _>(stream, interceptor, &mut writer).await
+                }
+                Ok(None) => Ok(()),
+                Err(e) => Err(e),
+            },
+            Ok(Err(method)) => {
+                handle_unknown_method::<Svc, _, _>(method, interceptor, &mut writer).await
             }
-            MethodReply::Error(err) => writer.send_error(&err).await?,
-            MethodReply::Multi(s) => {
-                trace!("Client {} now turning into a reply stream", writer.id());
-                stream = Some(s)
+            Err(e) => {
+                warn!("Error reading from socket: {:?}", e);
+
+                return;
             }
+        };
+        if let Err(e) = res {
+            warn!("Error writing to connection: {:?}", e);
+
+            return;
+        }
+    }
+}
+
+async fn handle_call<Svc, Interceptor, Write>(
+    service: &mut Svc,
+    interceptor: &mut Interceptor,
+    call: Call<Svc::MethodCall<'_>>,
+    writer: &mut WriteConnection<Write>,
+) -> crate::Result<Option<Svc::ReplyStream>>
+where
+    Svc: service::Service,
+    Interceptor: interceptor::Interceptor<Svc>,
+    Write: WriteHalf,
+{
+    let mut stream = None;
+    match service.handle(call).await {
+        MethodReply::Single(params) => {
+            let mut reply = Reply::new(params).set_continues(Some(false));
+            interceptor.reply(&mut reply, writer.id());
+            writer.send_reply(&reply).await?
+        }
+        MethodReply::Error(mut err) => {
+            interceptor.error(&mut err, writer.id());
+            writer.send_error(&err).await?
         }
+        MethodReply::Multi(s) => {
+            trace!("Client {} now turning into a reply stream", writer.id());
+            stream = Some(s)
+        }
+        MethodReply::SnapshotThenStream(params, s) => {
+            // The snapshot must be sent before the stream replies.
+            let mut reply = Reply::new(Some(params)).set_continues(Some(true));
+            interceptor.reply(&mut reply, writer.id());
+            writer.send_reply(&reply).await?;
 
-        Ok(stream)
+            trace!("Client {} now turning into a reply stream", writer.id());
+            stream = Some(s)
+        }
     }
+
+    Ok(stream)
+}
+
+async fn send_stream_replies<Svc, Interceptor, Write>(
+    mut stream: Svc::ReplyStream,
+    interceptor: &mut Interceptor,
+    writer: &mut WriteConnection<Write>,
+) -> crate::Result<()>
+where
+    Svc: service::Service,
+    Interceptor: interceptor::Interceptor<Svc>,
+    Write: WriteHalf,
+{
+    while let Some(mut reply) = stream.next().await {
+        interceptor.stream_reply(&mut reply, writer.id());
+        writer.send_reply(&reply).await?;
+    }
+    trace!("Stream closed for client {}", writer.id());
+
+    Ok(())
+}
+
+async fn handle_unknown_method<Svc, Interceptor, Write>(
+    method: &str,
+    interceptor: &mut Interceptor,
+    writer: &mut WriteConnection<Write>,
+) -> crate::Result<()>
+where
+    Svc: service::Service,
+    Interceptor: interceptor::Interceptor<Svc>,
+    Write: WriteHalf,
+{
+    let method = mayheap::String::from_str(method)?;
+    let mut err = varlink_service::Error::MethodNotFound { method };
+    interceptor.server_error(&mut err, writer.id());
+
+    writer.send_error(&err).await
 }
 
 const MAX_CONNECTIONS: usize = 16;
@@ -9,6 +9,16 @@ use crate::{Call, Reply};
 
 /// Service trait for handling method calls.
 pub trait Service {
+    /// The name of the interface this service implements, if any.
+    ///
+    /// If set, the [`crate::Server`] will reply with a
+    /// [`crate::varlink_service::Error::MethodNotFound`] error to all method calls that are not
+    /// for a member of this interface, without passing them on to [`Service::handle`]. Calls for
+    /// the `org.varlink.service` interface are always passed on.
+    ///
+    /// Defaults to `None`, which means all calls are passed on to the service.
+    const INTERFACE: Option<&'static str> = None;
+
     /// The type of method call that this service handles.
     ///
     /// This should be a type that can deserialize itself from a complete method call message: i-e
@@ -50,12 +60,24 @@ pub trait Service {
 }
 
 /// A service method call reply.
+///
+/// More kinds of replies may be added in the future, so matching on this needs a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum MethodReply<Params, ReplyStream, ReplyError> {
     /// A single reply.
+    ///
+    /// Use `None` for methods that don't return anything, in which case the reply is sent without
+    /// any parameters (see [`crate::Reply::empty`]).
     Single(Option<Params>),
     /// An error reply.
     Error(ReplyError),
     /// A multi-reply stream.
     Multi(ReplyStream),
+    /// A single reply, followed by a multi-reply stream.
+    ///
+    /// The first reply is sent right away with the `continues` flag set, and then the replies from
+    /// the stream follow. This is useful for methods that send the current state (a snapshot)
+    /// followed by updates to it.
+    SnapshotThenStream(Params, ReplyStream),
 }
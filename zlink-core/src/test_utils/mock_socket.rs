@@ -4,9 +4,15 @@
 //! used in tests to simulate socket behavior without requiring actual network
 //! connections.
 
-use crate::connection::socket::{ReadHalf, Socket, WriteHalf};
+use crate::connection::{
+    socket::{ReadHalf, Socket, WriteHalf},
+    BUFFER_SIZE,
+};
 use mayheap::Vec;
 
+// Enough for a message filling the read buffer of a connection, and the null byte following it.
+const READ_DATA_SIZE: usize = BUFFER_SIZE + 1;
+
 /// Mock socket implementation for testing.
 ///
 /// This socket pre-loads response data and allows tests to verify what was written.
@@ -15,7 +21,7 @@ use mayheap::Vec;
 #[derive(Debug)]
 #[doc(hidden)]
 pub struct MockSocket {
-    read_data: Vec<u8, 1024>,
+    read_data: Vec<u8, READ_DATA_SIZE>,
     read_pos: usize,
 }
 
@@ -62,7 +68,7 @@ impl Socket for MockSocket {
 #[derive(Debug)]
 #[doc(hidden)]
 pub struct MockReadHalf {
-    data: Vec<u8, 1024>,
+    data: Vec<u8, READ_DATA_SIZE>,
     pos: usize,
 }
 
@@ -164,3 +170,16 @@ impl WriteHalf for CountingWriteHalf {
         Ok(())
     }
 }
+
+/// Mock write half whose writes always fail.
+///
+/// This is useful for testing the handling of write errors.
+#[derive(Debug, Default)]
+#[doc(hidden)]
+pub struct FailingWriteHalf;
+
+impl WriteHalf for FailingWriteHalf {
+    async fn write(&mut self, _buf: &[u8]) -> crate::Result<()> {
+        Err(crate::Error::SocketWrite)
+    }
+}
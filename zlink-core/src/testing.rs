@@ -0,0 +1,191 @@
+//! Utilities for testing code using zlink, without any actual I/O.
+//!
+//! [`ChannelSocket`] is a socket backed by in-memory byte channels. It gives tests full control
+//! over how the bytes are split across reads, e.g to exercise the handling of messages split
+//! across reads or of a NUL byte right at the boundary of the read buffer.
+//!
+//! This module is only available with the `testing` feature.
+
+use core::task::{Poll, Waker};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    vec::Vec,
+};
+
+use crate::connection::socket::{ReadHalf, Socket, WriteHalf};
+
+/// A socket backed by a pair of byte channels.
+///
+/// Use the [`ChannelHandle`] returned by [`ChannelSocket::new`] to feed the bytes to be read and
+/// to inspect the bytes written. Each chunk of bytes fed is returned by a separate read, so tests
+/// can control exactly how messages are split across reads. Reads wait for more bytes to be fed,
+/// until [`ChannelHandle::close`] is called.
+#[derive(Debug)]
+pub struct ChannelSocket {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl ChannelSocket {
+    /// Create a new socket, along with the handle to feed and inspect it.
+    pub fn new() -> (Self, ChannelHandle) {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+
+        (
+            Self {
+                shared: shared.clone(),
+            },
+            ChannelHandle { shared },
+        )
+    }
+}
+
+impl Socket for ChannelSocket {
+    type ReadHalf = ChannelReadHalf;
+    type WriteHalf = ChannelWriteHalf;
+
+    fn split(self) -> (Self::ReadHalf, Self::WriteHalf) {
+        (
+            ChannelReadHalf {
+                shared: self.shared.clone(),
+            },
+            ChannelWriteHalf {
+                shared: self.shared,
+            },
+        )
+    }
+}
+
+/// The test side of a [`ChannelSocket`].
+#[derive(Debug, Clone)]
+pub struct ChannelHandle {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl ChannelHandle {
+    /// Feed `bytes` to be returned by the next read of the socket.
+    ///
+    /// If the buffer of the read is smaller than `bytes`, the rest is returned by the following
+    /// reads.
+    pub fn feed(&self, bytes: &[u8]) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.chunks.push_back(bytes.to_vec());
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// Close the read side, so reads return `0` once all the fed bytes are read.
+    pub fn close(&self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.closed = true;
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+
+    /// All the bytes written to the socket so far.
+    pub fn written(&self) -> Vec<u8> {
+        self.shared.lock().unwrap().written.clone()
+    }
+}
+
+/// The read half of a [`ChannelSocket`].
+#[derive(Debug)]
+pub struct ChannelReadHalf {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl ReadHalf for ChannelReadHalf {
+    async fn read(&mut self, buf: &mut [u8]) -> crate::Result<usize> {
+        core::future::poll_fn(|cx| {
+            let mut shared = self.shared.lock().unwrap();
+            let Some(chunk) = shared.chunks.front_mut() else {
+                if shared.closed {
+                    return Poll::Ready(Ok(0));
+                }
+                shared.waker = Some(cx.waker().clone());
+
+                return Poll::Pending;
+            };
+
+            let len = chunk.len().min(buf.len());
+            buf[..len].copy_from_slice(&chunk[..len]);
+            chunk.drain(..len);
+            if chunk.is_empty() {
+                shared.chunks.pop_front();
+            }
+
+            Poll::Ready(Ok(len))
+        })
+        .await
+    }
+}
+
+/// The write half of a [`ChannelSocket`].
+#[derive(Debug)]
+pub struct ChannelWriteHalf {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl WriteHalf for ChannelWriteHalf {
+    async fn write(&mut self, buf: &[u8]) -> crate::Result<()> {
+        self.shared.lock().unwrap().written.extend_from_slice(buf);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    chunks: VecDeque<Vec<u8>>,
+    closed: bool,
+    written: Vec<u8>,
+    waker: Option<Waker>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Call, Connection};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize)]
+    #[serde(tag = "method", content = "parameters")]
+    enum Methods {
+        #[serde(rename = "org.example.Get")]
+        Get,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Value {
+        value: u32,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct TestError {}
+
+    #[tokio::test]
+    async fn message_split_across_reads() -> crate::Result<()> {
+        let (socket, handle) = ChannelSocket::new();
+        let mut conn = Connection::new(socket);
+        conn.send_call(&Call::new(Methods::Get)).await?;
+        assert_eq!(handle.written(), b"{\"method\":\"org.example.Get\"}\0");
+
+        // The first half is available right away, the second only once the read is waiting.
+        handle.feed(br#"{"parame"#);
+        let (reply, _) = tokio::join!(conn.receive_reply::<Value, TestError>(), async {
+            tokio::task::yield_now().await;
+            handle.feed(b"ters\":{\"value\":42}}\0");
+        });
+        assert_eq!(reply?.unwrap().parameters().unwrap().value, 42);
+
+        handle.close();
+        assert!(matches!(
+            conn.receive_reply::<Value, TestError>().await,
+            Err(crate::Error::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof
+        ));
+
+        Ok(())
+    }
+}
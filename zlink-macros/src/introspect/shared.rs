@@ -35,7 +35,8 @@ pub(super) fn generate_field_definitions(
                     .ok_or_else(|| Error::new_spanned(field, "Field must have a name"))?;
 
                 let field_type = utils::remove_lifetimes_from_type(&field.ty);
-                let field_name_str = field_name.to_string();
+                let field_name_str = utils::parse_zlink_string_attr(&field.attrs, "rename")
+                    .unwrap_or_else(|| field_name.to_string());
 
                 let static_name = if let Some(variant_ident) = variant_prefix {
                     quote::format_ident!(
@@ -89,7 +90,8 @@ pub(super) fn generate_enum_variant_definitions(
         // Only support unit variants (no associated data).
         match &variant.fields {
             Fields::Unit => {
-                let variant_name = variant.ident.to_string();
+                let variant_name = utils::parse_zlink_string_attr(&variant.attrs, "rename")
+                    .unwrap_or_else(|| variant.ident.to_string());
                 let comments = utils::extract_doc_comments(&variant.attrs);
                 let comment_objects = generate_comment_objects(&comments, crate_path);
                 let variant_ref = quote! {
@@ -43,6 +43,8 @@ mod proxy;
 ///
 /// * `#[zlink(crate = "path")]` - Specifies the crate path to use for zlink types. Defaults to
 ///   `::zlink`.
+/// * `#[zlink(rename = "name")]` - On a field or a variant, specifies its name in the Varlink type.
+///   Defaults to the Rust identifier.
 ///
 /// # Limitations
 ///
@@ -205,6 +207,8 @@ pub fn derive_introspect_type(input: proc_macro::TokenStream) -> proc_macro::Tok
 ///
 /// * `#[zlink(crate = "path")]` - Specifies the crate path to use for zlink types. Defaults to
 ///   `::zlink`.
+/// * `#[zlink(rename = "name")]` - On a field or a variant, specifies its name in the Varlink type.
+///   Defaults to the Rust identifier.
 ///
 /// # Examples
 ///
@@ -293,6 +297,8 @@ pub fn derive_introspect_custom_type(input: proc_macro::TokenStream) -> proc_mac
 ///
 /// * `#[zlink(crate = "path")]` - Specifies the crate path to use for zlink types. Defaults to
 ///   `::zlink`.
+/// * `#[zlink(rename = "name")]` - On a field of a variant, specifies its name in the Varlink
+///   error. Defaults to the Rust identifier.
 ///
 /// # Example
 ///
@@ -354,6 +360,13 @@ pub fn derive_introspect_reply_error(input: proc_macro::TokenStream) -> proc_mac
 /// * `crate` - Specifies the crate path to use for zlink types. Defaults to `::zlink`.
 /// * `chain_name` - Custom name for the generated chain extension trait. Defaults to
 ///   `{TraitName}Chain`.
+/// * `impl_for` - An additional type to implement the trait for, e.g. `"PooledConnection<S>"`. The
+///   type must be generic over the socket type `S` and implement `AsMut<Connection<S>>`. This
+///   allows using the proxy methods through connection wrappers, such as pools or middleware.
+/// * `method_case` - The casing used to derive the Varlink method names from the names of the Rust
+///   methods, for methods that aren't renamed through `#[zlink(rename = "...")]`. One of
+///   `"PascalCase"` (default), `"camelCase"`, `"snake_case"`, `"SCREAMING_SNAKE_CASE"`,
+///   `"kebab-case"`, `"SCREAMING-KEBAB-CASE"`, `"lowercase"` or `"UPPERCASE"`.
 ///
 /// # Example
 ///
@@ -584,7 +597,8 @@ pub fn derive_introspect_reply_error(input: proc_macro::TokenStream) -> proc_mac
 ///
 /// For each proxy trait, the macro generates a corresponding chain extension trait. For example,
 /// `FtlProxy` gets `FtlProxyChain`. This trait is automatically implemented for `Chain` types,
-/// allowing seamless method chaining across interfaces.
+/// allowing seamless method chaining across interfaces. It has the same visibility as the proxy
+/// trait.
 ///
 /// # Method Requirements
 ///
@@ -608,6 +622,13 @@ pub fn derive_introspect_reply_error(input: proc_macro::TokenStream) -> proc_mac
 /// To specify a different Varlink method name, use the `#[zlink(rename = "...")]` attribute. See
 /// `list_machines` in the example above.
 ///
+/// # Optional Parameters
+///
+/// Parameters of type `Option<T>` are not sent at all when they're `None`. If the parameter type
+/// is an `Option` that can't be detected as such (e.g. a type alias), mark the parameter with the
+/// `#[zlink(default)]` attribute to get the same behavior. The `#[zlink(rename = "...")]`
+/// attribute can be used on parameters to specify a different name for the Varlink call.
+///
 /// # Streaming Methods
 ///
 /// For methods that support streaming (the 'more' flag), use the `#[zlink(more)]` attribute.
@@ -615,6 +636,19 @@ pub fn derive_introspect_reply_error(input: proc_macro::TokenStream) -> proc_mac
 /// ErrorType>>>>`. The proxy will automatically set the 'more' flag on the call and return a
 /// stream of replies.
 ///
+/// # Single Output Parameter
+///
+/// Varlink output parameters are always named, so the reply type is typically a struct with a
+/// field for each of them. For methods with a single output parameter, use the
+/// `#[zlink(output = "name")]` attribute to return the value of the parameter directly instead,
+/// e.g. `#[zlink(output = "user")]` for a method replying with `{"user": {...}}`.
+///
+/// # Helper Methods
+///
+/// Methods marked with the `#[zlink(skip)]` attribute don't correspond to a Varlink method and
+/// are left untouched, as provided methods of the trait. They must therefore have a default
+/// implementation, which can make use of the other methods of the trait.
+///
 /// # Generic Parameters
 ///
 /// The proxy macro supports generic type parameters on individual methods. Note that generic
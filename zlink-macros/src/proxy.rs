@@ -11,7 +11,7 @@ mod utils;
 use chain_extension::generate_chain_extension_method;
 use chain_method::generate_chain_method;
 use method_impl::generate_method_impl;
-use types::MethodAttrs;
+use types::{MethodAttrs, MethodCase};
 use utils::build_combined_where_clause;
 
 pub(crate) fn proxy(attr: TokenStream, input: TokenStream) -> TokenStream {
@@ -24,8 +24,15 @@ pub(crate) fn proxy(attr: TokenStream, input: TokenStream) -> TokenStream {
 fn proxy_impl(attr: TokenStream, input: TokenStream) -> Result<TokenStream, Error> {
     let mut trait_def = parse2::<ItemTrait>(input)?;
 
-    // Parse the interface name, crate path, and chain name from the attribute
-    let (interface_name, crate_path, chain_name) = parse_proxy_attributes(&attr, &trait_def)?;
+    // Parse the interface name, crate path, chain name, extra impl targets and method casing from
+    // the attribute
+    let ProxyAttrs {
+        interface_name,
+        crate_path,
+        chain_name,
+        impl_for,
+        method_case,
+    } = parse_proxy_attributes(&attr, &trait_def)?;
 
     // Validate trait definition
     validate_trait(&trait_def)?;
@@ -43,12 +50,24 @@ fn proxy_impl(attr: TokenStream, input: TokenStream) -> Result<TokenStream, Erro
             // Extract attributes once to avoid multiple mutable borrows
             let method_attrs = MethodAttrs::extract(&mut method.attrs)?;
 
+            // Skipped methods are left as they are, as provided methods of the trait.
+            if method_attrs.skip {
+                if method.default.is_none() {
+                    return Err(Error::new_spanned(
+                        &method.sig,
+                        "methods with `#[zlink(skip)]` must have a default implementation",
+                    ));
+                }
+                continue;
+            }
+
             // Generate chain extension method
             let (extension_method, extension_impl) = generate_chain_extension_method(
                 method,
                 &interface_name,
                 &trait_def.generics,
                 &method_attrs,
+                method_case,
                 &crate_path,
             )?;
             if !extension_method.is_empty() {
@@ -64,6 +83,7 @@ fn proxy_impl(attr: TokenStream, input: TokenStream) -> Result<TokenStream, Erro
                 &interface_name,
                 &trait_def.generics,
                 &method_attrs,
+                method_case,
                 &crate_path,
             )?;
             methods.push(method_impl);
@@ -74,6 +94,7 @@ fn proxy_impl(attr: TokenStream, input: TokenStream) -> Result<TokenStream, Erro
                 &interface_name,
                 &trait_def.generics,
                 &method_attrs,
+                method_case,
                 &crate_path,
             )?;
             if !chain_trait.is_empty() {
@@ -94,9 +115,11 @@ fn proxy_impl(attr: TokenStream, input: TokenStream) -> Result<TokenStream, Erro
         &methods,
         &chain_method_impls,
         &crate_path,
+        impl_for.as_ref(),
     );
     let chain_extension_trait_output = build_chain_extension_trait(
         &trait_def.ident,
+        &trait_def.vis,
         &chain_extension_methods,
         &chain_extension_impls,
         &crate_path,
@@ -110,10 +133,16 @@ fn proxy_impl(attr: TokenStream, input: TokenStream) -> Result<TokenStream, Erro
     })
 }
 
-fn parse_proxy_attributes(
-    attr: &TokenStream,
-    trait_def: &ItemTrait,
-) -> Result<(String, TokenStream, Option<syn::Ident>), Error> {
+/// The parsed arguments of the `proxy` attribute.
+struct ProxyAttrs {
+    interface_name: String,
+    crate_path: TokenStream,
+    chain_name: Option<syn::Ident>,
+    impl_for: Option<syn::Type>,
+    method_case: MethodCase,
+}
+
+fn parse_proxy_attributes(attr: &TokenStream, trait_def: &ItemTrait) -> Result<ProxyAttrs, Error> {
     if attr.is_empty() {
         return Err(Error::new_spanned(
             trait_def,
@@ -124,13 +153,21 @@ fn parse_proxy_attributes(
 
     // Try parsing as a simple string literal first (backward compatibility)
     if let Ok(Lit::Str(lit_str)) = parse2::<Lit>(attr.clone()) {
-        return Ok((lit_str.value(), quote! { ::zlink }, None));
+        return Ok(ProxyAttrs {
+            interface_name: lit_str.value(),
+            crate_path: quote! { ::zlink },
+            chain_name: None,
+            impl_for: None,
+            method_case: MethodCase::default(),
+        });
     }
 
     // Parse as name-value pairs
     let mut interface_name = None;
     let mut crate_path = None;
     let mut chain_name = None;
+    let mut impl_for = None;
+    let mut method_case = MethodCase::default();
 
     let parser = syn::meta::parser(|meta| {
         if meta.path.is_ident("interface") {
@@ -143,6 +180,20 @@ fn parse_proxy_attributes(
         } else if meta.path.is_ident("chain_name") {
             let value: syn::LitStr = meta.value()?.parse()?;
             chain_name = Some(syn::Ident::new(&value.value(), value.span()));
+        } else if meta.path.is_ident("impl_for") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            let ty: syn::Type = value.parse()?;
+            if !type_mentions_socket_param(&ty) {
+                return Err(Error::new_spanned(
+                    value,
+                    "`impl_for` type must be generic over the socket type `S`, \
+                     e.g. #[proxy(impl_for = \"MyWrapper<S>\")]",
+                ));
+            }
+            impl_for = Some(ty);
+        } else if meta.path.is_ident("method_case") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            method_case = MethodCase::parse(&value)?;
         } else {
             return Err(meta.error("unsupported attribute"));
         }
@@ -161,7 +212,26 @@ fn parse_proxy_attributes(
 
     let crate_path = crate_path.unwrap_or_else(|| quote! { ::zlink });
 
-    Ok((interface_name, crate_path, chain_name))
+    Ok(ProxyAttrs {
+        interface_name,
+        crate_path,
+        chain_name,
+        impl_for,
+        method_case,
+    })
+}
+
+// Check if the `S` socket type parameter appears anywhere in the given type.
+fn type_mentions_socket_param(ty: &syn::Type) -> bool {
+    fn contains_s(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            proc_macro2::TokenTree::Ident(ident) => ident == "S",
+            proc_macro2::TokenTree::Group(group) => contains_s(group.stream()),
+            _ => false,
+        })
+    }
+
+    contains_s(quote! { #ty })
 }
 
 fn validate_trait(trait_def: &ItemTrait) -> Result<(), Error> {
@@ -208,6 +278,7 @@ fn build_impl_output(
     methods: &[TokenStream],
     chain_method_impls: &[TokenStream],
     crate_path: &TokenStream,
+    impl_for: Option<&syn::Type>,
 ) -> TokenStream {
     // Build impl generics combining trait generics with socket generic
     let mut impl_generics = generics.clone();
@@ -222,11 +293,32 @@ fn build_impl_output(
     }
 
     // Build where clause combining existing constraints with socket constraint and trait bounds
-    let combined_where_clause = Some(build_combined_where_clause(
+    let combined_where_clause = build_combined_where_clause(
         where_clause.clone(),
         syn::parse_quote!(S: #crate_path::connection::socket::Socket),
         generics,
-    ));
+    );
+
+    // Optionally, also implement the trait for a user-specified wrapper type that can yield a
+    // `&mut Connection<S>`. The generated method bodies only access the connection through
+    // `AsMut<Connection<S>>`, so they're shared between both impls.
+    let wrapper_impl = impl_for.map(|wrapper| {
+        let mut wrapper_where_clause = combined_where_clause.clone();
+        wrapper_where_clause.predicates.push(syn::parse_quote!(
+            #wrapper: ::core::convert::AsMut<#crate_path::Connection<S>>
+        ));
+
+        quote! {
+            impl #impl_generics #trait_name #trait_generics_no_bounds for #wrapper
+            #wrapper_where_clause
+            {
+                type Socket = S;
+
+                #(#methods)*
+                #(#chain_method_impls)*
+            }
+        }
+    });
 
     quote! {
         impl #impl_generics #trait_name #trait_generics_no_bounds for #crate_path::Connection<S>
@@ -237,11 +329,14 @@ fn build_impl_output(
             #(#methods)*
             #(#chain_method_impls)*
         }
+
+        #wrapper_impl
     }
 }
 
 fn build_chain_extension_trait(
     trait_name: &syn::Ident,
+    trait_vis: &syn::Visibility,
     chain_extension_methods: &[TokenStream],
     chain_extension_impls: &[TokenStream],
     crate_path: &TokenStream,
@@ -258,7 +353,7 @@ fn build_chain_extension_trait(
         /// Extension trait for adding proxy calls to any chain.
         ///
         /// This trait provides methods to add proxy calls to a chain of method calls.
-        pub trait #chain_trait_name<'c, S, ReplyParams, ReplyError>
+        #trait_vis trait #chain_trait_name<'c, S, ReplyParams, ReplyError>
         where
             S: #crate_path::connection::socket::Socket,
             ReplyParams: ::serde::Deserialize<'c> + ::core::fmt::Debug,
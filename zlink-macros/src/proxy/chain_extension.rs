@@ -3,7 +3,7 @@ use quote::quote;
 use syn::{Error, FnArg, Pat};
 
 use super::{
-    types::{ArgInfo, MethodAttrs},
+    types::{ArgInfo, MethodAttrs, MethodCase},
     utils::{convert_to_single_lifetime, snake_case_to_pascal_case, type_contains_lifetime},
 };
 
@@ -12,6 +12,7 @@ pub(super) fn generate_chain_extension_method(
     interface_name: &str,
     _trait_generics: &syn::Generics,
     method_attrs: &MethodAttrs,
+    method_case: MethodCase,
     crate_path: &TokenStream,
 ) -> Result<(TokenStream, TokenStream), Error> {
     let method_name_str = method.sig.ident.to_string();
@@ -25,7 +26,7 @@ pub(super) fn generate_chain_extension_method(
         return Ok((quote! {}, quote! {}));
     }
 
-    let converted_name = snake_case_to_pascal_case(&method_name_str);
+    let converted_name = method_case.convert(&method_name_str);
     let actual_method_name = method_attrs.rename.as_deref().unwrap_or(&converted_name);
     let method_path = format!("{interface_name}.{actual_method_name}");
 
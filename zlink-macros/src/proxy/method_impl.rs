@@ -4,7 +4,7 @@ use std::collections::HashSet;
 use syn::{punctuated::Punctuated, Error, FnArg, Pat, Type};
 
 use super::{
-    types::{ArgInfo, MethodAttrs},
+    types::{ArgInfo, MethodAttrs, MethodCase, ParamAttrs},
     utils::*,
 };
 use crate::utils::*;
@@ -14,12 +14,13 @@ pub(super) fn generate_method_impl(
     interface_name: &str,
     trait_generics: &syn::Generics,
     method_attrs: &MethodAttrs,
+    method_case: MethodCase,
     crate_path: &TokenStream,
 ) -> Result<TokenStream, Error> {
     let method_name = &method.sig.ident;
     let method_name_str = method_name.to_string();
 
-    let converted_name = snake_case_to_pascal_case(&method_name_str);
+    let converted_name = method_case.convert(&method_name_str);
     let actual_method_name = method_attrs.rename.as_deref().unwrap_or(&converted_name);
 
     // Build the full method path: interface.method
@@ -66,10 +67,33 @@ pub(super) fn generate_method_impl(
         has_explicit_lifetimes,
     );
 
+    // The reply parameters are deserialized into `Output` if only the value of a single output
+    // parameter is to be returned.
+    let (output_struct_def, call_reply_type) = match &method_attrs.output {
+        Some(_) if matches!(&reply_type, Type::Tuple(tuple) if tuple.elems.is_empty()) => {
+            return Err(Error::new_spanned(
+                &method.sig,
+                "method with an `output` attribute must return a value",
+            ));
+        }
+        Some(output) => (
+            quote! {
+                #[derive(::serde::Deserialize, ::core::fmt::Debug)]
+                struct Output<T> {
+                    #[serde(rename = #output)]
+                    value: T,
+                }
+            },
+            quote!(Output<#reply_type>),
+        ),
+        None => (quote! {}, quote!(#reply_type)),
+    };
+
     // Common method call setup
     let method_call_setup = quote! {
         #params_struct_def
         #params_init
+        #output_struct_def
 
         #[derive(::serde::Serialize, ::core::fmt::Debug)]
         struct MethodCall<T> {
@@ -89,6 +113,12 @@ pub(super) fn generate_method_impl(
             // Unit type ()
             quote!(Ok(Ok(())))
         }
+        _ if method_attrs.output.is_some() => {
+            quote!(match reply.into_parameters() {
+                Some(params) => Ok(Ok(params.value)),
+                None => Err(#crate_path::Error::MissingParameters),
+            })
+        }
         _ => {
             quote!(match reply.into_parameters() {
                 Some(params) => Ok(Ok(params)),
@@ -104,6 +134,7 @@ pub(super) fn generate_method_impl(
         generate_streaming_method(
             method_call_setup,
             &reply_type,
+            &call_reply_type,
             &error_type,
             out_params_extract,
             crate_path,
@@ -112,6 +143,7 @@ pub(super) fn generate_method_impl(
         generate_regular_method(
             method_call_setup,
             &reply_type,
+            &call_reply_type,
             &error_type,
             out_params_extract,
             crate_path,
@@ -151,13 +183,12 @@ fn parse_method_arguments<'a>(
             let name = &pat_ident.ident;
             let ty = &pat_type.ty;
 
-            // Extract parameter rename attribute
-            let serialized_name = extract_param_rename_attr(&mut pat_type.attrs)
-                .ok()
-                .flatten();
+            // Extract parameter attributes
+            let param_attrs = ParamAttrs::extract(&mut pat_type.attrs).unwrap_or_default();
+            let serialized_name = param_attrs.rename;
 
             // Check if the type is optional
-            let is_optional = is_option_type(ty);
+            let is_optional = param_attrs.default || is_option_type(ty);
 
             // Only convert to single lifetime if there are no explicit lifetimes
             let ty_for_params = if has_explicit_lifetimes {
@@ -343,7 +374,9 @@ fn generate_oneway_method(
         #method_call_setup
 
         let call = #crate_path::Call::new(method_call).set_oneway(true);
-        self.send_call(&call).await
+        let connection =
+            ::core::convert::AsMut::<#crate_path::Connection<S>>::as_mut(self);
+        connection.send_call(&call).await
     };
     (return_type, implementation)
 }
@@ -351,6 +384,7 @@ fn generate_oneway_method(
 fn generate_streaming_method(
     method_call_setup: TokenStream,
     reply_type: &Type,
+    call_reply_type: &TokenStream,
     error_type: &Type,
     out_params_extract: TokenStream,
     crate_path: &TokenStream,
@@ -366,11 +400,15 @@ fn generate_streaming_method(
         #method_call_setup
 
         let call = #crate_path::Call::new(method_call).set_more(true);
-        self.send_call(&call).await?;
+        let connection =
+            ::core::convert::AsMut::<#crate_path::Connection<S>>::as_mut(self);
+        connection.send_call(&call).await?;
+        // The stream reads from the read half directly, so make sure the call is written.
+        connection.flush().await?;
 
         let stream = #crate_path::connection::chain::ReplyStream::new(
-            self.read_mut(),
-            |conn| conn.receive_reply::<#reply_type, #error_type>(),
+            connection.read_mut(),
+            |conn| conn.receive_reply::<#call_reply_type, #error_type>(),
             1,
         );
 
@@ -389,6 +427,7 @@ fn generate_streaming_method(
 fn generate_regular_method(
     method_call_setup: TokenStream,
     reply_type: &Type,
+    call_reply_type: &TokenStream,
     error_type: &Type,
     out_params_extract: TokenStream,
     crate_path: &TokenStream,
@@ -400,7 +439,9 @@ fn generate_regular_method(
         #method_call_setup
 
         let call = #crate_path::Call::new(method_call);
-        match self.call_method::<_, #reply_type, #error_type>(&call).await? {
+        let connection =
+            ::core::convert::AsMut::<#crate_path::Connection<S>>::as_mut(self);
+        match connection.call_method::<_, #call_reply_type, #error_type>(&call).await? {
             Ok(reply) => #out_params_extract,
             Err(error) => Ok(Err(error)),
         }
@@ -1,6 +1,6 @@
 use syn::{Attribute, Error, Meta};
 
-use super::utils::{extract_zlink_attrs, parse_rename_value};
+use super::utils::{extract_zlink_attrs, parse_rename_value, snake_case_to_pascal_case};
 
 /// Attributes that can be applied to proxy methods via #[zlink(...)].
 #[derive(Default)]
@@ -11,6 +11,10 @@ pub(super) struct MethodAttrs {
     pub is_streaming: bool,
     /// Method is one-way (fire and forget).
     pub is_oneway: bool,
+    /// Method is not a Varlink call but a helper with a default implementation.
+    pub skip: bool,
+    /// The name of the single output parameter, whose value is returned directly.
+    pub output: Option<String>,
 }
 
 impl MethodAttrs {
@@ -36,6 +40,28 @@ impl MethodAttrs {
                         }
                         method_attrs.is_oneway = true;
                     }
+                    Meta::NameValue(nv) if nv.path.is_ident("output") => {
+                        if method_attrs.output.is_some() {
+                            return Err(Error::new_spanned(&meta, "duplicate `output` attribute"));
+                        }
+                        let syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Str(lit_str),
+                            ..
+                        }) = &nv.value
+                        else {
+                            return Err(Error::new_spanned(
+                                &nv.value,
+                                "output value must be a string literal",
+                            ));
+                        };
+                        method_attrs.output = Some(lit_str.value());
+                    }
+                    Meta::Path(path) if path.is_ident("skip") => {
+                        if method_attrs.skip {
+                            return Err(Error::new_spanned(&meta, "duplicate `skip` attribute"));
+                        }
+                        method_attrs.skip = true;
+                    }
                     _ => {
                         return Err(Error::new_spanned(&meta, "unknown zlink attribute"));
                     }
@@ -48,6 +74,118 @@ impl MethodAttrs {
     }
 }
 
+/// Attributes that can be applied to proxy method parameters via #[zlink(...)].
+#[derive(Default)]
+pub(super) struct ParamAttrs {
+    /// Rename the parameter for the Varlink call.
+    pub rename: Option<String>,
+    /// The parameter is optional and is not sent if it's `None`.
+    ///
+    /// Parameters of type `Option<T>` are detected automatically but this is needed for types
+    /// that can't be, e.g. type aliases.
+    pub default: bool,
+}
+
+impl ParamAttrs {
+    /// Extract parameter attributes from a parameter's attribute list.
+    pub(super) fn extract(attrs: &mut Vec<Attribute>) -> Result<Self, Error> {
+        let attrs_result = extract_zlink_attrs(attrs, |meta_items| {
+            let mut param_attrs = Self::default();
+
+            for meta in meta_items {
+                match &meta {
+                    Meta::NameValue(nv) if nv.path.is_ident("rename") => {
+                        if param_attrs.rename.is_some() {
+                            return Err(Error::new_spanned(
+                                &meta,
+                                "duplicate `rename` attribute on parameter",
+                            ));
+                        }
+                        param_attrs.rename = parse_rename_value(&nv.value)?;
+                    }
+                    Meta::Path(path) if path.is_ident("default") => {
+                        if param_attrs.default {
+                            return Err(Error::new_spanned(
+                                &meta,
+                                "duplicate `default` attribute on parameter",
+                            ));
+                        }
+                        param_attrs.default = true;
+                    }
+                    _ => {
+                        return Err(Error::new_spanned(
+                            &meta,
+                            "unknown zlink attribute on parameter",
+                        ));
+                    }
+                }
+            }
+
+            Ok(param_attrs)
+        });
+        Ok(attrs_result.unwrap_or_default())
+    }
+}
+
+/// The casing applied to the names of methods that aren't explicitly renamed.
+#[derive(Clone, Copy, Default)]
+pub(super) enum MethodCase {
+    #[default]
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+    Lower,
+    Upper,
+}
+
+impl MethodCase {
+    /// Parse the value of the `method_case` attribute.
+    pub(super) fn parse(value: &syn::LitStr) -> Result<Self, Error> {
+        match value.value().as_str() {
+            "PascalCase" => Ok(Self::Pascal),
+            "camelCase" => Ok(Self::Camel),
+            "snake_case" => Ok(Self::Snake),
+            "SCREAMING_SNAKE_CASE" => Ok(Self::ScreamingSnake),
+            "kebab-case" => Ok(Self::Kebab),
+            "SCREAMING-KEBAB-CASE" => Ok(Self::ScreamingKebab),
+            "lowercase" => Ok(Self::Lower),
+            "UPPERCASE" => Ok(Self::Upper),
+            _ => Err(Error::new_spanned(
+                value,
+                "unsupported `method_case`, expected one of \"PascalCase\", \"camelCase\", \
+                 \"snake_case\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\", \
+                 \"SCREAMING-KEBAB-CASE\", \"lowercase\" or \"UPPERCASE\"",
+            )),
+        }
+    }
+
+    /// Convert the snake_case `name` of a Rust method to this casing.
+    pub(super) fn convert(self, name: &str) -> String {
+        let words = || name.split('_').filter(|word| !word.is_empty());
+
+        match self {
+            Self::Pascal => snake_case_to_pascal_case(name),
+            Self::Camel => {
+                let pascal = snake_case_to_pascal_case(name);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_lowercase().chain(chars).collect(),
+                    None => pascal,
+                }
+            }
+            Self::Snake => words().collect::<Vec<_>>().join("_").to_lowercase(),
+            Self::ScreamingSnake => words().collect::<Vec<_>>().join("_").to_uppercase(),
+            Self::Kebab => words().collect::<Vec<_>>().join("-").to_lowercase(),
+            Self::ScreamingKebab => words().collect::<Vec<_>>().join("-").to_uppercase(),
+            Self::Lower => words().collect::<String>().to_lowercase(),
+            Self::Upper => words().collect::<String>().to_uppercase(),
+        }
+    }
+}
+
 /// Information about a method argument.
 pub(super) struct ArgInfo<'a> {
     pub name: &'a syn::Ident,
@@ -147,38 +147,6 @@ pub(super) fn parse_rename_value(expr: &Expr) -> Result<Option<String>, Error> {
     }
 }
 
-/// Extract parameter rename attribute from zlink attributes and remove processed attributes.
-pub(super) fn extract_param_rename_attr(
-    attrs: &mut Vec<Attribute>,
-) -> Result<Option<String>, Error> {
-    let rename_result = extract_zlink_attrs(attrs, |meta_items| {
-        let mut rename_value = None;
-
-        for meta in meta_items {
-            match &meta {
-                Meta::NameValue(nv) if nv.path.is_ident("rename") => {
-                    if rename_value.is_some() {
-                        return Err(Error::new_spanned(
-                            &meta,
-                            "duplicate `rename` attribute on parameter",
-                        ));
-                    }
-                    rename_value = parse_rename_value(&nv.value)?;
-                }
-                _ => {
-                    return Err(Error::new_spanned(
-                        &meta,
-                        "unknown zlink attribute on parameter",
-                    ));
-                }
-            }
-        }
-
-        Ok(rename_value)
-    });
-    Ok(rename_result.unwrap_or(None))
-}
-
 /// Build a combined where clause from existing constraints, new constraint, and generic bounds.
 pub(super) fn build_combined_where_clause(
     existing: Option<syn::WhereClause>,
@@ -9,11 +9,19 @@ mod basic;
 mod complex_lifetimes;
 #[path = "proxy/generics.rs"]
 mod generics;
+#[path = "proxy/impl_for.rs"]
+mod impl_for;
 #[path = "proxy/lifetimes.rs"]
 mod lifetimes;
+#[path = "proxy/method_case.rs"]
+mod method_case;
 #[path = "proxy/optional_params.rs"]
 mod optional_params;
+#[path = "proxy/output.rs"]
+mod output;
 #[path = "proxy/rename.rs"]
 mod rename;
+#[path = "proxy/skip.rs"]
+mod skip;
 #[path = "proxy/streaming.rs"]
 mod streaming;
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use zlink::{connection::socket::Socket, proxy, test_utils::mock_socket::MockSocket, Connection};
+
+#[proxy(interface = "org.example.Wrapped", impl_for = "Wrapper<S>")]
+trait WrappedProxy {
+    async fn get_count(&mut self) -> zlink::Result<Result<Count, WrappedError>>;
+    #[zlink(oneway)]
+    async fn reset(&mut self) -> zlink::Result<()>;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Count {
+    count: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "error")]
+enum WrappedError {
+    Unavailable,
+}
+
+// A connection wrapper, e.g. a connection checked out of a pool.
+struct Wrapper<S: Socket> {
+    connection: Connection<S>,
+    uses: usize,
+}
+
+impl<S: Socket> AsMut<Connection<S>> for Wrapper<S> {
+    fn as_mut(&mut self) -> &mut Connection<S> {
+        self.uses += 1;
+        &mut self.connection
+    }
+}
+
+#[tokio::test]
+async fn proxy_through_wrapper() {
+    let responses = [r#"{"parameters":{"count":42}}"#];
+    let socket = MockSocket::new(&responses);
+    let mut wrapper = Wrapper {
+        connection: Connection::new(socket),
+        uses: 0,
+    };
+
+    let count = wrapper.get_count().await.unwrap().unwrap();
+    assert_eq!(count.count, 42);
+    wrapper.reset().await.unwrap();
+    assert_eq!(wrapper.uses, 2);
+
+    // The trait is still implemented for `Connection` itself.
+    let responses = [r#"{"parameters":{"count":7}}"#];
+    let mut conn = Connection::new(MockSocket::new(&responses));
+    let count = conn.get_count().await.unwrap().unwrap();
+    assert_eq!(count.count, 7);
+}
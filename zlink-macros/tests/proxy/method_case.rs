@@ -0,0 +1,42 @@
+#[tokio::test]
+async fn method_case_test() {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use zlink::{proxy, test_utils::mock_socket::MockSocket, Connection};
+
+    #[proxy(interface = "org.example.MethodCase", method_case = "camelCase")]
+    trait MethodCaseProxy {
+        async fn get_status(&mut self) -> zlink::Result<Result<(), Error>>;
+
+        // An explicit rename takes precedence.
+        #[zlink(rename = "GetHTTPStatus")]
+        async fn get_http_status(&mut self) -> zlink::Result<Result<(), Error>>;
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Error;
+
+    // Test get_status with the custom casing
+    let responses = json!({}).to_string();
+    let socket = MockSocket::new(&[&responses]);
+    let mut conn = Connection::new(socket);
+
+    conn.get_status().await.unwrap().unwrap();
+
+    let bytes_written = conn.write().write_half().written_data();
+    let written: serde_json::Value =
+        serde_json::from_slice(&bytes_written[..bytes_written.len() - 1]).unwrap();
+    assert_eq!(written["method"], "org.example.MethodCase.getStatus");
+
+    // Test get_http_status with the explicit rename
+    let responses = json!({}).to_string();
+    let socket = MockSocket::new(&[&responses]);
+    let mut conn = Connection::new(socket);
+
+    conn.get_http_status().await.unwrap().unwrap();
+
+    let bytes_written = conn.write().write_half().written_data();
+    let written: serde_json::Value =
+        serde_json::from_slice(&bytes_written[..bytes_written.len() - 1]).unwrap();
+    assert_eq!(written["method"], "org.example.MethodCase.GetHTTPStatus");
+}
@@ -56,3 +56,42 @@ async fn optional_params_test() {
         .unwrap()
         .unwrap();
 }
+
+#[tokio::test]
+async fn omitted_optional_params_not_serialized() {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use zlink::{proxy, test_utils::mock_socket::MockSocket, Connection};
+
+    type MaybeLabel = Option<String>;
+
+    #[proxy("org.example.Optional")]
+    trait OptionalProxy {
+        async fn set_label(
+            &mut self,
+            id: i32,
+            #[zlink(default)] label: MaybeLabel,
+            #[zlink(rename = "maxLength")] max_length: Option<i32>,
+        ) -> zlink::Result<Result<(), Error>>;
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Error;
+
+    let responses = json!({}).to_string();
+    let socket = MockSocket::new(&[&responses]);
+    let mut conn = Connection::new(socket);
+
+    conn.set_label(1, None, None).await.unwrap().unwrap();
+
+    let bytes_written = conn.write().write_half().written_data();
+    let written: serde_json::Value =
+        serde_json::from_slice(&bytes_written[..bytes_written.len() - 1]).unwrap();
+    let expected = json!({
+        "method": "org.example.Optional.SetLabel",
+        "parameters": {
+            "id": 1,
+        }
+    });
+    assert_eq!(written, expected);
+}
@@ -0,0 +1,57 @@
+use futures_util::TryStreamExt;
+
+#[tokio::test]
+async fn output_test() {
+    use futures_util::stream::Stream;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use zlink::{proxy, test_utils::mock_socket::MockSocket, Connection};
+
+    #[proxy("org.example.Output")]
+    trait OutputProxy {
+        #[zlink(output = "user")]
+        async fn get_user(&mut self, id: i64) -> zlink::Result<Result<User, Error>>;
+
+        #[zlink(output = "user", more)]
+        async fn watch_user(
+            &mut self,
+            id: i64,
+        ) -> zlink::Result<impl Stream<Item = zlink::Result<Result<User, Error>>>>;
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct User {
+        id: i64,
+        name: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Error;
+
+    // The value of the `user` parameter is returned directly.
+    let responses = json!({"parameters": {"user": {"id": 1, "name": "Alice"}}}).to_string();
+    let socket = MockSocket::new(&[&responses]);
+    let mut conn = Connection::new(socket);
+
+    let user = conn.get_user(1).await.unwrap().unwrap();
+    assert_eq!(user.id, 1);
+    assert_eq!(user.name, "Alice");
+
+    // Same for streaming methods.
+    let responses = [
+        json!({"continues": true, "parameters": {"user": {"id": 1, "name": "Alice"}}}).to_string(),
+        json!({"continues": false, "parameters": {"user": {"id": 1, "name": "Bob"}}}).to_string(),
+    ];
+    let socket = MockSocket::new(&responses.iter().map(|s| s.as_str()).collect::<Vec<_>>());
+    let mut conn = Connection::new(socket);
+
+    let stream = conn.watch_user(1).await.unwrap();
+    futures_util::pin_mut!(stream);
+    let users = stream
+        .try_collect::<Vec<Result<User, Error>>>()
+        .await
+        .unwrap();
+    assert_eq!(users.len(), 2);
+    assert_eq!(users[0].as_ref().unwrap().name, "Alice");
+    assert_eq!(users[1].as_ref().unwrap().name, "Bob");
+}
@@ -0,0 +1,49 @@
+#[tokio::test]
+async fn skip_test() {
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use zlink::{proxy, test_utils::mock_socket::MockSocket, Connection};
+
+    #[proxy("org.example.Skip")]
+    trait SkipProxy {
+        async fn get_value(&mut self) -> zlink::Result<Result<Value, Error>>;
+
+        #[zlink(skip)]
+        async fn get_doubled_value(&mut self) -> zlink::Result<Result<i64, Error>> {
+            let value = self.get_value().await?;
+
+            Ok(value.map(|value| value.value * 2))
+        }
+
+        async fn reset(&mut self) -> zlink::Result<Result<(), Error>>;
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Value {
+        value: i64,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Error;
+
+    let responses = [
+        json!({"parameters": {"value": 21}}).to_string(),
+        json!({}).to_string(),
+    ];
+    let socket = MockSocket::new(&[&responses[0], &responses[1]]);
+    let mut conn = Connection::new(socket);
+
+    // The helper method calls the generated one.
+    assert_eq!(conn.get_doubled_value().await.unwrap().unwrap(), 42);
+    conn.reset().await.unwrap().unwrap();
+
+    let bytes_written = conn.write().write_half().written_data();
+    let methods: Vec<serde_json::Value> = bytes_written
+        .split(|b| *b == b'\0')
+        .filter(|message| !message.is_empty())
+        .map(|message| serde_json::from_slice(message).unwrap())
+        .collect();
+    assert_eq!(methods.len(), 2);
+    assert_eq!(methods[0]["method"], "org.example.Skip.GetValue");
+    assert_eq!(methods[1]["method"], "org.example.Skip.Reset");
+}
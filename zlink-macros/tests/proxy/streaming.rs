@@ -93,3 +93,59 @@ async fn streaming_test() {
     assert_eq!(items[1].as_ref().unwrap().id, 2);
     assert_eq!(items[2].as_ref().unwrap().id, 3);
 }
+
+#[tokio::test]
+async fn coalesced_streaming_and_oneway_test() {
+    use futures_util::stream::Stream;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use std::time::Duration;
+    use zlink::{proxy, test_utils::mock_socket::MockSocket, Connection};
+
+    #[proxy("org.example.Stream")]
+    trait StreamProxy {
+        #[zlink(more)]
+        async fn get_stream(
+            &mut self,
+        ) -> zlink::Result<impl Stream<Item = zlink::Result<Result<Item, Error>>>>;
+
+        #[zlink(oneway)]
+        async fn notify(&mut self, message: &str) -> zlink::Result<()>;
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct Item {
+        id: u32,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Error;
+
+    let responses = [
+        json!({"continues": true, "parameters": {"id": 1}}).to_string(),
+        json!({"continues": false, "parameters": {"id": 2}}).to_string(),
+    ];
+    let socket = MockSocket::new(&responses.iter().map(|s| s.as_str()).collect::<Vec<_>>());
+    let mut conn = Connection::new(socket);
+    // Long enough that nothing is written because the duration elapsed.
+    conn.set_coalesce(Some(Duration::from_secs(3600)));
+
+    // Neither call is held back by coalescing.
+    conn.notify("hello").await.unwrap();
+    let written = std::str::from_utf8(conn.write().write_half().written_data()).unwrap();
+    assert!(written.contains(r#""method":"org.example.Stream.Notify""#));
+
+    {
+        let stream = conn.get_stream().await.unwrap();
+        futures_util::pin_mut!(stream);
+        let items = stream
+            .try_collect::<Vec<Result<Item, Error>>>()
+            .await
+            .unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].as_ref().unwrap().id, 2);
+    }
+
+    let written = std::str::from_utf8(conn.write().write_half().written_data()).unwrap();
+    assert!(written.contains(r#""method":"org.example.Stream.GetStream""#));
+}
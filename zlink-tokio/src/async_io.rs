@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use crate::{connection::socket, timeout::read_timeout, Result};
+use tokio::io::{self, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// A [`Socket`](socket::Socket) implementation over any asynchronous I/O stream.
+///
+/// This allows using any type implementing [`AsyncRead`] and [`AsyncWrite`] as the transport,
+/// e.g a TLS stream, a pipe or an SSH channel.
+#[derive(Debug)]
+pub struct AsyncIoSocket<T> {
+    stream: T,
+    read_timeout: Option<Duration>,
+}
+
+impl<T> AsyncIoSocket<T>
+where
+    T: AsyncRead + AsyncWrite + core::fmt::Debug,
+{
+    /// Create a new socket over the given stream.
+    pub fn new(stream: T) -> Self {
+        Self {
+            stream,
+            read_timeout: None,
+        }
+    }
+
+    /// The underlying stream.
+    pub fn inner(&self) -> &T {
+        &self.stream
+    }
+
+    /// Convert the socket into the underlying stream.
+    pub fn into_inner(self) -> T {
+        self.stream
+    }
+
+    /// The maximum time to wait for data to read, if any.
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read_timeout
+    }
+
+    /// Set the maximum time to wait for data to read.
+    ///
+    /// If no data arrives within `timeout`, reading fails with [`crate::Error::Timeout`], e.g
+    /// [`crate::Connection::receive_call`] when a client stays silent. By default, reads wait
+    /// indefinitely.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.read_timeout = timeout;
+    }
+}
+
+impl<T> socket::Socket for AsyncIoSocket<T>
+where
+    T: AsyncRead + AsyncWrite + core::fmt::Debug,
+{
+    type ReadHalf = AsyncIoReadHalf<T>;
+    type WriteHalf = AsyncIoWriteHalf<T>;
+
+    fn split(self) -> (Self::ReadHalf, Self::WriteHalf) {
+        let (read, write) = io::split(self.stream);
+
+        (
+            AsyncIoReadHalf {
+                half: read,
+                timeout: self.read_timeout,
+            },
+            AsyncIoWriteHalf(write),
+        )
+    }
+}
+
+impl<T> From<T> for AsyncIoSocket<T>
+where
+    T: AsyncRead + AsyncWrite + core::fmt::Debug,
+{
+    fn from(stream: T) -> Self {
+        Self::new(stream)
+    }
+}
+
+/// The [`ReadHalf`](socket::ReadHalf) implementation for [`AsyncIoSocket`].
+#[derive(Debug)]
+pub struct AsyncIoReadHalf<T> {
+    half: io::ReadHalf<T>,
+    timeout: Option<Duration>,
+}
+
+impl<T> socket::ReadHalf for AsyncIoReadHalf<T>
+where
+    T: AsyncRead + core::fmt::Debug,
+{
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        read_timeout(self.timeout, self.half.read(buf)).await
+    }
+}
+
+/// The [`WriteHalf`](socket::WriteHalf) implementation for [`AsyncIoSocket`].
+#[derive(Debug)]
+pub struct AsyncIoWriteHalf<T>(io::WriteHalf<T>);
+
+impl<T> socket::WriteHalf for AsyncIoWriteHalf<T>
+where
+    T: AsyncWrite + core::fmt::Debug,
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<()> {
+        self.0.write_all(buf).await?;
+        // Streams like TLS buffer the written data internally.
+        self.0.flush().await.map_err(Into::into)
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        self.0.shutdown().await.map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Call, Connection, ReplyError};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    #[serde(tag = "method", content = "parameters")]
+    enum Methods {
+        #[serde(rename = "org.example.echo.Echo")]
+        Echo { value: u32 },
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct EchoReply {
+        value: u32,
+    }
+
+    #[derive(Debug, ReplyError)]
+    #[zlink(interface = "org.example.echo")]
+    enum EchoError {}
+
+    #[tokio::test]
+    async fn duplex_round_trip() {
+        let (client, server) = io::duplex(64);
+        let mut client = Connection::new(AsyncIoSocket::new(client));
+        let mut server = Connection::new(AsyncIoSocket::new(server));
+
+        let server_task = tokio::spawn(async move {
+            let call = server.receive_call::<Methods>().await.unwrap();
+            let Methods::Echo { value } = call.method();
+            let reply = crate::Reply::new(Some(EchoReply { value: *value }));
+            server.send_reply(&reply).await.unwrap();
+        });
+
+        let call = Call::new(Methods::Echo { value: 42 });
+        let reply = client
+            .call_method::<_, EchoReply, EchoError>(&call)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply.parameters().unwrap().value, 42);
+
+        server_task.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn read_timeout_on_silent_peer() {
+        // The peer is kept alive but never sends anything.
+        let (client, _server) = io::duplex(64);
+        let mut socket = AsyncIoSocket::new(client);
+        socket.set_read_timeout(Some(std::time::Duration::from_secs(1)));
+        let mut conn = Connection::new(socket);
+
+        let res = conn.receive_call::<Methods>().await;
+        assert!(matches!(res, Err(crate::Error::Timeout)));
+    }
+}
@@ -0,0 +1,192 @@
+//! Streaming of large payloads as a sequence of replies.
+//!
+//! A method that returns a large payload (e.g the contents of a file) can use [`ChunkStream`] as
+//! its reply stream, so that the payload is sent in [`Chunk`]s without having to be read into
+//! memory as a whole. The client is expected to call the method with the `more` flag set, and
+//! reassemble the payload from the [`Chunk`]s it receives, until a reply without the `continues`
+//! flag.
+
+use std::{
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, ReadBuf};
+
+use crate::Reply;
+
+/// A chunk of a payload streamed by a [`ChunkStream`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chunk {
+    data: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Chunk {
+    /// The base64-encoded bytes of the chunk.
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// The error that ended the payload prematurely, if any.
+    ///
+    /// Only the last chunk of a payload can have an error, in which case the payload is
+    /// incomplete.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// The decoded bytes of the chunk.
+    ///
+    /// Fails if the chunk carries an [error](Self::error), or if the data is not valid base64.
+    pub fn decode(&self) -> io::Result<Vec<u8>> {
+        if let Some(error) = &self.error {
+            return Err(io::Error::other(error.clone()));
+        }
+
+        STANDARD
+            .decode(&self.data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// A stream of replies carrying the payload read from an [`AsyncRead`] in [`Chunk`]s.
+///
+/// Each reply carries at most the chunk size of bytes, base64-encoded. All replies have the
+/// `continues` flag set, except for the last one, which is sent once the end of the payload is
+/// reached. Hence the last chunk may be empty.
+///
+/// If reading fails, the stream ends with a chunk carrying the bytes read so far and the
+/// [error](Chunk::error), so that the client can tell an incomplete payload from a complete one.
+/// [`Chunk::decode`] fails for such a chunk.
+#[derive(Debug)]
+pub struct ChunkStream<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    filled: usize,
+    finished: bool,
+}
+
+impl<R> ChunkStream<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Create a stream of the payload read from `reader`, with the default chunk size of 12 KiB.
+    pub fn new(reader: R) -> Self {
+        Self::with_chunk_size(reader, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Create a stream of the payload read from `reader`, with chunks of `chunk_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `chunk_size` is 0.
+    pub fn with_chunk_size(reader: R, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "`chunk_size` must not be 0");
+
+        Self {
+            reader,
+            buffer: vec![0; chunk_size],
+            filled: 0,
+            finished: false,
+        }
+    }
+}
+
+impl<R> Stream for ChunkStream<R>
+where
+    R: AsyncRead + Unpin,
+{
+    type Item = Reply<Chunk>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.finished {
+            return Poll::Ready(None);
+        }
+
+        // Fill up a whole chunk, unless the end of the payload is reached or reading fails.
+        let mut end = false;
+        let mut error = None;
+        while this.filled < this.buffer.len() {
+            let mut buf = ReadBuf::new(&mut this.buffer[this.filled..]);
+            match ready!(Pin::new(&mut this.reader).poll_read(cx, &mut buf)) {
+                Ok(()) if buf.filled().is_empty() => end = true,
+                Ok(()) => this.filled += buf.filled().len(),
+                Err(e) => {
+                    end = true;
+                    error = Some(e.to_string());
+                }
+            }
+            if end {
+                break;
+            }
+        }
+
+        let data = STANDARD.encode(&this.buffer[..this.filled]);
+        this.filled = 0;
+        this.finished = end;
+
+        Poll::Ready(Some(
+            Reply::new(Some(Chunk { data, error })).set_continues(Some(!end)),
+        ))
+    }
+}
+
+// A multiple of 3, so that no chunk but the last one needs base64 padding.
+const DEFAULT_CHUNK_SIZE: usize = 12 * 1024;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+
+    // Yields `data` and then fails.
+    struct FailingReader(&'static [u8]);
+
+    impl AsyncRead for FailingReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            if self.0.is_empty() {
+                return Poll::Ready(Err(io::Error::other("disk on fire")));
+            }
+            let len = self.0.len().min(buf.remaining());
+            buf.put_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn read_error() {
+        let mut stream = ChunkStream::with_chunk_size(FailingReader(b"foobar"), 3);
+
+        let reply = stream.next().await.unwrap();
+        assert_eq!(reply.continues(), Some(true));
+        let chunk = reply.parameters().unwrap();
+        assert_eq!(chunk.decode().unwrap(), b"foo");
+        assert_eq!(chunk.error(), None);
+
+        let reply = stream.next().await.unwrap();
+        assert_eq!(reply.continues(), Some(true));
+        assert_eq!(reply.parameters().unwrap().decode().unwrap(), b"bar");
+
+        // The error must not pass for the end of the payload.
+        let reply = stream.next().await.unwrap();
+        assert_eq!(reply.continues(), Some(false));
+        let chunk = reply.parameters().unwrap();
+        assert_eq!(chunk.error(), Some("disk on fire"));
+        assert!(chunk.decode().is_err());
+
+        assert!(stream.next().await.is_none());
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use tokio::{
+    sync::Semaphore,
+    task::{spawn_local, LocalSet},
+};
+
+use crate::{serve_connection, Interceptor, Listener, Result, Service};
+
+/// A server that serves its connections concurrently.
+///
+/// Unlike [`crate::Server`], which handles all method calls in a single loop, this server serves
+/// each connection in a separate local task, so a slow method call on one connection doesn't
+/// hold up the others. The method calls received over the same connection are still handled one
+/// after the other, in the order they're received, as their replies must be sent in that order.
+///
+/// The number of connections served at the same time is limited by
+/// [`ConcurrentServer::with_max_concurrency`]. Further connections are only accepted once one of
+/// the connections being served is closed.
+///
+/// # Service state
+///
+/// **Each connection is served by its own clone of the service (and interceptor).** Any state
+/// stored directly in the service is therefore per-connection: changes made while handling a call
+/// on one connection are not seen by the other connections. State that must be shared between
+/// connections needs to be behind a shared reference, e.g an [`Arc`] of a
+/// [`Mutex`](std::sync::Mutex) or an [`Rc`](std::rc::Rc) of a
+/// [`RefCell`](std::cell::RefCell), since the connections are all served on the same thread.
+#[derive(Debug)]
+pub struct ConcurrentServer<L, S, I = ()> {
+    listener: L,
+    service: S,
+    interceptor: I,
+    max_concurrency: usize,
+}
+
+impl<L, S> ConcurrentServer<L, S>
+where
+    L: Listener,
+    S: Service + Clone + 'static,
+{
+    /// Create a new server that serves `service` to incomming connections from `listener`.
+    pub fn new(listener: L, service: S) -> Self {
+        Self {
+            listener,
+            service,
+            interceptor: (),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+        }
+    }
+}
+
+impl<L, S, I> ConcurrentServer<L, S, I>
+where
+    L: Listener,
+    L::Socket: 'static,
+    S: Service + Clone + 'static,
+    I: Interceptor<S> + Clone + 'static,
+{
+    /// Set the interceptor for all the replies sent by the server.
+    ///
+    /// See [`crate::interceptor::Interceptor`] for details.
+    pub fn with_interceptor<I2>(self, interceptor: I2) -> ConcurrentServer<L, S, I2>
+    where
+        I2: Interceptor<S> + Clone + 'static,
+    {
+        ConcurrentServer {
+            listener: self.listener,
+            service: self.service,
+            interceptor,
+            max_concurrency: self.max_concurrency,
+        }
+    }
+
+    /// Set the maximum number of connections served at the same time.
+    ///
+    /// Defaults to 16.
+    ///
+    /// # Panics
+    ///
+    /// If `max_concurrency` is 0.
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        assert!(max_concurrency > 0, "`max_concurrency` must not be 0");
+        self.max_concurrency = max_concurrency;
+
+        self
+    }
+
+    /// Run the server.
+    ///
+    /// The connections are served in tasks of a [`LocalSet`] that is driven by the returned
+    /// future, so the service doesn't need to be `Send`. As with [`crate::Server::run`], the
+    /// returned future itself can not be spawned in a task of a multi-threaded runtime.
+    pub async fn run(self) -> Result<()> {
+        let tasks = LocalSet::new();
+
+        tasks.run_until(self.accept_connections()).await
+    }
+
+    async fn accept_connections(mut self) -> Result<()> {
+        let permits = Arc::new(Semaphore::new(self.max_concurrency));
+
+        loop {
+            // The semaphore is never closed so this can't fail.
+            let permit = permits.clone().acquire_owned().await.unwrap();
+            let conn = self.listener.accept().await?;
+            let mut service = self.service.clone();
+            let mut interceptor = self.interceptor.clone();
+
+            spawn_local(async move {
+                serve_connection(conn, &mut service, &mut interceptor).await;
+                drop(permit);
+            });
+        }
+    }
+}
+
+const DEFAULT_MAX_CONCURRENCY: usize = 16;
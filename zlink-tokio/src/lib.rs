@@ -11,5 +11,14 @@
 #![cfg_attr(not(doctest), doc = include_str!("../README.md"))]
 
 pub use zlink_core::*;
+mod async_io;
+pub use async_io::{AsyncIoReadHalf, AsyncIoSocket, AsyncIoWriteHalf};
+pub mod chunked;
+mod concurrent_server;
+pub use concurrent_server::ConcurrentServer;
 pub mod notified;
+mod timeout;
+pub use timeout::Proxy;
+#[cfg(feature = "tls")]
+pub mod tls;
 pub mod unix;
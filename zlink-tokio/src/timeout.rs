@@ -0,0 +1,130 @@
+//! Timeouts for method calls.
+
+use std::{future::Future, time::Duration};
+
+use futures_util::{Stream, StreamExt};
+
+/// Timeouts for method calls through proxies.
+///
+/// The proxy traits generated by [`crate::proxy`] are implemented for [`crate::Connection`] and so
+/// is this trait. The `call` closure receives the connection and makes the call on it:
+///
+/// ```ignore
+/// use std::time::Duration;
+/// use zlink::{varlink_service::Proxy as _, Proxy as _};
+///
+/// let info = conn
+///     .call_timeout(Duration::from_secs(1), |conn| conn.get_info())
+///     .await?;
+/// ```
+///
+/// Note that after a timeout, the reply may still arrive later and be taken as the reply to the
+/// next call. Hence the connection should not be used for other calls afterwards.
+pub trait Proxy {
+    /// Call a method, failing with [`crate::Error::Timeout`] if it doesn't complete within
+    /// `duration`.
+    fn call_timeout<'c, F, Fut, T>(
+        &'c mut self,
+        duration: Duration,
+        call: F,
+    ) -> impl Future<Output = crate::Result<T>>
+    where
+        F: FnOnce(&'c mut Self) -> Fut,
+        Fut: Future<Output = crate::Result<T>> + 'c;
+
+    /// Call a method with the `more` flag, failing with [`crate::Error::Timeout`] if any of the
+    /// replies doesn't arrive within `duration`.
+    ///
+    /// The deadline applies to the call itself and then, separately, to each of the replies. The
+    /// returned stream ends after yielding the timeout error.
+    fn call_more_timeout<'c, F, Fut, S, T>(
+        &'c mut self,
+        duration: Duration,
+        call: F,
+    ) -> impl Future<Output = crate::Result<impl Stream<Item = crate::Result<T>> + 'c>>
+    where
+        F: FnOnce(&'c mut Self) -> Fut,
+        Fut: Future<Output = crate::Result<S>> + 'c,
+        S: Stream<Item = crate::Result<T>> + 'c,
+        T: 'c;
+}
+
+impl<Sock> Proxy for crate::Connection<Sock>
+where
+    Sock: crate::connection::Socket,
+{
+    fn call_timeout<'c, F, Fut, T>(
+        &'c mut self,
+        duration: Duration,
+        call: F,
+    ) -> impl Future<Output = crate::Result<T>>
+    where
+        F: FnOnce(&'c mut Self) -> Fut,
+        Fut: Future<Output = crate::Result<T>> + 'c,
+    {
+        with_timeout(duration, call(self))
+    }
+
+    fn call_more_timeout<'c, F, Fut, S, T>(
+        &'c mut self,
+        duration: Duration,
+        call: F,
+    ) -> impl Future<Output = crate::Result<impl Stream<Item = crate::Result<T>> + 'c>>
+    where
+        F: FnOnce(&'c mut Self) -> Fut,
+        Fut: Future<Output = crate::Result<S>> + 'c,
+        S: Stream<Item = crate::Result<T>> + 'c,
+        T: 'c,
+    {
+        more_with_timeout(duration, call(self))
+    }
+}
+
+/// Run `future`, failing with [`crate::Error::Timeout`] if it doesn't complete within `duration`.
+async fn with_timeout<F, T>(duration: Duration, future: F) -> crate::Result<T>
+where
+    F: Future<Output = crate::Result<T>>,
+{
+    tokio::time::timeout(duration, future)
+        .await
+        .unwrap_or(Err(crate::Error::Timeout))
+}
+
+/// Run `call` and then read the replies from the stream it returns, failing with
+/// [`crate::Error::Timeout`] if any of these doesn't complete within `duration`.
+async fn more_with_timeout<'s, F, S, T>(
+    duration: Duration,
+    call: F,
+) -> crate::Result<impl Stream<Item = crate::Result<T>> + 's>
+where
+    F: Future<Output = crate::Result<S>>,
+    S: Stream<Item = crate::Result<T>> + 's,
+    T: 's,
+{
+    let replies = Box::pin(with_timeout(duration, call).await?);
+
+    Ok(futures_util::stream::unfold(
+        Some(replies),
+        move |replies| async move {
+            let mut replies = replies?;
+            match tokio::time::timeout(duration, replies.next()).await {
+                Ok(Some(reply)) => Some((reply, Some(replies))),
+                Ok(None) => None,
+                Err(_) => Some((Err(crate::Error::Timeout), None)),
+            }
+        },
+    ))
+}
+
+/// Read through `read`, failing with [`crate::Error::Timeout`] if it doesn't complete within
+/// `timeout`, if any.
+pub(crate) async fn read_timeout<F>(timeout: Option<Duration>, read: F) -> crate::Result<usize>
+where
+    F: Future<Output = std::io::Result<usize>>,
+{
+    let read = async { read.await.map_err(Into::into) };
+    match timeout {
+        Some(duration) => with_timeout(duration, read).await,
+        None => read.await,
+    }
+}
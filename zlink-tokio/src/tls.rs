@@ -0,0 +1,242 @@
+//! Provides TLS-encrypted transport over TCP, using [`tokio_rustls`].
+
+use std::{
+    fmt,
+    future::Future,
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+};
+
+use crate::{AsyncIoSocket, Result};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::{lookup_host, TcpListener, TcpSocket, TcpStream, ToSocketAddrs},
+};
+use tokio_rustls::{
+    rustls::{pki_types::ServerName, ClientConfig, ServerConfig},
+    server, Accept, TlsAcceptor, TlsConnector, TlsStream,
+};
+
+pub use tokio_rustls::rustls;
+
+/// The [`Socket`](crate::connection::socket::Socket) implementation over TLS streams.
+pub type Stream = AsyncIoSocket<TlsStream<TcpStream>>;
+
+/// The connection type that uses TLS over TCP for transport.
+pub type Connection = crate::Connection<Stream>;
+
+/// The [`Socket`](crate::connection::socket::Socket) implementation of connections accepted by a
+/// [`TlsListener`].
+pub type ServerSocket = AsyncIoSocket<ServerStream>;
+
+/// The type of connections accepted by a [`TlsListener`].
+pub type ServerConnection = crate::Connection<ServerSocket>;
+
+/// Connect to the TLS server at the given address.
+///
+/// The `server_name` is used to verify the certificate of the server.
+pub async fn connect<A>(
+    addr: A,
+    server_name: ServerName<'static>,
+    client_config: Arc<ClientConfig>,
+) -> Result<Connection>
+where
+    A: ToSocketAddrs,
+{
+    let stream = TcpStream::connect(addr).await?;
+    let stream = TlsConnector::from(client_config)
+        .connect(server_name, stream)
+        .await?;
+
+    Ok(Connection::new(AsyncIoSocket::new(stream.into())))
+}
+
+/// Create a new TLS listener and bind it to `addr`.
+pub async fn bind<A>(addr: A, server_config: Arc<ServerConfig>) -> Result<TlsListener>
+where
+    A: ToSocketAddrs,
+{
+    let listener = TcpListener::bind(addr).await?;
+
+    Ok(TlsListener::new(listener, server_config))
+}
+
+/// Create a new TLS listener and bind it to `addr`, with the given listen `backlog`.
+///
+/// The backlog is the maximum number of pending connections, which the OS may cap. [`bind`] uses
+/// a default, which can be too small for services that many clients connect to at once.
+///
+/// If `addr` resolves to multiple addresses, they're tried in order until one succeeds.
+pub async fn bind_with_backlog<A>(
+    addr: A,
+    backlog: u32,
+    server_config: Arc<ServerConfig>,
+) -> Result<TlsListener>
+where
+    A: ToSocketAddrs,
+{
+    let mut last_err = None;
+    for addr in lookup_host(addr).await? {
+        let socket = if addr.is_ipv4() {
+            TcpSocket::new_v4()?
+        } else {
+            TcpSocket::new_v6()?
+        };
+        // Same as `TcpListener::bind`.
+        #[cfg(unix)]
+        socket.set_reuseaddr(true)?;
+
+        match socket.bind(addr).and_then(|_| socket.listen(backlog)) {
+            Ok(listener) => return Ok(TlsListener::new(listener, server_config)),
+            Err(e) => last_err = Some(e),
+        }
+    }
+
+    Err(last_err
+        .unwrap_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "could not resolve to any address",
+            )
+        })
+        .into())
+}
+
+/// A TLS listener.
+///
+/// Accepts TCP connections, which perform the TLS handshake on their first use (see
+/// [`ServerStream`]).
+#[derive(Debug)]
+pub struct TlsListener {
+    listener: TcpListener,
+    server_config: Arc<ServerConfig>,
+}
+
+impl TlsListener {
+    /// Create a new TLS listener from a TCP listener.
+    pub fn new(listener: TcpListener, server_config: Arc<ServerConfig>) -> Self {
+        Self {
+            listener,
+            server_config,
+        }
+    }
+}
+
+impl crate::Listener for TlsListener {
+    type Socket = ServerSocket;
+
+    async fn accept(&mut self) -> Result<crate::Connection<Self::Socket>> {
+        let (stream, _) = self.listener.accept().await?;
+        let handshake = TlsAcceptor::from(self.server_config.clone()).accept(stream);
+
+        Ok(ServerConnection::new(AsyncIoSocket::new(ServerStream {
+            state: ServerStreamState::Handshaking(handshake),
+        })))
+    }
+}
+
+impl crate::LocalAddr for TlsListener {
+    type Address = std::net::SocketAddr;
+
+    fn local_addr(&self) -> Result<Self::Address> {
+        self.listener.local_addr().map_err(Into::into)
+    }
+}
+
+/// A TLS stream accepted by a [`TlsListener`].
+///
+/// The TLS handshake is performed on the first read or write, rather than when the connection is
+/// accepted, so that a slow (or malicious) client can't hold up accepting other clients.
+pub struct ServerStream {
+    state: ServerStreamState,
+}
+
+enum ServerStreamState {
+    Handshaking(Accept<TcpStream>),
+    Ready(server::TlsStream<TcpStream>),
+    Failed,
+}
+
+impl ServerStream {
+    /// Whether the TLS handshake has been completed.
+    pub fn is_handshake_complete(&self) -> bool {
+        matches!(self.state, ServerStreamState::Ready(_))
+    }
+
+    fn poll_handshake(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<&mut server::TlsStream<TcpStream>>> {
+        if let ServerStreamState::Handshaking(handshake) = &mut self.state {
+            match ready!(Pin::new(handshake).poll(cx)) {
+                Ok(stream) => self.state = ServerStreamState::Ready(stream),
+                Err(e) => {
+                    self.state = ServerStreamState::Failed;
+
+                    return Poll::Ready(Err(e));
+                }
+            }
+        }
+
+        match &mut self.state {
+            ServerStreamState::Ready(stream) => Poll::Ready(Ok(stream)),
+            ServerStreamState::Failed => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "TLS handshake failed",
+            ))),
+            ServerStreamState::Handshaking(_) => unreachable!("handshake just completed"),
+        }
+    }
+}
+
+impl fmt::Debug for ServerStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match &self.state {
+            ServerStreamState::Handshaking(_) => "Handshaking",
+            ServerStreamState::Ready(_) => "Ready",
+            ServerStreamState::Failed => "Failed",
+        };
+
+        f.debug_struct("ServerStream")
+            .field("state", &state)
+            .finish()
+    }
+}
+
+impl AsyncRead for ServerStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let stream = ready!(self.get_mut().poll_handshake(cx))?;
+
+        Pin::new(stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for ServerStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let stream = ready!(self.get_mut().poll_handshake(cx))?;
+
+        Pin::new(stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let stream = ready!(self.get_mut().poll_handshake(cx))?;
+
+        Pin::new(stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let stream = ready!(self.get_mut().poll_handshake(cx))?;
+
+        Pin::new(stream).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,78 @@
+use tokio::net::unix::{gid_t, pid_t, uid_t, UCred};
+
+/// The credentials of the peer process of a Unix Domain Socket connection.
+///
+/// Use [`Stream::peer_credentials`](super::Stream::peer_credentials) to get them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerCredentials {
+    uid: uid_t,
+    gid: gid_t,
+    pid: Option<pid_t>,
+}
+
+impl PeerCredentials {
+    /// The user ID of the peer process.
+    pub fn uid(&self) -> uid_t {
+        self.uid
+    }
+
+    /// The group ID of the peer process.
+    pub fn gid(&self) -> gid_t {
+        self.gid
+    }
+
+    /// The process ID of the peer process, if available on this platform.
+    pub fn pid(&self) -> Option<pid_t> {
+        self.pid
+    }
+
+    /// The name of the peer process' executable, as reported by `/proc/<pid>/comm`.
+    ///
+    /// This is best-effort: `None` is returned on platforms other than Linux, if the PID is not
+    /// available or if the process has exited since the credentials were obtained. Note that the
+    /// kernel truncates the name to 15 bytes.
+    pub fn process_name(&self) -> Option<String> {
+        #[cfg(target_os = "linux")]
+        {
+            let pid = self.pid?;
+            let comm = std::fs::read_to_string(format!("/proc/{pid}/comm")).ok()?;
+
+            Some(comm.trim_end_matches('\n').to_string())
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        None
+    }
+}
+
+impl From<UCred> for PeerCredentials {
+    fn from(cred: UCred) -> Self {
+        Self {
+            uid: cred.uid(),
+            gid: cred.gid(),
+            pid: cred.pid(),
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn process_name_of_current_process() {
+        let credentials = PeerCredentials {
+            uid: 0,
+            gid: 0,
+            pid: Some(std::process::id() as pid_t),
+        };
+        let comm = std::fs::read_to_string("/proc/self/comm").unwrap();
+        assert_eq!(credentials.process_name().unwrap(), comm.trim_end());
+
+        let credentials = PeerCredentials {
+            pid: None,
+            ..credentials
+        };
+        assert!(credentials.process_name().is_none());
+    }
+}
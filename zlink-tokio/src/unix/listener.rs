@@ -1,4 +1,4 @@
-use std::os::fd::OwnedFd;
+use std::{os::fd::OwnedFd, time::Duration};
 
 use crate::{Connection, Result};
 
@@ -8,7 +8,26 @@ where
     P: AsRef<std::path::Path>,
 {
     tokio::net::UnixListener::bind(path)
-        .map(|listener| Listener { listener })
+        .map(Listener::new)
+        .map_err(Into::into)
+}
+
+/// Create a new unix domain socket listener and bind it to `path`, with the given listen
+/// `backlog`.
+///
+/// The backlog is the maximum number of pending connections, which the OS may cap. [`bind`] uses
+/// a default, which can be too small for services that many clients connect to at once, e.g when
+/// the system boots.
+pub fn bind_with_backlog<P>(path: P, backlog: u32) -> Result<Listener>
+where
+    P: AsRef<std::path::Path>,
+{
+    let socket = tokio::net::UnixSocket::new_stream()?;
+    socket.bind(path)?;
+
+    socket
+        .listen(backlog)
+        .map(Listener::new)
         .map_err(Into::into)
 }
 
@@ -16,6 +35,30 @@ where
 #[derive(Debug)]
 pub struct Listener {
     listener: tokio::net::UnixListener,
+    read_timeout: Option<Duration>,
+}
+
+impl Listener {
+    fn new(listener: tokio::net::UnixListener) -> Self {
+        Self {
+            listener,
+            read_timeout: None,
+        }
+    }
+
+    /// The maximum time to wait for data to read on the accepted connections, if any.
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read_timeout
+    }
+
+    /// Set the maximum time to wait for data to read on the accepted connections.
+    ///
+    /// This is applied to each accepted stream through [`super::Stream::set_read_timeout`], so a
+    /// server doesn't wait indefinitely for clients that stay silent. By default, reads wait
+    /// indefinitely.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.read_timeout = timeout;
+    }
 }
 
 impl crate::Listener for Listener {
@@ -25,11 +68,24 @@ impl crate::Listener for Listener {
         self.listener
             .accept()
             .await
-            .map(|(stream, _)| super::Stream::from(stream).into())
+            .map(|(stream, _)| {
+                let mut stream = super::Stream::from(stream);
+                stream.set_read_timeout(self.read_timeout);
+
+                stream.into()
+            })
             .map_err(Into::into)
     }
 }
 
+impl crate::LocalAddr for Listener {
+    type Address = tokio::net::unix::SocketAddr;
+
+    fn local_addr(&self) -> Result<Self::Address> {
+        self.listener.local_addr().map_err(Into::into)
+    }
+}
+
 impl TryFrom<OwnedFd> for Listener {
     type Error = crate::Error;
 
@@ -38,7 +94,7 @@ impl TryFrom<OwnedFd> for Listener {
         std_listener.set_nonblocking(true)?;
 
         tokio::net::UnixListener::from_std(std_listener)
-            .map(|listener| Listener { listener })
+            .map(Listener::new)
             .map_err(Into::into)
     }
 }
@@ -46,7 +102,7 @@ impl TryFrom<OwnedFd> for Listener {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::Listener as _;
+    use crate::{Listener as _, LocalAddr as _};
     use std::os::unix::net::UnixListener as StdUnixListener;
     use tempfile::TempDir;
 
@@ -117,4 +173,41 @@ mod tests {
 
         let _stream = connect_task.await.unwrap();
     }
+
+    #[tokio::test]
+    async fn local_addr() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("test5.sock");
+
+        let listener = bind(&socket_path).unwrap();
+        let addr = listener.local_addr().unwrap();
+        assert_eq!(addr.as_pathname(), Some(socket_path.as_path()));
+    }
+
+    #[tokio::test]
+    async fn backlog() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("test6.sock");
+
+        let listener = bind_with_backlog(&socket_path, 4096).unwrap();
+        let addr = listener.local_addr().unwrap();
+        assert_eq!(addr.as_pathname(), Some(socket_path.as_path()));
+    }
+
+    #[tokio::test]
+    async fn read_timeout() {
+        let temp_dir = TempDir::new().unwrap();
+        let socket_path = temp_dir.path().join("test7.sock");
+
+        let mut listener = bind(&socket_path).unwrap();
+        listener.set_read_timeout(Some(Duration::from_millis(100)));
+        assert_eq!(listener.read_timeout(), Some(Duration::from_millis(100)));
+
+        // The client is kept alive but never sends anything.
+        let _client = tokio::net::UnixStream::connect(&socket_path).await.unwrap();
+        let mut connection = listener.accept().await.unwrap();
+
+        let res = connection.receive_call::<serde::de::IgnoredAny>().await;
+        assert!(matches!(res, Err(crate::Error::Timeout)));
+    }
 }
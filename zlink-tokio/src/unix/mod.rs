@@ -1,6 +1,8 @@
 //! Provides transport over Unix Domain Sockets.
 
+mod credentials;
+pub use credentials::PeerCredentials;
 mod stream;
 pub use stream::{connect, Connection, Stream};
 mod listener;
-pub use listener::{bind, Listener};
+pub use listener::{bind, bind_with_backlog, Listener};
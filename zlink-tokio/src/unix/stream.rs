@@ -1,5 +1,9 @@
+use std::time::Duration;
+
+use super::PeerCredentials;
 use crate::{
     connection::socket::{self, Socket},
+    timeout::read_timeout,
     Result,
 };
 use tokio::{
@@ -17,39 +21,79 @@ where
 {
     UnixStream::connect(path)
         .await
-        .map(Stream)
+        .map(Stream::from)
         .map(Connection::new)
         .map_err(Into::into)
 }
 
 /// The [`Socket`] implementation using Unix Domain Sockets.
 #[derive(Debug)]
-pub struct Stream(UnixStream);
+pub struct Stream {
+    stream: UnixStream,
+    read_timeout: Option<Duration>,
+}
+
+impl Stream {
+    /// The credentials of the peer process.
+    ///
+    /// Since the stream is consumed by the connection, this needs to be called before creating it.
+    pub fn peer_credentials(&self) -> Result<PeerCredentials> {
+        self.stream.peer_cred().map(Into::into).map_err(Into::into)
+    }
+
+    /// The maximum time to wait for data to read, if any.
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read_timeout
+    }
+
+    /// Set the maximum time to wait for data to read.
+    ///
+    /// If no data arrives within `timeout`, reading fails with [`crate::Error::Timeout`], e.g
+    /// [`crate::Connection::receive_call`] when a client stays silent. By default, reads wait
+    /// indefinitely. Since the stream is consumed by the connection, this needs to be called
+    /// before creating it. For the streams accepted by a server, use
+    /// [`super::Listener::set_read_timeout`] instead.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.read_timeout = timeout;
+    }
+}
 
 impl Socket for Stream {
     type ReadHalf = ReadHalf;
     type WriteHalf = WriteHalf;
 
     fn split(self) -> (Self::ReadHalf, Self::WriteHalf) {
-        let (read, write) = self.0.into_split();
+        let (read, write) = self.stream.into_split();
 
-        (ReadHalf(read), WriteHalf(write))
+        (
+            ReadHalf {
+                half: read,
+                timeout: self.read_timeout,
+            },
+            WriteHalf(write),
+        )
     }
 }
 
 impl From<UnixStream> for Stream {
     fn from(stream: UnixStream) -> Self {
-        Self(stream)
+        Self {
+            stream,
+            read_timeout: None,
+        }
     }
 }
 
 /// The [`ReadHalf`] implementation using Unix Domain Sockets.
 #[derive(Debug)]
-pub struct ReadHalf(unix::OwnedReadHalf);
+pub struct ReadHalf {
+    half: unix::OwnedReadHalf,
+    timeout: Option<Duration>,
+}
 
 impl socket::ReadHalf for ReadHalf {
     async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        self.0.read(buf).await.map_err(Into::into)
+        read_timeout(self.timeout, self.half.read(buf)).await
     }
 }
 
@@ -68,4 +112,8 @@ impl socket::WriteHalf for WriteHalf {
 
         Ok(())
     }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        self.0.shutdown().await.map_err(Into::into)
+    }
 }
@@ -0,0 +1,84 @@
+mod common;
+
+use common::DuplexListener;
+use futures_util::{pin_mut, stream::StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{duplex, DuplexStream},
+    select,
+};
+use zlink::{
+    chunked::{Chunk, ChunkStream},
+    service::MethodReply,
+    AsyncIoSocket, Call, Connection, ReplyError, Service,
+};
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn stream_reader_in_chunks() -> Result<(), Box<dyn std::error::Error>> {
+    let (client, server) = duplex(1024);
+    let listener = DuplexListener::new([server]);
+    let server = zlink::Server::new(listener, Files);
+    select! {
+        res = server.run() => res?,
+        res = run_client(client) => res?,
+    }
+
+    Ok(())
+}
+
+async fn run_client(stream: DuplexStream) -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = Connection::new(AsyncIoSocket::new(stream));
+
+    let call = Call::new(Method::Read).set_more(true);
+    let replies = conn
+        .chain_call::<_, Chunk, FilesError>(&call)?
+        .send()
+        .await?;
+    pin_mut!(replies);
+
+    let mut payload = Vec::new();
+    let mut chunks = 0;
+    while let Some(reply) = replies.next().await {
+        let reply = reply?.unwrap();
+        payload.extend(reply.parameters().unwrap().decode().unwrap());
+        chunks += 1;
+    }
+    assert_eq!(payload, PAYLOAD);
+    // 10 full chunks and a final one with the remaining 4 bytes.
+    assert_eq!(chunks, 11);
+
+    Ok(())
+}
+
+const PAYLOAD: &[u8] = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do.";
+const CHUNK_SIZE: usize = 6;
+
+struct Files;
+
+impl Service for Files {
+    type MethodCall<'de> = Method;
+    type ReplyParams<'ser> = Chunk;
+    type ReplyStream = ChunkStream<&'static [u8]>;
+    type ReplyStreamParams = Chunk;
+    type ReplyError<'ser> = FilesError;
+
+    async fn handle<'ser>(
+        &'ser mut self,
+        call: Call<Self::MethodCall<'_>>,
+    ) -> MethodReply<Self::ReplyParams<'ser>, Self::ReplyStream, Self::ReplyError<'ser>> {
+        match call.method() {
+            Method::Read => MethodReply::Multi(ChunkStream::with_chunk_size(PAYLOAD, CHUNK_SIZE)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method")]
+enum Method {
+    #[serde(rename = "org.example.files.Read")]
+    Read,
+}
+
+#[derive(Debug, ReplyError)]
+#[zlink(interface = "org.example.files")]
+enum FilesError {}
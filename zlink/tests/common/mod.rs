@@ -0,0 +1,38 @@
+//! Helpers shared by the integration tests.
+
+use tokio::io::DuplexStream;
+use zlink::{AsyncIoSocket, Connection};
+
+/// A listener handing out the server ends of in-memory duplex streams, in order.
+///
+/// Once all the streams are handed out, accepting never completes.
+#[derive(Debug)]
+pub struct DuplexListener(Vec<DuplexStream>);
+
+impl DuplexListener {
+    /// Create a listener handing out `streams`.
+    pub fn new(streams: impl IntoIterator<Item = DuplexStream>) -> Self {
+        Self(streams.into_iter().collect())
+    }
+}
+
+impl zlink::Listener for DuplexListener {
+    type Socket = AsyncIoSocket<DuplexStream>;
+
+    async fn accept(&mut self) -> zlink::Result<Connection<Self::Socket>> {
+        if self.0.is_empty() {
+            // No more connections.
+            return futures_util::future::pending().await;
+        }
+
+        Ok(Connection::new(AsyncIoSocket::new(self.0.remove(0))))
+    }
+}
+
+impl zlink::LocalAddr for DuplexListener {
+    type Address = ();
+
+    fn local_addr(&self) -> zlink::Result<Self::Address> {
+        Ok(())
+    }
+}
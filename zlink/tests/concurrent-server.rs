@@ -0,0 +1,93 @@
+use std::{sync::Arc, time::Duration};
+
+use futures_util::stream::Empty;
+use serde::{Deserialize, Serialize};
+use tokio::{select, sync::Barrier, time::timeout};
+use zlink::{
+    service::MethodReply,
+    unix::{bind, connect},
+    varlink_service, Call, ConcurrentServer, Reply, Service,
+};
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn concurrent_slow_calls() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let socket_path = dir.path().join("zlink-test-concurrent-server.sock");
+
+    let listener = bind(&socket_path)?;
+    // Each call only completes once both calls are being handled, so the clients would never get
+    // their replies if the calls were handled one after the other.
+    let service = Waiter {
+        barrier: Arc::new(Barrier::new(2)),
+    };
+    let server = ConcurrentServer::new(listener, service).with_max_concurrency(2);
+    select! {
+        res = server.run() => res?,
+        res = timeout(TIMEOUT, run_clients(&socket_path)) => res??,
+    }
+
+    Ok(())
+}
+
+async fn run_clients(socket_path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    let (first, second) = tokio::join!(run_client(socket_path), run_client(socket_path));
+    first?;
+    second?;
+
+    Ok(())
+}
+
+async fn run_client(socket_path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = connect(socket_path).await?;
+
+    let reply = conn
+        .call_method::<_, Waited, varlink_service::Error>(&Call::new(Method::Wait))
+        .await??;
+    assert!(reply.into_parameters().unwrap().waited);
+
+    Ok(())
+}
+
+// Only there to fail the test instead of hanging, if the calls are not handled concurrently.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+struct Waiter {
+    // Shared between the clones of the service serving each connection.
+    barrier: Arc<Barrier>,
+}
+
+impl Service for Waiter {
+    const INTERFACE: Option<&'static str> = Some("org.example.waiter");
+
+    type MethodCall<'de> = Method;
+    type ReplyParams<'ser> = Waited;
+    type ReplyStream = Empty<Reply<Waited>>;
+    type ReplyStreamParams = Waited;
+    type ReplyError<'ser> = varlink_service::Error;
+
+    async fn handle<'ser>(
+        &'ser mut self,
+        call: Call<Self::MethodCall<'_>>,
+    ) -> MethodReply<Self::ReplyParams<'ser>, Self::ReplyStream, Self::ReplyError<'ser>> {
+        match call.method() {
+            Method::Wait => {
+                self.barrier.wait().await;
+
+                MethodReply::Single(Some(Waited { waited: true }))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method")]
+enum Method {
+    #[serde(rename = "org.example.waiter.Wait")]
+    Wait,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Waited {
+    waited: bool,
+}
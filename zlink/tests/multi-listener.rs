@@ -0,0 +1,85 @@
+mod common;
+
+use common::DuplexListener;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{duplex, DuplexStream},
+    select,
+};
+use zlink::{
+    service::MethodReply, AsyncIoSocket, Call, Connection, MultiListener, ReplyError, Service,
+};
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn accept_on_multiple_listeners() -> Result<(), Box<dyn std::error::Error>> {
+    let (first, first_server) = duplex(1024);
+    let (second, second_server) = duplex(1024);
+    let mut listener = MultiListener::new([DuplexListener::new([first_server])]);
+    listener.add_listener(DuplexListener::new([second_server]));
+    assert_eq!(zlink::LocalAddr::local_addr(&listener)?, vec![(), ()]);
+
+    let server = zlink::Server::new(listener, Pinger);
+    select! {
+        res = server.run() => res?,
+        res = run_clients(first, second) => res?,
+    }
+
+    Ok(())
+}
+
+async fn run_clients(
+    first: DuplexStream,
+    second: DuplexStream,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Both connections are served by the same server.
+    let (first, second) = tokio::join!(ping(first), ping(second));
+    first?;
+    second?;
+
+    Ok(())
+}
+
+async fn ping(stream: DuplexStream) -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = Connection::new(AsyncIoSocket::new(stream));
+    let reply = conn
+        .call_method::<_, Pong, PingError>(&Call::new(Method::Ping))
+        .await??;
+    assert!(reply.parameters().unwrap().pong);
+
+    Ok(())
+}
+
+struct Pinger;
+
+impl Service for Pinger {
+    type MethodCall<'de> = Method;
+    type ReplyParams<'ser> = Pong;
+    type ReplyStream = futures_util::stream::Empty<zlink::Reply<Pong>>;
+    type ReplyStreamParams = Pong;
+    type ReplyError<'ser> = PingError;
+
+    async fn handle<'ser>(
+        &'ser mut self,
+        call: Call<Self::MethodCall<'_>>,
+    ) -> MethodReply<Self::ReplyParams<'ser>, Self::ReplyStream, Self::ReplyError<'ser>> {
+        match call.method() {
+            Method::Ping => MethodReply::Single(Some(Pong { pong: true })),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method")]
+enum Method {
+    #[serde(rename = "org.example.ping.Ping")]
+    Ping,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Pong {
+    pong: bool,
+}
+
+#[derive(Debug, ReplyError)]
+#[zlink(interface = "org.example.ping")]
+enum PingError {}
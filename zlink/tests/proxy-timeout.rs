@@ -0,0 +1,59 @@
+#![cfg(feature = "proxy")]
+
+use futures_util::{pin_mut, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::{io::duplex, time::Duration};
+use zlink::{
+    proxy, varlink_service::Proxy as _, AsyncIoSocket, Connection, Proxy as _, ReplyError,
+};
+
+#[test_log::test(tokio::test(start_paused = true))]
+async fn proxy_call_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    // The server end is kept around but never replies.
+    let (client, _server) = duplex(1024);
+    let mut conn = Connection::new(AsyncIoSocket::new(client));
+
+    let res = conn
+        .call_timeout(Duration::from_secs(1), |conn| conn.get_info())
+        .await;
+    assert!(matches!(res, Err(zlink::Error::Timeout)));
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test(start_paused = true))]
+async fn proxy_call_more_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    // A connection that timed out may still receive the late reply, so use a fresh one.
+    let (client, _server) = duplex(1024);
+    let mut conn = Connection::new(AsyncIoSocket::new(client));
+
+    let replies = conn
+        .call_more_timeout(Duration::from_secs(1), |conn| conn.monitor())
+        .await?;
+    pin_mut!(replies);
+    assert!(matches!(
+        replies.next().await,
+        Some(Err(zlink::Error::Timeout))
+    ));
+    // The stream ends after the timeout.
+    assert!(replies.next().await.is_none());
+
+    Ok(())
+}
+
+#[proxy("org.example.silent")]
+trait SilentProxy {
+    #[zlink(more)]
+    async fn monitor(
+        &mut self,
+    ) -> zlink::Result<impl Stream<Item = zlink::Result<Result<Event, SilentError>>>>;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Event {
+    id: u32,
+}
+
+#[derive(Debug, ReplyError)]
+#[zlink(interface = "org.example.silent")]
+enum SilentError {}
@@ -0,0 +1,153 @@
+use std::{cell::Cell, rc::Rc};
+
+use futures_util::stream::Empty;
+use serde::{Deserialize, Serialize};
+use tokio::select;
+use zlink::{
+    service::MethodReply,
+    unix::{bind, connect},
+    varlink_service, Call, Interceptor, Reply, ReplyError, Service,
+};
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn interceptor_observes_replies() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let socket_path = dir.path().join("zlink-test-reply-interceptor.sock");
+
+    let counter = Counter::default();
+    let listener = bind(&socket_path)?;
+    let server = zlink::Server::new(listener, Counting).with_interceptor(counter.clone());
+    select! {
+        res = server.run() => res?,
+        res = run_client(&socket_path) => res?,
+    }
+
+    assert_eq!(counter.replies.get(), 2);
+    assert_eq!(counter.errors.get(), 1);
+    assert_eq!(counter.server_errors.get(), 1);
+
+    Ok(())
+}
+
+async fn run_client(socket_path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = connect(socket_path).await?;
+
+    // The interceptor stamps each reply with the number of replies sent so far.
+    for expected_id in 1..=2 {
+        let reply = conn
+            .call_method::<_, Count, CountError>(&Call::new(Method::Count))
+            .await?
+            .unwrap();
+        assert_eq!(reply.id(), Some(expected_id));
+    }
+
+    let error = conn
+        .call_method::<_, Count, CountError>(&Call::new(Method::Fail))
+        .await?
+        .unwrap_err();
+    assert_eq!(error, CountError::Failed);
+
+    // Calls for another interface never reach the service but their error reply is intercepted.
+    let res = conn
+        .call_method::<_, Count, varlink_service::Error>(&Call::new(OtherMethod::Count))
+        .await;
+    match res {
+        Err(zlink::Error::VarlinkService(varlink_service::Error::MethodNotFound { method })) => {
+            assert_eq!(method.as_str(), "org.example.intercepted.Count");
+        }
+        _ => panic!("Expected `MethodNotFound` error, got: {res:?}"),
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Default, Clone)]
+struct Counter {
+    replies: Rc<Cell<u64>>,
+    errors: Rc<Cell<u64>>,
+    server_errors: Rc<Cell<u64>>,
+}
+
+impl Interceptor<Counting> for Counter {
+    fn reply<'ser>(
+        &mut self,
+        reply: &mut Reply<<Counting as Service>::ReplyParams<'ser>>,
+        _connection_id: usize,
+    ) where
+        Counting: 'ser,
+    {
+        self.replies.set(self.replies.get() + 1);
+        *reply = reply.clone().set_id(Some(self.replies.get()));
+    }
+
+    fn error<'ser>(
+        &mut self,
+        _error: &mut <Counting as Service>::ReplyError<'ser>,
+        _connection_id: usize,
+    ) where
+        Counting: 'ser,
+    {
+        self.errors.set(self.errors.get() + 1);
+    }
+
+    fn server_error(&mut self, error: &mut varlink_service::Error, _connection_id: usize) {
+        self.server_errors.set(self.server_errors.get() + 1);
+        // Rewrite the method name, to check that the modified error is sent.
+        if let varlink_service::Error::MethodNotFound { method } = error {
+            *method = method
+                .as_str()
+                .replace("other", "intercepted")
+                .parse()
+                .unwrap();
+        }
+    }
+}
+
+struct Counting;
+
+impl Service for Counting {
+    const INTERFACE: Option<&'static str> = Some("org.example.count");
+
+    type MethodCall<'de> = Method;
+    type ReplyParams<'ser> = Count;
+    type ReplyStream = Empty<Reply<Count>>;
+    type ReplyStreamParams = Count;
+    type ReplyError<'ser> = CountError;
+
+    async fn handle<'ser>(
+        &'ser mut self,
+        call: Call<Self::MethodCall<'_>>,
+    ) -> MethodReply<Self::ReplyParams<'ser>, Self::ReplyStream, Self::ReplyError<'ser>> {
+        match call.method() {
+            Method::Count => MethodReply::Single(Some(Count { counted: true })),
+            Method::Fail => MethodReply::Error(CountError::Failed),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method")]
+enum Method {
+    #[serde(rename = "org.example.count.Count")]
+    Count,
+    #[serde(rename = "org.example.count.Fail")]
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "method")]
+enum OtherMethod {
+    #[serde(rename = "org.example.other.Count")]
+    Count,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Count {
+    counted: bool,
+}
+
+#[derive(Debug, PartialEq, ReplyError)]
+#[zlink(interface = "org.example.count")]
+enum CountError {
+    Failed,
+}
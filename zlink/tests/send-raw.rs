@@ -0,0 +1,28 @@
+use tokio::io::{duplex, AsyncReadExt};
+use zlink::{AsyncIoSocket, Connection};
+
+#[test_log::test(tokio::test)]
+async fn send_raw() -> Result<(), Box<dyn std::error::Error>> {
+    let (client, mut server) = duplex(1024);
+    let mut conn = Connection::new(AsyncIoSocket::new(client));
+
+    let message = br#"{"method":"org.example.ping.Ping"}"#;
+    conn.send_raw(message).await?;
+    // An already terminated message isn't terminated twice.
+    conn.send_raw(&[&message[..], b"\0"].concat()).await?;
+
+    let mut received = vec![0; (message.len() + 1) * 2];
+    server.read_exact(&mut received).await?;
+    for sent in received.chunks(message.len() + 1) {
+        assert_eq!(&sent[..message.len()], message);
+        assert_eq!(sent[message.len()], b'\0');
+    }
+
+    // Interior NUL bytes would break the framing.
+    assert!(matches!(
+        conn.send_raw(b"{}\0{}").await,
+        Err(zlink::Error::InteriorNul)
+    ));
+
+    Ok(())
+}
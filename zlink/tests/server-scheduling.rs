@@ -0,0 +1,146 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use common::DuplexListener;
+use futures_util::{pin_mut, stream::StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{duplex, DuplexStream},
+    select,
+};
+use zlink::{
+    service::MethodReply, AsyncIoSocket, Call, Connection, Reply, ReplyError, Scheduling, Server,
+    Service,
+};
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn no_starvation_under_stream_load() -> Result<(), Box<dyn std::error::Error>> {
+    for scheduling in [Scheduling::RoundRobin, Scheduling::Priority] {
+        STREAMED.store(0, Ordering::SeqCst);
+        let (monitor, monitor_server) = duplex(1024);
+        let (pinger, pinger_server) = duplex(1024);
+        let listener = DuplexListener::new([monitor_server, pinger_server]);
+        let server = Server::new(listener, Ticker).with_scheduling(scheduling);
+        select! {
+            res = server.run() => res?,
+            res = run_clients(monitor, pinger, scheduling) => res?,
+        }
+
+        // With round-robin scheduling, the always ready reply stream got its turn after each
+        // method call.
+        if scheduling == Scheduling::RoundRobin {
+            assert!(STREAMED.load(Ordering::SeqCst) >= PINGS);
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_clients(
+    monitor: DuplexStream,
+    pinger: DuplexStream,
+    scheduling: Scheduling,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut monitor = Connection::new(AsyncIoSocket::new(monitor));
+    monitor
+        .send_call(&Call::new(Method::Monitor).set_more(true))
+        .await?;
+    // Wait for the stream to be set up before flooding the server with method calls.
+    monitor.receive_reply::<Count, TickerError>().await??;
+
+    let mut pinger = Connection::new(AsyncIoSocket::new(pinger));
+    select! {
+        res = drain(&mut monitor) => res,
+        res = ping(&mut pinger, scheduling) => res,
+    }
+}
+
+async fn drain(
+    conn: &mut Connection<AsyncIoSocket<DuplexStream>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        let reply = conn.receive_reply::<Count, TickerError>().await??;
+        assert_eq!(reply.continues(), Some(true));
+    }
+}
+
+async fn ping(
+    conn: &mut Connection<AsyncIoSocket<DuplexStream>>,
+    scheduling: Scheduling,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Send all the calls at once so there is always a method call ready to be handled.
+    let call = Call::new(Method::Ping);
+    let mut chain = conn.chain_call::<_, Count, TickerError>(&call)?;
+    for _ in 1..PINGS {
+        chain = chain.append(&call)?;
+    }
+    let replies = chain.send().await?;
+    pin_mut!(replies);
+
+    // Each reply carries the number of stream replies sent when the call was handled.
+    let mut counts = Vec::new();
+    while let Some(reply) = replies.next().await {
+        counts.push(reply??.parameters().unwrap().count);
+    }
+    assert_eq!(counts.len(), PINGS);
+    if scheduling == Scheduling::RoundRobin {
+        // A stream reply was sent in between each two method calls.
+        assert!(counts.windows(2).all(|w| w[0] < w[1]), "{counts:?}");
+    } else {
+        // All the method calls were handled before any other stream reply was sent.
+        assert!(counts.iter().all(|c| *c == counts[0]), "{counts:?}");
+    }
+
+    Ok(())
+}
+
+const PINGS: usize = 20;
+
+static STREAMED: AtomicUsize = AtomicUsize::new(0);
+
+struct Ticker;
+
+impl Service for Ticker {
+    type MethodCall<'de> = Method;
+    type ReplyParams<'ser> = Count;
+    type ReplyStream = futures_util::stream::RepeatWith<fn() -> Reply<Count>>;
+    type ReplyStreamParams = Count;
+    type ReplyError<'ser> = TickerError;
+
+    async fn handle<'ser>(
+        &'ser mut self,
+        call: Call<Self::MethodCall<'_>>,
+    ) -> MethodReply<Self::ReplyParams<'ser>, Self::ReplyStream, Self::ReplyError<'ser>> {
+        match call.method() {
+            Method::Ping => MethodReply::Single(Some(Count {
+                count: STREAMED.load(Ordering::SeqCst),
+            })),
+            Method::Monitor => MethodReply::Multi(futures_util::stream::repeat_with(tick)),
+        }
+    }
+}
+
+fn tick() -> Reply<Count> {
+    let count = STREAMED.fetch_add(1, Ordering::SeqCst) + 1;
+
+    Reply::new(Some(Count { count })).set_continues(Some(true))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method")]
+enum Method {
+    #[serde(rename = "org.example.ticker.Ping")]
+    Ping,
+    #[serde(rename = "org.example.ticker.Monitor")]
+    Monitor,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Count {
+    count: usize,
+}
+
+#[derive(Debug, ReplyError)]
+#[zlink(interface = "org.example.ticker")]
+enum TickerError {}
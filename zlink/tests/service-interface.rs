@@ -0,0 +1,90 @@
+use futures_util::stream::Empty;
+use serde::{Deserialize, Serialize};
+use tokio::select;
+use zlink::{
+    service::MethodReply,
+    unix::{bind, connect},
+    varlink_service, Call, Reply, Service,
+};
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn wrong_interface_call() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = tempfile::tempdir()?;
+    let socket_path = dir.path().join("zlink-test-service-interface.sock");
+
+    let listener = bind(&socket_path)?;
+    let server = zlink::Server::new(listener, Pinger);
+    select! {
+        res = server.run() => res?,
+        res = run_client(&socket_path) => res?,
+    }
+
+    Ok(())
+}
+
+async fn run_client(socket_path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = connect(socket_path).await?;
+
+    // A call for another interface is rejected by the server with the standard error.
+    let call = Call::new(ClientMethod::Other);
+    let res = conn
+        .call_method::<_, Pong, varlink_service::Error>(&call)
+        .await;
+    match res {
+        Err(zlink::Error::VarlinkService(varlink_service::Error::MethodNotFound { method })) => {
+            assert_eq!(method.as_str(), "org.example.other.Ping");
+        }
+        _ => panic!("Expected `MethodNotFound` error, got: {res:?}"),
+    }
+
+    // The connection remains usable afterwards.
+    let call = Call::new(ClientMethod::Ping);
+    let pong = conn
+        .call_method::<_, Pong, varlink_service::Error>(&call)
+        .await??;
+    assert!(pong.into_parameters().unwrap().pong);
+
+    Ok(())
+}
+
+struct Pinger;
+
+impl Service for Pinger {
+    const INTERFACE: Option<&'static str> = Some("org.example.ping");
+
+    type MethodCall<'de> = Method;
+    type ReplyParams<'ser> = Pong;
+    type ReplyStream = Empty<Reply<Pong>>;
+    type ReplyStreamParams = Pong;
+    type ReplyError<'ser> = varlink_service::Error;
+
+    async fn handle<'ser>(
+        &'ser mut self,
+        call: Call<Self::MethodCall<'_>>,
+    ) -> MethodReply<Self::ReplyParams<'ser>, Self::ReplyStream, Self::ReplyError<'ser>> {
+        match call.method() {
+            Method::Ping => MethodReply::Single(Some(Pong { pong: true })),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "method")]
+enum Method {
+    #[serde(rename = "org.example.ping.Ping")]
+    Ping,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "method")]
+enum ClientMethod {
+    #[serde(rename = "org.example.ping.Ping")]
+    Ping,
+    #[serde(rename = "org.example.other.Ping")]
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Pong {
+    pong: bool,
+}
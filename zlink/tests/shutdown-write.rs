@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+use zlink::{AsyncIoSocket, Call, Connection, ReplyError};
+
+#[test_log::test(tokio::test)]
+async fn shutdown_write() -> Result<(), Box<dyn std::error::Error>> {
+    let (client, mut server) = duplex(1024);
+    let mut conn = Connection::new(AsyncIoSocket::new(client));
+    conn.enqueue_call(&Call::new(Method::Ping))?;
+    conn.shutdown_write().await?;
+
+    // The peer receives the enqueued call, followed by EOF.
+    let mut received = Vec::new();
+    server.read_to_end(&mut received).await?;
+    assert_eq!(received, b"{\"method\":\"org.example.ping.Ping\"}\0");
+
+    // The read side is still usable.
+    server
+        .write_all(b"{\"parameters\":{\"pong\":true}}\0")
+        .await?;
+    let reply = conn.receive_reply::<Pong, PingError>().await?.unwrap();
+    assert!(reply.parameters().unwrap().pong);
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method")]
+enum Method {
+    #[serde(rename = "org.example.ping.Ping")]
+    Ping,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pong {
+    pong: bool,
+}
+
+#[derive(Debug, ReplyError)]
+#[zlink(interface = "org.example.ping")]
+enum PingError {}
@@ -0,0 +1,98 @@
+mod common;
+
+use common::DuplexListener;
+use futures_util::{pin_mut, stream::StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{duplex, DuplexStream},
+    select,
+};
+use zlink::{service::MethodReply, AsyncIoSocket, Call, Connection, Reply, ReplyError, Service};
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn snapshot_then_updates() -> Result<(), Box<dyn std::error::Error>> {
+    let (client, server) = duplex(1024);
+    let listener = DuplexListener::new([server]);
+    let server = zlink::Server::new(listener, Temperature);
+    select! {
+        res = server.run() => res?,
+        res = run_client(client) => res?,
+    }
+
+    Ok(())
+}
+
+async fn run_client(stream: DuplexStream) -> Result<(), Box<dyn std::error::Error>> {
+    let mut conn = Connection::new(AsyncIoSocket::new(stream));
+
+    let call = Call::new(Method::Monitor).set_more(true);
+    let replies = conn
+        .chain_call::<_, Reading, TemperatureError>(&call)?
+        .send()
+        .await?;
+    pin_mut!(replies);
+
+    let mut readings = Vec::new();
+    while let Some(reply) = replies.next().await {
+        let reply = reply?.unwrap();
+        readings.push((reply.parameters().unwrap().celsius, reply.continues()));
+    }
+    // The snapshot comes first, followed by the updates.
+    assert_eq!(
+        readings,
+        [
+            (20, Some(true)),
+            (21, Some(true)),
+            (22, Some(true)),
+            (23, Some(false)),
+        ]
+    );
+
+    Ok(())
+}
+
+struct Temperature;
+
+impl Service for Temperature {
+    type MethodCall<'de> = Method;
+    type ReplyParams<'ser> = Reading;
+    type ReplyStream = futures_util::stream::Iter<std::vec::IntoIter<Reply<Reading>>>;
+    type ReplyStreamParams = Reading;
+    type ReplyError<'ser> = TemperatureError;
+
+    async fn handle<'ser>(
+        &'ser mut self,
+        call: Call<Self::MethodCall<'_>>,
+    ) -> MethodReply<Self::ReplyParams<'ser>, Self::ReplyStream, Self::ReplyError<'ser>> {
+        match call.method() {
+            Method::Monitor => {
+                let updates = (21..=23)
+                    .map(|celsius| {
+                        Reply::new(Some(Reading { celsius })).set_continues(Some(celsius != 23))
+                    })
+                    .collect::<Vec<_>>();
+
+                MethodReply::SnapshotThenStream(
+                    Reading { celsius: 20 },
+                    futures_util::stream::iter(updates),
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method")]
+enum Method {
+    #[serde(rename = "org.example.temperature.Monitor")]
+    Monitor,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Reading {
+    celsius: i64,
+}
+
+#[derive(Debug, ReplyError)]
+#[zlink(interface = "org.example.temperature")]
+enum TemperatureError {}
@@ -0,0 +1,137 @@
+use std::{error::Error, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpStream;
+use zlink::{
+    tls::{
+        bind, bind_with_backlog, connect,
+        rustls::{
+            pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName},
+            ClientConfig, RootCertStore, ServerConfig,
+        },
+        ServerConnection,
+    },
+    Call, Listener, LocalAddr, Reply, ReplyError,
+};
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn tls_round_trip() -> Result<(), Box<dyn Error>> {
+    let (cert, server_config) = server_config()?;
+    let mut listener = bind("127.0.0.1:0", Arc::new(server_config)).await?;
+    let addr = listener.local_addr()?;
+
+    let server = tokio::spawn(async move {
+        // A client that never performs the handshake must not hold up accepting others.
+        let _stalled = listener.accept().await.unwrap();
+        let mut conn = listener.accept().await.unwrap();
+        echo(&mut conn).await;
+    });
+
+    let _stalled = TcpStream::connect(addr).await?;
+    let server_name = ServerName::try_from("localhost")?;
+    let mut conn = connect(addr, server_name, client_config(cert)?).await?;
+    assert_eq!(call_echo(&mut conn, 42).await?, 42);
+
+    server.await?;
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn tls_bind_with_backlog() -> Result<(), Box<dyn Error>> {
+    const CLIENTS: u32 = 4;
+
+    let (cert, server_config) = server_config()?;
+    let server_config = Arc::new(server_config);
+    let mut listener = bind_with_backlog("127.0.0.1:0", CLIENTS, server_config.clone()).await?;
+    let addr = listener.local_addr()?;
+
+    // The address is taken now.
+    assert!(bind_with_backlog(addr, CLIENTS, server_config)
+        .await
+        .is_err());
+
+    // The clients connect at once, waiting in the backlog until they're accepted.
+    let client_config = client_config(cert)?;
+    let mut clients = Vec::new();
+    for value in 0..CLIENTS {
+        let server_name = ServerName::try_from("localhost")?;
+        let client_config = client_config.clone();
+        clients.push(tokio::spawn(async move {
+            let mut conn = connect(addr, server_name, client_config).await.unwrap();
+            let echoed = call_echo(&mut conn, value).await.unwrap();
+            assert_eq!(echoed, value);
+        }));
+    }
+
+    for _ in 0..CLIENTS {
+        let mut conn = listener.accept().await?;
+        tokio::spawn(async move { echo(&mut conn).await });
+    }
+    for client in clients {
+        client.await?;
+    }
+
+    Ok(())
+}
+
+/// Generate a self-signed certificate for `localhost` and a server configuration using it.
+fn server_config() -> Result<(CertificateDer<'static>, ServerConfig), Box<dyn Error>> {
+    let certified_key = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])?;
+    let cert = certified_key.cert.der().clone();
+    let key = PrivateKeyDer::from(PrivatePkcs8KeyDer::from(
+        certified_key.key_pair.serialize_der(),
+    ));
+
+    let server_config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert.clone()], key)?;
+
+    Ok((cert, server_config))
+}
+
+/// A client configuration trusting `cert`.
+fn client_config(cert: CertificateDer<'static>) -> Result<Arc<ClientConfig>, Box<dyn Error>> {
+    let mut roots = RootCertStore::empty();
+    roots.add(cert)?;
+    let client_config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    Ok(Arc::new(client_config))
+}
+
+/// Reply to an `Echo` call on `conn`.
+async fn echo(conn: &mut ServerConnection) {
+    let call = conn.receive_call::<Method>().await.unwrap();
+    let Method::Echo { value } = call.method();
+    let reply = Reply::new(Some(Echoed { value: *value }));
+    conn.send_reply(&reply).await.unwrap();
+}
+
+/// Call `Echo` with `value` on `conn`, returning the echoed value.
+async fn call_echo(conn: &mut zlink::tls::Connection, value: u32) -> zlink::Result<u32> {
+    let call = Call::new(Method::Echo { value });
+    let reply = conn
+        .call_method::<_, Echoed, EchoError>(&call)
+        .await?
+        .unwrap();
+
+    Ok(reply.parameters().unwrap().value)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method", content = "parameters")]
+enum Method {
+    #[serde(rename = "org.example.echo.Echo")]
+    Echo { value: u32 },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Echoed {
+    value: u32,
+}
+
+#[derive(Debug, ReplyError)]
+#[zlink(interface = "org.example.echo")]
+enum EchoError {}
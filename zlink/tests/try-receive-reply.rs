@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use tokio::io::{duplex, AsyncWriteExt};
+use zlink::{AsyncIoSocket, Call, Connection, ReplyError};
+
+#[test_log::test(tokio::test)]
+async fn try_receive_reply() -> Result<(), Box<dyn std::error::Error>> {
+    let (client, mut server) = duplex(1024);
+    let mut conn = Connection::new(AsyncIoSocket::new(client));
+    conn.send_call(&Call::new(Method::Ping)).await?;
+
+    // Nothing has been sent by the server yet.
+    assert!(conn.try_receive_reply::<Pong, PingError>()?.is_none());
+
+    server
+        .write_all(b"{\"parameters\":{\"pong\":true}}\0")
+        .await?;
+    let reply = conn
+        .try_receive_reply::<Pong, PingError>()?
+        .unwrap()
+        .unwrap();
+    assert!(reply.parameters().unwrap().pong);
+
+    // And nothing more after that.
+    assert!(conn.try_receive_reply::<Pong, PingError>()?.is_none());
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method")]
+enum Method {
+    #[serde(rename = "org.example.ping.Ping")]
+    Ping,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pong {
+    pong: bool,
+}
+
+#[derive(Debug, ReplyError)]
+#[zlink(interface = "org.example.ping")]
+enum PingError {}